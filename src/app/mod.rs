@@ -1,7 +1,13 @@
-use crate::components::{OutputPanel, SearchPanel, SettingsPanel, StatusBar};
-use crate::models::{AppState, PlatformSearchResults, SearchContext};
-use crate::services::SettingsService;
+use crate::components::{
+    HistoryPanel, Onboarding, OutputPanel, SearchPanel, SettingsPanel, StatusBar, Toast,
+    ToastStack, WhatsNew,
+};
+use crate::models::{AppState, Platform, PlatformSearchResults, SearchContext};
+use crate::services::{CredentialStatus, DownloadControl, HealthService, SettingsService};
+use crate::utils::is_newer_version;
 use dioxus::prelude::*;
+use dioxus_desktop::tao::event::{Event, WindowEvent};
+use dioxus_desktop::use_wry_event_handler;
 
 #[derive(Props, PartialEq)]
 pub struct AppProps {}
@@ -14,13 +20,67 @@ pub fn App(cx: Scope<AppProps>) -> Element {
     let search_context = use_state(cx, || None::<SearchContext>);
     let is_searching = use_state(cx, || false);
     let status_message = use_state(cx, || String::new());
+    let toasts = use_state(cx, Vec::<Toast>::new);
+    let download_control = use_state(cx, DownloadControl::default);
+    let settings_loaded = use_state(cx, || false);
+    let credential_health = use_state(cx, Vec::<(Platform, CredentialStatus)>::new);
+    let show_whats_new = use_state(cx, || false);
+    let settings_section = use_state(cx, || "appearance");
+    let settings_focus = use_state(cx, || None::<Platform>);
+
+    // Cancel in-flight downloads and record progress when the window closes
+    let shutdown_control = download_control.get().clone();
+    use_wry_event_handler(cx, move |event, _| {
+        if let Event::WindowEvent {
+            event: WindowEvent::CloseRequested,
+            ..
+        } = event
+        {
+            if shutdown_control.is_active() {
+                log::warn!("Window closed during a download; cancelling in-flight transfers");
+                shutdown_control.shutdown();
+            }
+        }
+    });
 
     // Load settings on startup
     use_effect(cx, (), |_| {
-        to_owned![app_state];
+        to_owned![
+            app_state,
+            settings_loaded,
+            credential_health,
+            show_whats_new
+        ];
         async move {
             if let Ok(settings) = SettingsService::load_settings().await {
-                app_state.set(AppState { settings });
+                SettingsService::apply_log_level(settings.log_level);
+                // The setup guide covers a fresh install; updates get the notes
+                if !settings.first_run
+                    && is_newer_version(env!("CARGO_PKG_VERSION"), &settings.last_seen_version)
+                {
+                    log::info!(
+                        "Updated from version '{}' to {}",
+                        settings.last_seen_version,
+                        env!("CARGO_PKG_VERSION")
+                    );
+                    show_whats_new.set(true);
+                }
+                app_state.set(AppState {
+                    settings: settings.clone(),
+                });
+                settings_loaded.set(true);
+
+                if settings.verify_credentials_on_startup {
+                    credential_health.set(
+                        Platform::ALL
+                            .iter()
+                            .map(|platform| (*platform, CredentialStatus::Checking))
+                            .collect(),
+                    );
+                    credential_health.set(HealthService::check_all(&settings).await);
+                }
+            } else {
+                settings_loaded.set(true);
             }
         }
     });
@@ -36,6 +96,40 @@ pub fn App(cx: Scope<AppProps>) -> Element {
             header {
                 class: "app-header",
                 h1 { class: "app-title", "Fedi Sleuth" }
+                // Which platforms are ready to search; each opens its settings
+                div {
+                    class: "platform-badges",
+                    Platform::ALL.into_iter().map(|platform| {
+                        let api = &app_state.settings.api;
+                        let (state, detail) = if !api.is_enabled(platform) {
+                            ("off", "disabled")
+                        } else if api.is_signed_in(platform) {
+                            ("ready", "signed in")
+                        } else {
+                            ("signed-out", "enabled, not signed in")
+                        };
+                        // The startup credential check, when it ran, knows better
+                        let (health, detail) = match credential_health.iter().find(|(checked, _)| *checked == platform) {
+                            Some((_, CredentialStatus::Checking)) => (" health-dot checking", "checking…".to_string()),
+                            Some((_, CredentialStatus::Valid)) => (" health-dot valid", "signed in".to_string()),
+                            Some((_, CredentialStatus::Invalid(reason))) => (" health-dot invalid", reason.clone()),
+                            None => ("", detail.to_string()),
+                        };
+                        rsx! {
+                            button {
+                                key: "{platform.name()}",
+                                class: "platform-badge {state}{health}",
+                                title: "{platform.name()}: {detail}. Click for its settings.",
+                                onclick: move |_| {
+                                    current_view.set("settings");
+                                    settings_section.set("api");
+                                    settings_focus.set(Some(platform));
+                                },
+                                "{platform.emoji()}"
+                            }
+                        }
+                    })
+                }
                 div {
                     class: "nav-buttons",
                     button {
@@ -43,6 +137,11 @@ pub fn App(cx: Scope<AppProps>) -> Element {
                         onclick: move |_| current_view.set("search"),
                         "🔍 Search"
                     }
+                    button {
+                        class: if **current_view == "history" { "nav-btn active" } else { "nav-btn" },
+                        onclick: move |_| current_view.set("history"),
+                        "🕘 History"
+                    }
                     button {
                         class: if **current_view == "settings" { "nav-btn active" } else { "nav-btn" },
                         onclick: move |_| current_view.set("settings"),
@@ -66,6 +165,7 @@ pub fn App(cx: Scope<AppProps>) -> Element {
                                     search_context: search_context.clone(),
                                     is_searching: is_searching.clone(),
                                     status_message: status_message.clone(),
+                                    toasts: toasts.clone(),
                                 }
                             }
                             div {
@@ -75,19 +175,52 @@ pub fn App(cx: Scope<AppProps>) -> Element {
                                     app_state: app_state.clone(),
                                     search_context: search_context.clone(),
                                     status_message: status_message.clone(),
+                                    download_control: download_control.clone(),
+                                    toasts: toasts.clone(),
                                 }
                             }
                         }
                     },
+                    "history" => rsx! {
+                        HistoryPanel {
+                            toasts: toasts.clone(),
+                        }
+                    },
                     "settings" => rsx! {
                         SettingsPanel {
                             app_state: app_state.clone(),
+                            section: settings_section.clone(),
+                            focus: settings_focus.clone(),
                         }
                     },
                     _ => rsx! { div { "Unknown view" } }
                 }
             }
 
+            // First-run setup guide, shown once settings have loaded
+            if **settings_loaded && app_state.settings.first_run {
+                rsx! {
+                    Onboarding {
+                        app_state: app_state.clone(),
+                    }
+                }
+            }
+
+            // Changes since the version last run, once after an update
+            if **show_whats_new && !app_state.settings.first_run {
+                rsx! {
+                    WhatsNew {
+                        app_state: app_state.clone(),
+                        visible: show_whats_new.clone(),
+                    }
+                }
+            }
+
+            // Search and download outcomes
+            ToastStack {
+                toasts: toasts.clone(),
+            }
+
             // Status bar
             StatusBar {
                 message: (**status_message).clone(),
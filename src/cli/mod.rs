@@ -0,0 +1,251 @@
+// Headless search mode
+//
+// `pixelfed-rust --cli --query <q> [--type user|hashtag|...] [--platform
+// pixelfed,mastodon,bluesky] [--days N] [--download] [--out DIR]` runs the
+// same platform services and DownloadService as the GUI, using the saved
+// settings and credentials, and prints a JSON summary to stdout.
+
+use anyhow::Result;
+use serde_json::{json, Value};
+
+use crate::models::{Platform, PlatformSearchResults, SearchContext, SearchType};
+use crate::services::http_util::measure_requests;
+use crate::services::{platform_service, DownloadControl, DownloadService, SettingsService};
+
+const USAGE: &str = "Usage: pixelfed-rust --cli --query <query> [options]
+
+Options:
+  --type <type>         user (default), hashtag, bookmarks, favourites, post, feed,
+                        trending
+  --platform <list>     comma-separated: pixelfed, mastodon, bluesky
+                        (default: every platform enabled in settings)
+  --days <n>            days to search back (default 180)
+  --download            download the media of the results
+  --out <dir>           download folder (default: the one in settings)";
+
+struct CliArgs {
+    query: String,
+    search_type: SearchType,
+    platforms: Option<Vec<Platform>>,
+    days: u32,
+    download: bool,
+    out: Option<String>,
+}
+
+impl CliArgs {
+    fn parse(args: &[String]) -> Result<Self> {
+        let mut parsed = CliArgs {
+            query: String::new(),
+            search_type: SearchType::User,
+            platforms: None,
+            days: 180,
+            download: false,
+            out: None,
+        };
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            let mut value = |name: &str| {
+                iter.next()
+                    .cloned()
+                    .ok_or_else(|| anyhow::anyhow!("{} needs a value", name))
+            };
+            match arg.as_str() {
+                "--cli" => {}
+                "--query" => parsed.query = value("--query")?,
+                "--type" => parsed.search_type = parse_search_type(&value("--type")?)?,
+                "--platform" => {
+                    parsed.platforms = Some(
+                        value("--platform")?
+                            .split(',')
+                            .map(parse_platform)
+                            .collect::<Result<_>>()?,
+                    )
+                }
+                "--days" => {
+                    parsed.days = value("--days")?
+                        .parse::<u32>()
+                        .map_err(|_| anyhow::anyhow!("--days must be a number"))?
+                        .clamp(1, 3650)
+                }
+                "--download" => parsed.download = true,
+                "--out" => parsed.out = Some(value("--out")?),
+                other => return Err(anyhow::anyhow!("Unknown argument: {}", other)),
+            }
+        }
+
+        if let Some(problem) = parsed.search_type.query_problem(&parsed.query) {
+            return Err(anyhow::anyhow!("--query: {}", problem));
+        }
+        Ok(parsed)
+    }
+}
+
+fn parse_search_type(value: &str) -> Result<SearchType> {
+    match value.to_lowercase().as_str() {
+        "user" => Ok(SearchType::User),
+        "hashtag" | "tag" => Ok(SearchType::Hashtag),
+        "bookmarks" => Ok(SearchType::Bookmarks),
+        "favourites" | "favorites" => Ok(SearchType::Favourites),
+        "post" | "url" => Ok(SearchType::PostUrl),
+        "feed" => Ok(SearchType::Feed),
+        "trending" => Ok(SearchType::Trending),
+        other => Err(anyhow::anyhow!("Unknown search type: {}", other)),
+    }
+}
+
+fn parse_platform(value: &str) -> Result<Platform> {
+    Platform::ALL
+        .into_iter()
+        .find(|platform| platform.name().eq_ignore_ascii_case(value.trim()))
+        .ok_or_else(|| anyhow::anyhow!("Unknown platform: {}", value))
+}
+
+/// True when the process was started in headless mode
+pub fn requested(args: &[String]) -> bool {
+    args.iter().any(|arg| arg == "--cli")
+}
+
+/// Run a headless search and return the process exit code
+pub fn run(args: &[String]) -> i32 {
+    let parsed = match CliArgs::parse(args) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, USAGE);
+            return 2;
+        }
+    };
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(err) => {
+            eprintln!("Failed to start async runtime: {}", err);
+            return 1;
+        }
+    };
+
+    match runtime.block_on(search(parsed)) {
+        Ok(summary) => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&summary).unwrap_or_default()
+            );
+            0
+        }
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            1
+        }
+    }
+}
+
+async fn search(args: CliArgs) -> Result<Value> {
+    let mut settings = SettingsService::load_settings().await?;
+    if let Some(out) = &args.out {
+        settings.download.base_path = out.clone();
+    }
+
+    let platforms = args.platforms.clone().unwrap_or_else(|| {
+        Platform::ALL
+            .into_iter()
+            .filter(|platform| settings.api.is_enabled(*platform))
+            .collect()
+    });
+    if platforms.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No platforms enabled. Enable one in the app's settings or pass --platform."
+        ));
+    }
+
+    let mut groups: Vec<PlatformSearchResults> = Vec::new();
+    for platform in platforms {
+        let service = platform_service(platform, &settings, None);
+        let label = platform.name().to_string();
+
+        if !service.is_enabled() {
+            groups.push(PlatformSearchResults::error(
+                platform,
+                label,
+                "Disabled in settings".to_string(),
+            ));
+            continue;
+        }
+
+        log::info!("Searching {} for {}", platform.name(), args.query);
+        let (outcome, timing) = measure_requests(service.search(
+            args.query.clone(),
+            args.search_type.clone(),
+            args.days,
+        ))
+        .await;
+        match outcome {
+            Ok(outcome) => {
+                let mut results = outcome.results;
+                let mut stats = outcome.stats;
+                timing.record(&mut stats);
+                stats.matched = results.len();
+                results.retain(|result| !settings.search.is_muted(result));
+                stats.kept = results.len();
+                results.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+                let mut group = PlatformSearchResults::success(platform, label, results);
+                group.partial_error = outcome.stopped_early;
+                group.stats = Some(stats);
+                group.notice = outcome.notice;
+                groups.push(group);
+            }
+            Err(err) => groups.push(PlatformSearchResults::error(
+                platform,
+                label,
+                err.to_string(),
+            )),
+        }
+    }
+
+    let platform_summaries: Vec<Value> = groups
+        .iter()
+        .map(|group| {
+            json!({
+                "platform": group.platform.name(),
+                "posts": group.results.len(),
+                "media": group.results.iter().map(|result| result.media.len() as u64).sum::<u64>(),
+                "partial": group.partial_error,
+                "error": group.error,
+                "notice": group.notice,
+                "stats": group.stats.map(|stats| json!({
+                    "pages": stats.pages,
+                    "seen": stats.seen,
+                    "matched": stats.matched,
+                    "kept": stats.kept,
+                    "requests": stats.requests,
+                    "request_ms": stats.request_ms,
+                    "elapsed_ms": stats.elapsed_ms,
+                })),
+            })
+        })
+        .collect();
+
+    let download = if args.download {
+        let context = SearchContext::new(args.query.clone(), args.search_type.clone(), args.days);
+        let summary = DownloadService::new(settings.clone())
+            .download_all(Some(context), groups, &DownloadControl::default(), |_| {})
+            .await?;
+        json!({
+            "root": summary.root.display().to_string(),
+            "downloaded": summary.downloaded,
+            "skipped": summary.skipped,
+            "duplicates": summary.duplicates,
+            "failed": summary.failed,
+            "bytes": summary.bytes,
+        })
+    } else {
+        Value::Null
+    };
+
+    Ok(json!({
+        "query": args.query,
+        "type": args.search_type.slug(),
+        "days": args.days,
+        "platforms": platform_summaries,
+        "download": download,
+    }))
+}
@@ -0,0 +1,105 @@
+use crate::components::{push_toast, Toast, ToastLevel};
+use crate::services::{DownloadRecord, HistoryService};
+use crate::utils::{format_bytes, open_folder};
+use dioxus::prelude::*;
+
+#[derive(Props, PartialEq)]
+pub struct HistoryPanelProps {
+    pub toasts: UseState<Vec<Toast>>,
+}
+
+/// Past download runs, newest first; clicking one opens its folder
+pub fn HistoryPanel(cx: Scope<HistoryPanelProps>) -> Element {
+    let records = use_state(cx, Vec::<DownloadRecord>::new);
+
+    use_future(cx, (), |_| {
+        to_owned![records];
+        async move {
+            let loaded = tokio::task::spawn_blocking(HistoryService::load)
+                .await
+                .unwrap_or_default();
+            records.set(loaded);
+        }
+    });
+
+    let handle_clear = move |_| match HistoryService::clear() {
+        Ok(()) => records.set(Vec::new()),
+        Err(err) => push_toast(
+            &cx.props.toasts,
+            ToastLevel::Error,
+            format!("Could not clear history: {}", err),
+        ),
+    };
+
+    cx.render(rsx! {
+        div {
+            class: "history-container",
+            div {
+                class: "history-header",
+                h2 { "Download history" }
+                button {
+                    disabled: records.is_empty(),
+                    onclick: handle_clear,
+                    "Clear history"
+                }
+            }
+
+            if records.is_empty() {
+                rsx! {
+                    div {
+                        class: "empty-state",
+                        "🕘 No downloads yet. Finished downloads are listed here."
+                    }
+                }
+            } else {
+                rsx! {
+                    div {
+                        class: "history-list",
+                        records.iter().rev().enumerate().map(|(index, record)| {
+                            let destination = record.destination.clone();
+                            let platforms = record
+                                .platform_counts
+                                .iter()
+                                .map(|(name, count)| format!("{} {}", name, count))
+                                .collect::<Vec<_>>()
+                                .join(" · ");
+                            let when = record
+                                .timestamp
+                                .with_timezone(&chrono::Local)
+                                .format("%Y-%m-%d %H:%M");
+                            let size = format_bytes(record.total_bytes);
+                            rsx! {
+                                button {
+                                    key: "{index}",
+                                    class: "history-entry",
+                                    title: "Open folder",
+                                    onclick: move |_| {
+                                        if let Err(err) = open_folder(&destination) {
+                                            push_toast(&cx.props.toasts, ToastLevel::Error, err.to_string());
+                                        }
+                                    },
+                                    div {
+                                        class: "history-entry-top",
+                                        strong { "{record.query}" }
+                                        span { class: "history-date", "{when}" }
+                                    }
+                                    div {
+                                        class: "history-entry-meta",
+                                        "{record.files} files · {size}"
+                                        if !platforms.is_empty() {
+                                            rsx! { " · {platforms}" }
+                                        }
+                                    }
+                                    div {
+                                        class: "history-path",
+                                        "📂 {record.destination.display()}"
+                                    }
+                                }
+                            }
+                        })
+                    }
+                }
+            }
+        }
+    })
+}
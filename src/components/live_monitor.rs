@@ -0,0 +1,212 @@
+use crate::components::{push_toast, Toast, ToastLevel};
+use crate::models::{
+    AppState, Platform, PlatformSearchResults, SearchContext, SearchResult, SearchType,
+};
+use crate::services::{DownloadControl, DownloadService, MastodonService};
+use dioxus::prelude::*;
+use std::path::PathBuf;
+
+#[derive(Props, PartialEq)]
+pub struct LiveMonitorProps {
+    pub hashtag: String,
+    pub app_state: UseState<AppState>,
+    pub search_results: UseState<Vec<PlatformSearchResults>>,
+    pub toasts: UseState<Vec<Toast>>,
+}
+
+/// Put a streamed post at the top of the Mastodon results, marked new
+fn append_live_post(
+    search_results: &UseState<Vec<PlatformSearchResults>>,
+    tag: &str,
+    post: SearchResult,
+) -> bool {
+    let mut added = false;
+    search_results.with_mut(|groups| {
+        let index = match groups
+            .iter()
+            .position(|group| group.platform == Platform::Mastodon && group.error.is_none())
+        {
+            Some(index) => index,
+            None => {
+                groups.push(PlatformSearchResults::success(
+                    Platform::Mastodon,
+                    format!("Mastodon · live #{}", tag.trim_start_matches('#')),
+                    Vec::new(),
+                ));
+                groups.len() - 1
+            }
+        };
+
+        let group = &mut groups[index];
+        if group.results.iter().any(|existing| existing.id == post.id) {
+            return;
+        }
+        group.new_ids.insert(post.id.clone());
+        group.results.insert(0, post);
+        added = true;
+    });
+    added
+}
+
+/// Follow a hashtag on Mastodon and add new posts to the results as they
+/// are published, optionally downloading their media
+pub fn LiveMonitor(cx: Scope<LiveMonitorProps>) -> Element {
+    let task = use_state(cx, || None::<TaskId>);
+    let live_tag = use_state(cx, String::new);
+    let received = use_state(cx, || 0usize);
+    let auto_download = use_state(cx, || false);
+
+    let handle_start = move |_| {
+        let tag = cx.props.hashtag.trim().to_string();
+        if tag.is_empty() {
+            push_toast(
+                &cx.props.toasts,
+                ToastLevel::Warn,
+                "Enter a hashtag to monitor",
+            );
+            return;
+        }
+        let settings = cx.props.app_state.get().settings.clone();
+        if !settings.api.mastodon.enabled {
+            push_toast(
+                &cx.props.toasts,
+                ToastLevel::Warn,
+                "Enable Mastodon in Settings to use the live monitor",
+            );
+            return;
+        }
+
+        received.set(0);
+        live_tag.set(tag.clone());
+        to_owned![
+            task,
+            received,
+            auto_download,
+            cx.props.search_results,
+            cx.props.toasts
+        ];
+
+        let id = cx.spawn(async move {
+            let service = MastodonService::new(&settings);
+            let downloader = DownloadService::new(settings.clone());
+            let control = DownloadControl::default();
+            let mut download_root: Option<PathBuf> = None;
+
+            // The stream calls back synchronously; downloads happen here
+            let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<SearchResult>();
+            let stream = {
+                let tag = tag.clone();
+                async move {
+                    service
+                        .stream_hashtag(&tag, |post| {
+                            let _ = sender.send(post);
+                        })
+                        .await
+                }
+            };
+            let consume = async {
+                while let Some(post) = receiver.recv().await {
+                    if settings.search.is_muted(&post) {
+                        continue;
+                    }
+                    let wants_download = *auto_download.current() && !post.media.is_empty();
+                    if !append_live_post(&search_results, &tag, post.clone()) {
+                        continue;
+                    }
+                    received.with_mut(|count| *count += 1);
+
+                    if !wants_download {
+                        continue;
+                    }
+                    let root = match &download_root {
+                        Some(root) => root.clone(),
+                        None => {
+                            let context = SearchContext::new(tag.clone(), SearchType::Hashtag, 1);
+                            match downloader.create_download_root(Some(&context)) {
+                                Ok(root) => download_root.insert(root).clone(),
+                                Err(err) => {
+                                    push_toast(
+                                        &toasts,
+                                        ToastLevel::Error,
+                                        format!("Live download failed: {}", err),
+                                    );
+                                    continue;
+                                }
+                            }
+                        }
+                    };
+                    if let Err(err) = downloader
+                        .download_into(&root, vec![post], &control, |_| {})
+                        .await
+                    {
+                        log::warn!("Live download failed: {}", err);
+                        push_toast(
+                            &toasts,
+                            ToastLevel::Warn,
+                            format!("Live download failed: {}", err),
+                        );
+                    }
+                }
+            };
+
+            let (outcome, ()) = tokio::join!(stream, consume);
+            task.set(None);
+            if let Err(err) = outcome {
+                log::warn!("Live monitor for {} stopped: {}", tag, err);
+                push_toast(
+                    &toasts,
+                    ToastLevel::Error,
+                    format!("Live monitor stopped: {}", err),
+                );
+            }
+        });
+        task.set(Some(id));
+    };
+
+    let handle_stop = move |_| {
+        if let Some(id) = *task.get() {
+            // Dropping the task closes the stream
+            cx.remove_future(id);
+            task.set(None);
+            push_toast(
+                &cx.props.toasts,
+                ToastLevel::Info,
+                format!(
+                    "Stopped monitoring {} ({} new posts)",
+                    live_tag.get(),
+                    received.get()
+                ),
+            );
+        }
+    };
+
+    cx.render(rsx! {
+        div {
+            class: "live-monitor",
+            if task.is_some() {
+                rsx! {
+                    span { class: "live-indicator", "● Live {live_tag} · {received} new" }
+                    button { class: "live-stop-btn", onclick: handle_stop, "⏹ Stop" }
+                }
+            } else {
+                rsx! {
+                    button {
+                        class: "live-btn",
+                        title: "Follow this hashtag on Mastodon and add new posts as they are published",
+                        onclick: handle_start,
+                        "📡 Live monitor"
+                    }
+                }
+            }
+            label {
+                class: "checkbox-label",
+                input {
+                    r#type: "checkbox",
+                    checked: **auto_download,
+                    onchange: move |evt| auto_download.set(evt.value.parse::<bool>().unwrap_or(false)),
+                }
+                " Auto-download new media"
+            }
+        }
+    })
+}
@@ -1,9 +1,19 @@
+pub mod history_panel;
+pub mod live_monitor;
+pub mod onboarding;
 pub mod output_panel;
 pub mod search_panel;
 pub mod settings_panel;
 pub mod status_bar;
+pub mod toast;
+pub mod whats_new;
 
+pub use history_panel::HistoryPanel;
+pub use live_monitor::LiveMonitor;
+pub use onboarding::Onboarding;
 pub use output_panel::OutputPanel;
 pub use search_panel::SearchPanel;
 pub use settings_panel::SettingsPanel;
 pub use status_bar::StatusBar;
+pub use toast::{push_toast, Toast, ToastLevel, ToastStack};
+pub use whats_new::WhatsNew;
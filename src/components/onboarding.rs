@@ -0,0 +1,278 @@
+use dioxus::prelude::*;
+
+use crate::components::settings_panel::{parse_checkbox, start_platform_oauth_flow};
+use crate::models::{AppSettings, AppState, Platform};
+use crate::services::{oauth_scopes, SettingsService};
+
+#[derive(Props, PartialEq)]
+pub struct OnboardingProps {
+    pub app_state: UseState<AppState>,
+}
+
+/// Store the final settings with onboarding marked complete
+fn finish_onboarding(cx: &ScopeState, app_state: &UseState<AppState>, mut settings: AppSettings) {
+    settings.first_run = false;
+    app_state.set(AppState {
+        settings: settings.clone(),
+    });
+
+    cx.spawn(async move {
+        if let Err(err) = SettingsService::save_settings(&settings).await {
+            log::error!("Failed to save onboarding settings: {}", err);
+        }
+    });
+}
+
+/// One-time setup guide shown on first launch
+pub fn Onboarding(cx: Scope<OnboardingProps>) -> Element {
+    let step = use_state(cx, || 0usize);
+    let draft = use_state(cx, || cx.props.app_state.get().settings.clone());
+    let sign_in_status = use_state(cx, String::new);
+    let signing_in = use_state(cx, || false);
+
+    let api = &draft.get().api;
+    let any_enabled = api.pixelfed.enabled || api.mastodon.enabled || api.bluesky.enabled;
+
+    let sign_in = move |platform: Platform| {
+        to_owned![draft, sign_in_status, signing_in];
+        let name = platform.name().to_string();
+        let platform_auth = match platform {
+            Platform::Pixelfed => draft.get().api.pixelfed.clone(),
+            _ => draft.get().api.mastodon.clone(),
+        };
+        let scopes = oauth_scopes(platform, platform_auth.request_write_scope);
+        let redirect_port = draft.get().api.oauth_redirect_port;
+        let network = draft.get().network.clone();
+
+        signing_in.set(true);
+        sign_in_status.set(format!("Waiting for {} sign-in in your browser...", name));
+
+        cx.spawn(async move {
+            match start_platform_oauth_flow(
+                &name,
+                platform_auth,
+                &scopes,
+                redirect_port,
+                &network,
+                None,
+            )
+            .await
+            {
+                Ok(updated) => {
+                    draft.with_mut(|settings| match platform {
+                        Platform::Pixelfed => settings.api.pixelfed = updated,
+                        _ => settings.api.mastodon = updated,
+                    });
+                    sign_in_status.set(format!("✅ Signed in to {}", name));
+                }
+                Err(err) => sign_in_status.set(format!("❌ {}", err)),
+            }
+            signing_in.set(false);
+        });
+    };
+
+    cx.render(rsx! {
+        div {
+            class: "onboarding-overlay",
+            div {
+                class: "onboarding-card",
+                div {
+                    class: "onboarding-steps",
+                    ["Welcome", "Platforms", "Sign in", "Done"].iter().enumerate().map(|(index, title)| rsx! {
+                        span {
+                            key: "{index}",
+                            class: if index == **step { "onboarding-step active" } else { "onboarding-step" },
+                            "{index + 1}. {title}"
+                        }
+                    })
+                }
+
+                match **step {
+                    0 => rsx! {
+                        h2 { "👋 Welcome to Fedi Sleuth" }
+                        p { "Fedi Sleuth searches Pixelfed, Mastodon and Bluesky for a user's posts or a hashtag and downloads the media it finds." }
+                        p { "Every platform starts disabled. The next two steps enable at least one platform and sign you in; everything here can be changed later under ⚙️ Settings." }
+                    },
+                    1 => rsx! {
+                        h2 { "🌐 Choose your platforms" }
+
+                        div {
+                            class: "form-group",
+                            label {
+                                input {
+                                    r#type: "checkbox",
+                                    checked: api.pixelfed.enabled,
+                                    onchange: move |evt| draft.with_mut(|settings| settings.api.pixelfed.enabled = parse_checkbox(&evt.value)),
+                                }
+                                " 🟣 Pixelfed"
+                            }
+                            if api.pixelfed.enabled {
+                                rsx! {
+                                    input {
+                                        r#type: "text",
+                                        placeholder: "pixelfed.social",
+                                        value: "{api.pixelfed.instance_url}",
+                                        oninput: move |evt| draft.with_mut(|settings| settings.api.pixelfed.instance_url = evt.value.clone()),
+                                    }
+                                    small { "Your Pixelfed instance" }
+                                }
+                            }
+                        }
+
+                        div {
+                            class: "form-group",
+                            label {
+                                input {
+                                    r#type: "checkbox",
+                                    checked: api.mastodon.enabled,
+                                    onchange: move |evt| draft.with_mut(|settings| settings.api.mastodon.enabled = parse_checkbox(&evt.value)),
+                                }
+                                " 🐘 Mastodon"
+                            }
+                            if api.mastodon.enabled {
+                                rsx! {
+                                    input {
+                                        r#type: "text",
+                                        placeholder: "mastodon.social",
+                                        value: "{api.mastodon.instance_url}",
+                                        oninput: move |evt| draft.with_mut(|settings| settings.api.mastodon.instance_url = evt.value.clone()),
+                                    }
+                                    small { "Your Mastodon instance" }
+                                }
+                            }
+                        }
+
+                        div {
+                            class: "form-group",
+                            label {
+                                input {
+                                    r#type: "checkbox",
+                                    checked: api.bluesky.enabled,
+                                    onchange: move |evt| draft.with_mut(|settings| settings.api.bluesky.enabled = parse_checkbox(&evt.value)),
+                                }
+                                " 🦋 Bluesky"
+                            }
+                            if api.bluesky.enabled {
+                                rsx! {
+                                    input {
+                                        r#type: "text",
+                                        placeholder: "you.bsky.social",
+                                        value: "{api.bluesky.handle}",
+                                        oninput: move |evt| draft.with_mut(|settings| settings.api.bluesky.handle = evt.value.clone()),
+                                    }
+                                    input {
+                                        r#type: "password",
+                                        placeholder: "App password",
+                                        value: "{api.bluesky.app_password}",
+                                        oninput: move |evt| draft.with_mut(|settings| settings.api.bluesky.app_password = evt.value.clone()),
+                                    }
+                                    small { "Create an app password under Bluesky Settings → Privacy and security → App passwords." }
+                                }
+                            }
+                        }
+                    },
+                    2 => rsx! {
+                        h2 { "🔑 Sign in" }
+                        p { "Pixelfed and Mastodon need an OAuth sign-in before they can search. Your browser opens the instance's authorization page; Fedi Sleuth only asks for read access." }
+
+                        if api.pixelfed.enabled {
+                            rsx! {
+                                div {
+                                    class: "onboarding-signin",
+                                    span { "🟣 Pixelfed ({api.pixelfed.instance_url})" }
+                                    if api.pixelfed.access_token.is_some() {
+                                        rsx! { span { class: "onboarding-ok", "✅ Signed in" } }
+                                    } else {
+                                        rsx! {
+                                            button {
+                                                class: "primary",
+                                                disabled: **signing_in,
+                                                onclick: move |_| sign_in(Platform::Pixelfed),
+                                                "Sign in"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        if api.mastodon.enabled {
+                            rsx! {
+                                div {
+                                    class: "onboarding-signin",
+                                    span { "🐘 Mastodon ({api.mastodon.instance_url})" }
+                                    if api.mastodon.access_token.is_some() {
+                                        rsx! { span { class: "onboarding-ok", "✅ Signed in" } }
+                                    } else {
+                                        rsx! {
+                                            button {
+                                                class: "primary",
+                                                disabled: **signing_in,
+                                                onclick: move |_| sign_in(Platform::Mastodon),
+                                                "Sign in"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        if api.bluesky.enabled {
+                            rsx! {
+                                div {
+                                    class: "onboarding-signin",
+                                    span { "🦋 Bluesky ({api.bluesky.handle})" }
+                                    span { class: "onboarding-ok", "Uses your app password, no browser sign-in needed" }
+                                }
+                            }
+                        }
+
+                        if !sign_in_status.is_empty() {
+                            rsx! { p { class: "onboarding-status", "{sign_in_status}" } }
+                        }
+                    },
+                    _ => rsx! {
+                        h2 { "🎉 You're all set" }
+                        p { "Pick User or Hashtag in the search panel, enter a query and press Search. Results from every enabled platform appear side by side, ready to download." }
+                        p { "Sign-ins you skipped can be completed any time under ⚙️ Settings → API & Authentication." }
+                    },
+                }
+
+                div {
+                    class: "onboarding-actions",
+                    button {
+                        class: "onboarding-skip",
+                        onclick: move |_| finish_onboarding(cx, &cx.props.app_state, cx.props.app_state.get().settings.clone()),
+                        "Skip setup"
+                    }
+                    if **step > 0 {
+                        rsx! {
+                            button {
+                                onclick: move |_| step.set(**step - 1),
+                                "← Back"
+                            }
+                        }
+                    }
+                    if **step < 3 {
+                        rsx! {
+                            button {
+                                class: "primary",
+                                disabled: (**step == 1 && !any_enabled) || **signing_in,
+                                onclick: move |_| step.set(**step + 1),
+                                "Continue →"
+                            }
+                        }
+                    } else {
+                        rsx! {
+                            button {
+                                class: "primary",
+                                onclick: move |_| finish_onboarding(cx, &cx.props.app_state, draft.get().clone()),
+                                "Start searching"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
@@ -1,6 +1,30 @@
-use crate::models::{AppState, PlatformSearchResults, SearchContext, SearchType};
-use crate::services::DownloadService;
+use crate::components::{push_toast, Toast, ToastLevel};
+use crate::models::{
+    AppState, Platform, PlatformSearchResults, Poll, SearchContext, SearchResult, SearchSettings,
+    SearchType,
+};
+use crate::services::{
+    ContactSheetService, DownloadControl, DownloadRecord, DownloadService, DownloadSummary,
+    HistoryService, HtmlExportService, LinkExportService, MediaStatus, ResumableDownload,
+    SettingsService,
+};
+use crate::utils::{
+    copy_to_clipboard, format_bytes, format_duration_ms, format_post_date, format_utc_date,
+    open_folder, result_markdown, truncate_text,
+};
 use dioxus::prelude::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Per-post media outcome of the last download, keyed by platform and id
+type MediaStatuses = HashMap<(Platform, String), MediaStatus>;
+
+/// A large download waiting for the user to confirm it
+struct PendingDownload {
+    files: usize,
+    /// Known bytes and files of unknown size, once the estimate is in
+    estimate: Option<(u64, usize)>,
+}
 
 #[derive(Props, PartialEq)]
 pub struct OutputPanelProps {
@@ -8,20 +32,504 @@ pub struct OutputPanelProps {
     pub app_state: UseState<AppState>,
     pub search_context: UseState<Option<SearchContext>>,
     pub status_message: UseState<String>,
+    pub toasts: UseState<Vec<Toast>>,
+    pub download_control: UseState<DownloadControl>,
+}
+
+/// Persist a mute-list change and drop newly muted posts from the loaded results
+fn apply_mute(
+    cx: &ScopeState,
+    app_state: &UseState<AppState>,
+    search_results: &UseState<Vec<PlatformSearchResults>>,
+    update: impl FnOnce(&mut SearchSettings) -> bool,
+) {
+    let mut settings = app_state.get().settings.clone();
+    if !update(&mut settings.search) {
+        return;
+    }
+
+    let mut groups = search_results.get().clone();
+    for group in groups.iter_mut() {
+        group
+            .results
+            .retain(|result| !settings.search.is_muted(result));
+    }
+    search_results.set(groups);
+    app_state.with_mut(|state| state.settings = settings.clone());
+
+    cx.spawn(async move {
+        if let Err(err) = SettingsService::save_settings(&settings).await {
+            log::error!("Failed to persist mute list: {}", err);
+        }
+    });
+}
+
+#[derive(Props, PartialEq)]
+struct ResultItemProps {
+    result: SearchResult,
+    is_new: bool,
+    /// How the post's media fared in the last download, if it was included
+    media_status: Option<MediaStatus>,
+    app_state: UseState<AppState>,
+    search_results: UseState<Vec<PlatformSearchResults>>,
+    toasts: UseState<Vec<Toast>>,
+}
+
+/// Characters of post text shown before "show more"
+const CONTENT_PREVIEW_CHARS: usize = 280;
+
+/// Most of a post's text rendered at once; sidecars and copies keep it all
+const CONTENT_DISPLAY_CHARS: usize = 20_000;
+
+/// First `limit` characters of `content`, cut back to a word boundary when
+/// one is close; `None` when the text already fits
+fn content_preview(content: &str, limit: usize) -> Option<&str> {
+    let (cut, _) = content.char_indices().nth(limit)?;
+    let head = &content[..cut];
+    let word_end = head
+        .rfind(char::is_whitespace)
+        .filter(|index| *index > cut * 3 / 4)
+        .unwrap_or(cut);
+    Some(head[..word_end].trim_end())
+}
+
+/// A single post in the results list, with its hover popup
+fn ResultItem(cx: Scope<ResultItemProps>) -> Element {
+    let result = &cx.props.result;
+    let muted_author = result.author.clone();
+    let tags = result.hashtags();
+    let date_display = cx.props.app_state.get().settings.appearance.date_display;
+    let date = format_post_date(&result.created_at, date_display);
+    let date_utc = format_utc_date(&result.created_at);
+    let media = result.media_breakdown();
+    let expanded = use_state(cx, || false);
+    let preview = content_preview(&result.content, CONTENT_PREVIEW_CHARS);
+    let content = truncate_text(&result.content, CONTENT_DISPLAY_CHARS);
+    // Posts behind a content warning stay collapsed until clicked
+    let revealed = use_state(cx, || false);
+    let hidden = result.content_warning.is_some() && !**revealed;
+    let poll_summary = result.poll.as_ref().map(Poll::summary);
+    let media_count = result.media.len();
+
+    cx.render(rsx! {
+        div {
+            class: if cx.props.is_new { "result-item is-new" } else { "result-item" },
+            div {
+                class: "result-header",
+                span {
+                    class: "result-platform",
+                    "{result.platform.emoji()} {result.platform.name()}"
+                }
+                strong { "{result.author}" }
+                if let Some(booster) = &result.reblogged_by {
+                    rsx! { span { class: "boosted-by", title: "Boosted into the results by {booster}", "🔁 {booster}" } }
+                }
+                if cx.props.is_new {
+                    rsx! { span { class: "new-badge", "NEW" } }
+                }
+                button {
+                    class: "mute-btn",
+                    title: "Mute this author in future searches",
+                    onclick: move |_| apply_mute(cx, &cx.props.app_state, &cx.props.search_results, |search| search.mute_author(&muted_author)),
+                    "🔇"
+                }
+                span { class: "result-date", title: "{date_utc}", "{date}" }
+            }
+            if let Some(warning) = &result.content_warning {
+                rsx! {
+                    button {
+                        class: "cw-banner",
+                        title: if **revealed { "Hide the post behind its content warning" } else { "Show the post and its media" },
+                        onclick: move |_| revealed.set(!**revealed),
+                        "⚠️ CW: {warning} · "
+                        if **revealed { "hide" } else { "show" }
+                    }
+                }
+            }
+            (!hidden && !result.content.is_empty()).then(|| match preview {
+                Some(preview) => rsx! {
+                    p {
+                        class: "result-content",
+                        if **expanded { rsx! { "{content} " } } else { rsx! { "{preview}… " } }
+                        button {
+                            class: "content-toggle",
+                            onclick: move |_| expanded.set(!**expanded),
+                            if **expanded { "show less" } else { "show more" }
+                        }
+                    }
+                },
+                None => rsx! {
+                    p { class: "result-content", "{content}" }
+                },
+            })
+            if !hidden && !tags.is_empty() {
+                rsx! {
+                    div {
+                        class: "result-tags",
+                        tags.into_iter().map(|tag| {
+                            let muted_tag = tag.clone();
+                            rsx! {
+                                button {
+                                    key: "{tag}",
+                                    class: "tag-chip",
+                                    title: "Mute #{tag} in future searches",
+                                    onclick: move |_| apply_mute(cx, &cx.props.app_state, &cx.props.search_results, |search| search.mute_tag(&muted_tag)),
+                                    "#{tag} 🔇"
+                                }
+                            }
+                        })
+                    }
+                }
+            }
+            div {
+                class: "result-meta",
+                if media.is_empty() {
+                    rsx! { span { "No media" } }
+                }
+                if media.images > 0 {
+                    rsx! { span { title: "Images", "📷 {media.images}" } }
+                }
+                if media.videos > 0 {
+                    rsx! { span { title: "Videos", "🎞 {media.videos}" } }
+                }
+                if media.links > 0 {
+                    rsx! { span { title: "Link previews", "🔗 {media.links}" } }
+                }
+                if let Some(summary) = &poll_summary {
+                    rsx! { span { title: "Poll: {summary}", "📊 Poll" } }
+                }
+                span { "👍 {result.likes}" }
+                span { "🔄 {result.shares}" }
+                match cx.props.media_status {
+                    Some(MediaStatus::Complete) => rsx! { span { class: "media-status ok", title: "All media saved in the last download", "✅ Saved" } },
+                    Some(MediaStatus::Partial) => rsx! { span { class: "media-status failed", title: "Some media failed to download; the links may be dead", "⚠️ Some media failed" } },
+                    Some(MediaStatus::Missing) => rsx! { span { class: "media-status failed", title: "No media could be downloaded; the links may be dead", "❌ Media failed" } },
+                    None => rsx! { "" },
+                }
+            }
+
+            (!hidden).then(|| rsx! {
+                div {
+                    class: "result-popup",
+                    div { class: "popup-header",
+                        strong { "{result.author}" }
+                        span { title: "{date_utc}", "{date}" }
+                    }
+                    if !result.content.is_empty() {
+                        rsx! {
+                            div { class: "popup-content", "{content}" }
+                        }
+                    }
+                    if let (Some(poll), Some(summary)) = (&result.poll, &poll_summary) {
+                        rsx! {
+                            div { class: "popup-poll",
+                                poll.options.iter().enumerate().map(|(idx, option)| {
+                                    let percent = poll.percent(option);
+                                    let share = percent.map(|percent| format!("{}%", percent)).unwrap_or_default();
+                                    let width = percent.unwrap_or(0);
+                                    rsx! {
+                                        div {
+                                            key: "{idx}",
+                                            class: "poll-option",
+                                            div { class: "poll-bar", style: "width: {width}%" }
+                                            span { class: "poll-title", "{option.title}" }
+                                            span { class: "poll-share", "{share}" }
+                                        }
+                                    }
+                                })
+                                small { "{summary}" }
+                            }
+                        }
+                    }
+                    if !result.media.is_empty() {
+                        rsx! {
+                            div { class: "popup-media",
+                                result.media.iter().enumerate().map(|(idx, item)| {
+                                    let url = &item.url;
+                                    if item.is_video() {
+                                        rsx! {
+                                            video {
+                                                key: "{url}",
+                                                class: "popup-thumbnail",
+                                                src: "{url}",
+                                                controls: "true",
+                                                preload: "metadata",
+                                                muted: "true",
+                                                r#loop: "true",
+                                                playsinline: "true",
+                                            }
+                                        }
+                                    } else {
+                                        let alt = item.description.clone().unwrap_or_else(|| format!("Media {}", idx + 1));
+                                        rsx! {
+                                            img {
+                                                key: "{url}",
+                                                class: "popup-thumbnail",
+                                                src: "{url}",
+                                                alt: "{alt}",
+                                                title: "{alt}",
+                                            }
+                                        }
+                                    }
+                                })
+                            }
+                        }
+                    }
+                    div { class: "popup-meta",
+                        div { "📷 Media: {media_count}" }
+                        div { "👍 Likes: {result.likes}" }
+                        div { "🔄 Shares: {result.shares}" }
+                        button {
+                            class: "copy-markdown-btn",
+                            title: "Copy this post as a Markdown snippet",
+                            onclick: move |_| match copy_to_clipboard(&result_markdown(&cx.props.result)) {
+                                Ok(()) => push_toast(&cx.props.toasts, ToastLevel::Info, "Post copied as Markdown"),
+                                Err(err) => push_toast(&cx.props.toasts, ToastLevel::Error, err.to_string()),
+                            },
+                            "📋 Copy as Markdown"
+                        }
+                    }
+                }
+            })
+        }
+    })
+}
+
+/// Case-insensitive author/content match for the live results filter;
+/// `needle` is already lowercased and an empty needle matches everything
+fn matches_live_filter(result: &SearchResult, needle: &str) -> bool {
+    needle.is_empty()
+        || result.author.to_lowercase().contains(needle)
+        || result.content.to_lowercase().contains(needle)
+}
+
+fn media_status(statuses: &MediaStatuses, result: &SearchResult) -> Option<MediaStatus> {
+    statuses.get(&(result.platform, result.id.clone())).copied()
+}
+
+/// The live filter, narrowed to posts whose media failed to download when
+/// `failed_only` is set
+fn passes_filters(
+    result: &SearchResult,
+    needle: &str,
+    failed_only: bool,
+    statuses: &MediaStatuses,
+) -> bool {
+    matches_live_filter(result, needle)
+        && (!failed_only
+            || media_status(statuses, result).is_some_and(|status| status.has_failures()))
+}
+
+/// Add a finished run to the download history
+fn record_history(
+    context: Option<&SearchContext>,
+    groups: &[PlatformSearchResults],
+    outcome: &anyhow::Result<DownloadSummary>,
+) {
+    if let Ok(summary) = outcome {
+        if let Err(err) = HistoryService::record(DownloadRecord::new(context, groups, summary)) {
+            log::warn!("Failed to save download history: {}", err);
+        }
+    }
+}
+
+/// Every post of the groups that searched without error
+fn downloadable_results(groups: &[PlatformSearchResults]) -> Vec<SearchResult> {
+    groups
+        .iter()
+        .filter(|group| group.error.is_none())
+        .flat_map(|group| group.results.iter().cloned())
+        .collect()
+}
+
+/// Turn the result of a download run into a toast
+fn report_download(toasts: &UseState<Vec<Toast>>, outcome: anyhow::Result<DownloadSummary>) {
+    match outcome {
+        Ok(summary) => {
+            let mut notes = Vec::new();
+            if summary.skipped > 0 {
+                notes.push(format!("{} skipped over the size limit", summary.skipped));
+            }
+            if summary.duplicates > 0 {
+                notes.push(format!("{} duplicates linked", summary.duplicates));
+            }
+            if summary.failed > 0 {
+                notes.push(format!("{} failed", summary.failed));
+            }
+            if summary.conversion_failed > 0 {
+                notes.push(format!(
+                    "{} kept in original format after conversion failed",
+                    summary.conversion_failed
+                ));
+            }
+            let notes = if notes.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", notes.join(", "))
+            };
+            let level = if summary.failed > 0 {
+                ToastLevel::Warn
+            } else {
+                ToastLevel::Success
+            };
+            push_toast(
+                toasts,
+                level,
+                format!(
+                    "Download completed! {} files saved to: {}{}",
+                    summary.downloaded,
+                    summary.root.display(),
+                    notes
+                ),
+            );
+        }
+        Err(e) => {
+            push_toast(toasts, ToastLevel::Error, format!("Download failed: {}", e));
+        }
+    }
 }
 
 pub fn OutputPanel(cx: Scope<OutputPanelProps>) -> Element {
     let is_downloading = use_state(cx, || false);
     let download_progress = use_state(cx, || 0.0f64);
+    let merged_view = use_state(cx, || false);
+    let live_filter = use_state(cx, String::new);
+    let filter_needle = live_filter.trim().to_lowercase();
+    let resumable = use_state(cx, || None::<ResumableDownload>);
+    let last_download_root = use_state(cx, || None::<PathBuf>);
+    let media_statuses = use_state(cx, MediaStatuses::new);
+    let failed_media_only = use_state(cx, || false);
+    let links_only = use_state(cx, || false);
+    let confirm_download = use_state(cx, || None::<PendingDownload>);
+    let failed_only = **failed_media_only;
+    let status_of = |result: &SearchResult| media_status(media_statuses.get(), result);
+    let shows = |result: &SearchResult| {
+        passes_filters(result, &filter_needle, failed_only, media_statuses.get())
+    };
+    let any_failed = media_statuses
+        .get()
+        .values()
+        .any(|status| status.has_failures());
+
+    // Offer to resume a run the app did not get to finish last time
+    let base_path = cx.props.app_state.get().settings.download.base_path.clone();
+    use_future(cx, (&base_path,), |_| {
+        to_owned![resumable, cx.props.app_state];
+        async move {
+            let settings = app_state.get().settings.clone();
+            let found = tokio::task::spawn_blocking(move || {
+                DownloadService::new(settings).find_incomplete()
+            })
+            .await
+            .ok()
+            .flatten();
+            resumable.set(found);
+        }
+    });
+
+    // Export what is on screen: every loaded post passing the live filter
+    let handle_export_html = move |_| {
+        let results: Vec<SearchResult> = cx
+            .props
+            .search_results
+            .get()
+            .iter()
+            .filter(|group| group.error.is_none())
+            .flat_map(|group| group.results.iter())
+            .filter(|result| {
+                passes_filters(
+                    result,
+                    &live_filter.trim().to_lowercase(),
+                    *failed_media_only.get(),
+                    media_statuses.get(),
+                )
+            })
+            .cloned()
+            .collect();
 
-    let handle_download = |_| {
+        let service = HtmlExportService::new(cx.props.app_state.get().settings.clone());
+        match service.export(cx.props.search_context.get().as_ref(), &results) {
+            Ok(path) => push_toast(
+                &cx.props.toasts,
+                ToastLevel::Success,
+                format!("Exported {} posts to {}", results.len(), path.display()),
+            ),
+            Err(err) => push_toast(
+                &cx.props.toasts,
+                ToastLevel::Error,
+                format!("HTML export failed: {}", err),
+            ),
+        }
+    };
+
+    let handle_resume = move |_| {
+        let Some(previous) = resumable.get().clone() else {
+            return;
+        };
+        to_owned![
+            cx.props.app_state,
+            cx.props.status_message,
+            cx.props.toasts,
+            cx.props.download_control,
+            is_downloading,
+            download_progress,
+            resumable,
+            last_download_root,
+            media_statuses
+        ];
+        resumable.set(None);
+
+        cx.spawn(async move {
+            is_downloading.set(true);
+            status_message.set("Resuming download...".to_string());
+
+            let download_service = DownloadService::new(app_state.get().settings.clone());
+            let control = download_control.get().clone();
+            let outcome = download_service
+                .resume(previous, &control, |progress| {
+                    download_progress.set(progress);
+                    status_message.set(format!("Downloading... {:.1}%", progress * 100.0));
+                })
+                .await;
+            record_history(None, &[], &outcome);
+            if let Ok(summary) = &outcome {
+                last_download_root.set(Some(summary.root.clone()));
+                media_statuses.with_mut(|statuses| statuses.extend(summary.media_status.clone()));
+            }
+            report_download(&toasts, outcome);
+
+            status_message.set(String::new());
+            is_downloading.set(false);
+            download_progress.set(0.0);
+        });
+    };
+
+    let handle_discard_resume = move |_| {
+        if let Some(previous) = resumable.get() {
+            if let Err(err) = DownloadService::discard_incomplete(previous) {
+                push_toast(
+                    &cx.props.toasts,
+                    ToastLevel::Error,
+                    format!("Could not discard the previous download: {}", err),
+                );
+            }
+        }
+        resumable.set(None);
+    };
+
+    let start_download = move || {
         to_owned![
             cx.props.search_results,
             cx.props.search_context,
             cx.props.app_state,
             cx.props.status_message,
+            cx.props.toasts,
+            cx.props.download_control,
             is_downloading,
-            download_progress
+            download_progress,
+            last_download_root,
+            media_statuses,
+            links_only
         ];
 
         cx.spawn(async move {
@@ -29,7 +537,33 @@ pub fn OutputPanel(cx: Scope<OutputPanelProps>) -> Element {
             let has_media = current_groups.iter().any(|group| !group.results.is_empty());
 
             if !has_media {
-                status_message.set("No results to download".to_string());
+                push_toast(&toasts, ToastLevel::Warn, "No results to download");
+                return;
+            }
+
+            // Hand the URLs to an external downloader instead of fetching them
+            if *links_only.get() {
+                let results = downloadable_results(&current_groups);
+                let service = LinkExportService::new(app_state.get().settings.clone());
+                match service.export(search_context.get().as_ref(), &results) {
+                    Ok((root, count)) => {
+                        push_toast(
+                            &toasts,
+                            ToastLevel::Success,
+                            format!(
+                                "Wrote {} media links to links.txt and links.csv in {}",
+                                count,
+                                root.display()
+                            ),
+                        );
+                        last_download_root.set(Some(root));
+                    }
+                    Err(err) => push_toast(
+                        &toasts,
+                        ToastLevel::Error,
+                        format!("Link export failed: {}", err),
+                    ),
+                }
                 return;
             }
 
@@ -38,35 +572,129 @@ pub fn OutputPanel(cx: Scope<OutputPanelProps>) -> Element {
 
             let download_service = DownloadService::new(app_state.get().settings.clone());
             let context_snapshot = search_context.get().clone();
+            let control = download_control.get().clone();
 
-            match download_service
-                .download_all(context_snapshot, current_groups, |progress| {
-                    download_progress.set(progress);
-                    status_message.set(format!("Downloading... {:.1}%", progress * 100.0));
-                })
-                .await
-            {
-                Ok(download_path) => {
-                    status_message.set(format!(
-                        "Download completed! Files saved to: {}",
-                        download_path.display()
-                    ));
-                }
-                Err(e) => {
-                    status_message.set(format!("Download failed: {}", e));
-                }
+            let outcome = download_service
+                .download_all(
+                    context_snapshot.clone(),
+                    current_groups.clone(),
+                    &control,
+                    |progress| {
+                        download_progress.set(progress);
+                        status_message.set(format!("Downloading... {:.1}%", progress * 100.0));
+                    },
+                )
+                .await;
+            record_history(context_snapshot.as_ref(), &current_groups, &outcome);
+            if let Ok(summary) = &outcome {
+                last_download_root.set(Some(summary.root.clone()));
+                media_statuses.set(summary.media_status.clone());
             }
+            report_download(&toasts, outcome);
+
+            status_message.set(String::new());
 
             is_downloading.set(false);
             download_progress.set(0.0);
         });
     };
 
+    // Large downloads wait for a confirmation showing what they will write
+    let handle_download = move |_| {
+        let settings = &cx.props.app_state.get().settings;
+        let threshold = settings.download.confirm_above_files as usize;
+        let results = downloadable_results(cx.props.search_results.get());
+        let files: usize = results.iter().map(|result| result.media.len()).sum();
+        if *links_only.get() || threshold == 0 || files < threshold {
+            start_download();
+            return;
+        }
+
+        confirm_download.set(Some(PendingDownload {
+            files,
+            estimate: None,
+        }));
+        let service = DownloadService::new(settings.clone());
+        to_owned![confirm_download];
+        cx.spawn(async move {
+            let estimate = service.estimate_size(&results).await;
+            confirm_download.with_mut(|pending| {
+                if let Some(pending) = pending {
+                    pending.estimate = Some(estimate);
+                }
+            });
+        });
+    };
+
     cx.render(rsx! {
         div {
             class: "output-container",
             h2 { "Search Results" }
 
+            if let Some(pending) = confirm_download.get() {
+                let files = pending.files;
+                let size = match pending.estimate {
+                    None => "Estimating size…".to_string(),
+                    Some((bytes, 0)) => format!("About {}", format_bytes(bytes)),
+                    Some((bytes, unknown)) => format!(
+                        "At least {} ({} file(s) did not report a size)",
+                        format_bytes(bytes),
+                        unknown
+                    ),
+                };
+                let destination = cx.props.app_state.get().settings.download.base_path.clone();
+                rsx! {
+                    div {
+                        class: "confirm-overlay",
+                        div {
+                            class: "confirm-card",
+                            h3 { "Download {files} files?" }
+                            p { "{size}" }
+                            p { "Into {destination}" }
+                            small { "The file count that asks first is set in Settings → Download." }
+                            div {
+                                class: "confirm-actions",
+                                button {
+                                    onclick: move |_| confirm_download.set(None),
+                                    "Cancel"
+                                }
+                                button {
+                                    class: "primary",
+                                    onclick: move |_| {
+                                        confirm_download.set(None);
+                                        start_download();
+                                    },
+                                    "⬇️ Download"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if let Some(previous) = resumable.get() {
+                let remaining = previous.manifest.remaining();
+                let total = previous.manifest.entries.len();
+                rsx! {
+                    div {
+                        class: "resume-banner",
+                        span {
+                            "⏸ A previous download stopped with {remaining} of {total} files left in {previous.root.display()}"
+                        }
+                        button {
+                            class: "primary",
+                            disabled: *is_downloading.get(),
+                            onclick: handle_resume,
+                            "Resume previous download"
+                        }
+                        button {
+                            onclick: handle_discard_resume,
+                            "Discard"
+                        }
+                    }
+                }
+            }
+
             if cx.props.search_results.get().is_empty() {
                 rsx! {
                     div {
@@ -96,21 +724,63 @@ pub fn OutputPanel(cx: Scope<OutputPanelProps>) -> Element {
                     .iter()
                     .filter(|group| group.error.is_some())
                     .count();
+                let partial_platforms = cx
+                    .props
+                    .search_results
+                    .get()
+                    .iter()
+                    .filter(|group| group.partial_error.is_some())
+                    .count();
+                let new_posts: usize = cx
+                    .props
+                    .search_results
+                    .get()
+                    .iter()
+                    .map(|group| group.new_ids.len())
+                    .sum();
 
                 rsx! {
                     div {
                         class: "results-summary",
                         p { "{total_posts} posts across {successful_platforms} platform(s)" }
 
+                        if new_posts > 0 {
+                            rsx! {
+                                small {
+                                    class: "summary-new",
+                                    "🆕 {new_posts} new since the last run of this search"
+                                }
+                            }
+                        }
+
                         if let Some(context) = cx.props.search_context.get().as_ref() {
                             let label = match context.search_type {
                                 SearchType::User => format!("User: {}", context.query),
                                 SearchType::Hashtag => format!("Hashtag: {}", context.query),
+                                SearchType::Bookmarks => "Bookmarks".to_string(),
+                                SearchType::Favourites => "Favourites".to_string(),
+                                SearchType::PostUrl => format!("Post: {}", context.query),
+                                SearchType::Feed => format!("Feed: {}", context.query),
+                                SearchType::Trending => "Trending".to_string(),
+                            };
+                            let excluding = if context.exclude_terms.is_empty() {
+                                String::new()
+                            } else {
+                                format!(" · Excluding {}", context.exclude_terms.join(", "))
                             };
                             rsx! {
                                 small {
                                     class: "summary-context",
-                                    "{label} · Last {context.days_back} day(s)"
+                                    "{label} · Last {context.days_back} day(s){excluding}"
+                                }
+                            }
+                        }
+
+                        if partial_platforms > 0 {
+                            rsx! {
+                                small {
+                                    class: "summary-errors",
+                                    "{partial_platforms} platform(s) returned partial results"
                                 }
                             }
                         }
@@ -124,19 +794,109 @@ pub fn OutputPanel(cx: Scope<OutputPanelProps>) -> Element {
                             }
                         }
 
+                        div {
+                            class: "view-toggle",
+                            button {
+                                class: if **merged_view { "view-btn" } else { "view-btn active" },
+                                onclick: move |_| merged_view.set(false),
+                                "By platform"
+                            }
+                            button {
+                                class: if **merged_view { "view-btn active" } else { "view-btn" },
+                                onclick: move |_| merged_view.set(true),
+                                "Combined timeline"
+                            }
+                        }
+
+                        div {
+                            class: "live-filter",
+                            input {
+                                r#type: "search",
+                                placeholder: "Filter loaded results by author or text…",
+                                value: "{live_filter}",
+                                oninput: move |evt| live_filter.set(evt.value.clone()),
+                            }
+                            if !filter_needle.is_empty() {
+                                rsx! {
+                                    button {
+                                        title: "Clear filter",
+                                        onclick: move |_| live_filter.set(String::new()),
+                                        "✕"
+                                    }
+                                }
+                            }
+                            if any_failed || failed_only {
+                                rsx! {
+                                    label {
+                                        class: "checkbox-label",
+                                        input {
+                                            r#type: "checkbox",
+                                            checked: failed_only,
+                                            onchange: move |evt| failed_media_only.set(evt.value.parse::<bool>().unwrap_or(false)),
+                                        }
+                                        " Only posts with failed media"
+                                    }
+                                }
+                            }
+                        }
+
                         button {
                             class: "download-btn primary",
                             disabled: *is_downloading.get(),
                             onclick: handle_download,
                             if *is_downloading.get() {
                                 "⬇️ Downloading... {download_progress.get() * 100.0:.1}%"
+                            } else if **links_only {
+                                "🔗 Export links"
                             } else {
                                 "⬇️ Download All"
                             }
                         }
 
+                        label {
+                            class: "checkbox-label",
+                            title: "Write links.txt and links.csv of the media URLs instead of downloading the files",
+                            input {
+                                r#type: "checkbox",
+                                checked: **links_only,
+                                disabled: *is_downloading.get(),
+                                onchange: move |evt| links_only.set(evt.value.parse::<bool>().unwrap_or(false)),
+                            }
+                            " Links only"
+                        }
+
+                        button {
+                            class: "export-btn",
+                            title: "Save an offline index.html of the listed posts to the download folder",
+                            onclick: handle_export_html,
+                            "🌐 Export as HTML"
+                        }
+
+                        if let Some(root) = last_download_root.get().clone().filter(|_| !*is_downloading.get()) {
+                            rsx! {
+                                button {
+                                    class: "open-folder-btn",
+                                    title: "{root.display()}",
+                                    onclick: move |_| {
+                                        if let Err(err) = open_folder(&root) {
+                                            push_toast(&cx.props.toasts, ToastLevel::Error, err.to_string());
+                                        }
+                                    },
+                                    "📂 Open folder"
+                                }
+                            }
+                        }
+
                         if *is_downloading.get() {
                             rsx! {
+                                button {
+                                    class: "cancel-btn",
+                                    onclick: move |_| {
+                                        cx.props.download_control.get().cancel();
+                                        cx.props.status_message.set("Cancelling download...".to_string());
+                                    },
+                                    "✖ Cancel"
+                                }
                                 div {
                                     class: "progress-bar",
                                     div {
@@ -148,114 +908,174 @@ pub fn OutputPanel(cx: Scope<OutputPanelProps>) -> Element {
                         }
                     }
 
-                    div {
-                        class: "results-groups",
-                        cx.props.search_results.get().iter().enumerate().map(|(group_index, group)| rsx! {
-                            div {
-                                key: "{group_index}",
-                                class: "platform-section",
-                                div {
-                                    class: "platform-header",
-                                    h3 { "{group.label}" }
-                                    span { class: "platform-count", "{group.results.len()} posts" }
-                                }
+                    if **merged_view {
+                        // Interleave every platform's posts into one chronological list
+                        let mut merged: Vec<(&SearchResult, bool)> = cx
+                            .props
+                            .search_results
+                            .get()
+                            .iter()
+                            .filter(|group| group.error.is_none())
+                            .flat_map(|group| {
+                                group
+                                    .results
+                                    .iter()
+                                    .filter(|result| shows(result))
+                                    .map(move |result| (result, group.is_new(&result.id)))
+                            })
+                            .collect();
+                        merged.sort_by(|a, b| b.0.created_at.cmp(&a.0.created_at));
 
-                                if let Some(error) = &group.error {
-                                    rsx! {
-                                        div {
-                                            class: "platform-error",
-                                            "⚠️ {error}"
-                                        }
+                        rsx! {
+                            div {
+                                class: "platform-results merged-results",
+                                merged.into_iter().map(|(result, is_new)| rsx! {
+                                    ResultItem {
+                                        key: "{result.platform.name()}-{result.id}",
+                                        result: result.clone(),
+                                        is_new: is_new,
+                                        media_status: status_of(result),
+                                        app_state: cx.props.app_state.clone(),
+                                        search_results: cx.props.search_results.clone(),
+                                        toasts: cx.props.toasts.clone(),
                                     }
-                                } else if group.results.is_empty() {
-                                    rsx! {
+                                })
+                            }
+                        }
+                    } else {
+                        rsx! {
+                            div {
+                                class: "results-groups",
+                                cx.props.search_results.get().iter().enumerate().map(|(group_index, group)| rsx! {
+                                    div {
+                                        key: "{group_index}",
+                                        class: "platform-section",
                                         div {
-                                            class: "platform-empty",
-                                            "No posts returned from this platform."
+                                            class: "platform-header",
+                                            h3 { "{group.label}" }
+                                            if filter_needle.is_empty() && !failed_only {
+                                                rsx! { span { class: "platform-count", "{group.results.len()} posts" } }
+                                            } else {
+                                                let shown = group.results.iter().filter(|result| shows(result)).count();
+                                                rsx! { span { class: "platform-count", "{shown} of {group.results.len()} posts" } }
+                                            }
+                                            if group.error.is_none() && !group.results.is_empty() {
+                                                rsx! {
+                                                    button {
+                                                        class: "sheet-btn",
+                                                        title: "Save a thumbnail grid of this platform's posts to the download folder",
+                                                        onclick: move |_| {
+                                                            let Some(group) = cx.props.search_results.get().get(group_index).cloned() else {
+                                                                return;
+                                                            };
+                                                            to_owned![cx.props.app_state, cx.props.search_context, cx.props.status_message, cx.props.toasts];
+                                                            status_message.set(format!("Generating contact sheet for {}...", group.label));
+                                                            cx.spawn(async move {
+                                                                let service = ContactSheetService::new(app_state.get().settings.clone());
+                                                                match service.generate(search_context.get().clone(), &group).await {
+                                                                    Ok(path) => push_toast(&toasts, ToastLevel::Success, format!("Contact sheet saved to {}", path.display())),
+                                                                    Err(err) => push_toast(&toasts, ToastLevel::Error, format!("Contact sheet failed: {}", err)),
+                                                                }
+                                                                status_message.set(String::new());
+                                                            });
+                                                        },
+                                                        "🖼 Contact sheet"
+                                                    }
+                                                }
+                                            }
                                         }
-                                    }
-                                } else {
-                                    rsx! {
-                                        div {
-                                            class: "platform-results",
-                                            group.results.iter().enumerate().map(|(index, result)| rsx! {
+
+                                        if let Some(notice) = &group.notice {
+                                            rsx! {
                                                 div {
-                                                    key: "{index}",
-                                                    class: "result-item",
-                                                    div {
-                                                        class: "result-header",
-                                                        span {
-                                                            class: "result-platform",
-                                                            "{group.platform.emoji()} {group.platform.name()}"
+                                                    class: "platform-notice",
+                                                    "🔒 {notice}"
+                                                }
+                                            }
+                                        }
+
+                                        if let Some(stats) = group.stats {
+                                            let search_time = format_duration_ms(stats.elapsed_ms);
+                                            let average_request = stats.average_request_ms().map(format_duration_ms);
+                                            rsx! {
+                                                details {
+                                                    class: "search-stats",
+                                                    summary { "Details: {stats.kept} kept of {stats.seen} posts fetched" }
+                                                    ul {
+                                                        li { "Pages fetched: {stats.pages}" }
+                                                        li { "Posts returned by the server: {stats.seen}" }
+                                                        li { "Inside the date range and matching the search: {stats.matched}" }
+                                                        li { "Left after the mute list and exclusions: {stats.kept}" }
+                                                        li { "Search time: {search_time}" }
+                                                        li { "HTTP requests: {stats.requests}" }
+                                                        if let Some(average) = &average_request {
+                                                            rsx! { li { "Average request time: {average}" } }
                                                         }
-                                                        strong { "{result.author}" }
-                                                        span { class: "result-date", "{result.created_at}" }
-                                                    }
-                                                    (!result.content.is_empty()).then(|| rsx! {
-                                                        p { class: "result-content", "{result.content}" }
-                                                    })
-                                                    div {
-                                                        class: "result-meta",
-                                                        span { "📷 {result.media_count} media" }
-                                                        span { "👍 {result.likes}" }
-                                                        span { "🔄 {result.shares}" }
                                                     }
+                                                }
+                                            }
+                                        }
 
-                                                    div {
-                                                        class: "result-popup",
-                                                        div { class: "popup-header",
-                                                            strong { "{result.author}" }
-                                                            span { "{result.created_at}" }
-                                                        }
-                                                        if !result.content.is_empty() {
-                                                            rsx! {
-                                                                div { class: "popup-content", "{result.content}" }
-                                                            }
-                                                        }
-                                                        if !result.media_urls.is_empty() {
-                                                            rsx! {
-                                                                div { class: "popup-media",
-                                                                    result.media_urls.iter().zip(result.media_types.iter()).enumerate().map(|(idx, (url, media_type))| {
-                                                                        if media_type == "video" || media_type == "gifv" {
-                                                                            rsx! {
-                                                                                video {
-                                                                                    key: "{url}",
-                                                                                    class: "popup-thumbnail",
-                                                                                    src: "{url}",
-                                                                                    controls: "true",
-                                                                                    preload: "metadata",
-                                                                                    muted: "true",
-                                                                                    r#loop: "true",
-                                                                                    playsinline: "true",
-                                                                                }
-                                                                            }
-                                                                        } else {
-                                                                            rsx! {
-                                                                                img {
-                                                                                    key: "{url}",
-                                                                                    class: "popup-thumbnail",
-                                                                                    src: "{url}",
-                                                                                    alt: "Media {idx + 1}"
-                                                                                }
-                                                                            }
-                                                                        }
-                                                                    })
-                                                                }
-                                                            }
-                                                        }
-                                                        div { class: "popup-meta",
-                                                            div { "📷 Media: {result.media_count}" }
-                                                            div { "👍 Likes: {result.likes}" }
-                                                            div { "🔄 Shares: {result.shares}" }
+                                        if let Some(error) = &group.error {
+                                            let details = format!(
+                                                "Fedi-Sleuth {} — {}\n{}",
+                                                env!("CARGO_PKG_VERSION"),
+                                                group.label,
+                                                error
+                                            );
+                                            rsx! {
+                                                div {
+                                                    class: "platform-error",
+                                                    span { "⚠️ {error}" }
+                                                    button {
+                                                        class: "copy-error-btn",
+                                                        title: "Copy the full error text for a bug report",
+                                                        onclick: move |_| match copy_to_clipboard(&details) {
+                                                            Ok(()) => push_toast(&cx.props.toasts, ToastLevel::Info, "Error details copied to clipboard"),
+                                                            Err(err) => push_toast(&cx.props.toasts, ToastLevel::Error, err.to_string()),
+                                                        },
+                                                        "📋 Copy error"
+                                                    }
+                                                }
+                                            }
+                                        } else if group.results.is_empty() {
+                                            rsx! {
+                                                div {
+                                                    class: "platform-empty",
+                                                    "No posts returned from this platform."
+                                                }
+                                            }
+                                        } else {
+                                            rsx! {
+                                                if let Some(partial) = &group.partial_error {
+                                                    rsx! {
+                                                        div {
+                                                            class: "platform-partial",
+                                                            title: "{partial}",
+                                                            "⚠️ Partial results (stopped early due to server error): {partial}"
                                                         }
                                                     }
                                                 }
-                                            })
+                                                div {
+                                                    class: "platform-results",
+                                                    group.results.iter().enumerate().filter(|(_, result)| shows(result)).map(|(index, result)| rsx! {
+                                                        ResultItem {
+                                                            key: "{index}",
+                                                            result: result.clone(),
+                                                            is_new: group.is_new(&result.id),
+                                                            media_status: status_of(result),
+                                                            app_state: cx.props.app_state.clone(),
+                                                            search_results: cx.props.search_results.clone(),
+                                                            toasts: cx.props.toasts.clone(),
+                                                        }
+                                                    })
+                                                }
+                                            }
                                         }
                                     }
-                                }
+                                })
                             }
-                        })
+                        }
                     }
                 }
             }
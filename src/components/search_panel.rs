@@ -1,10 +1,16 @@
+use crate::components::settings_panel::normalize_instance_url;
+use crate::components::{push_toast, LiveMonitor, Toast, ToastLevel};
 use crate::models::{
     AppSettings, AppState, Platform, PlatformSearchResults, SearchContext, SearchType,
+    TagCombination, TagQuery,
 };
+use crate::services::http_util::measure_requests;
 use crate::services::{
-    platform_display_name, BlueskyService, MastodonService, PixelfedService, SocialPlatform,
+    platform_display_name, platform_services, public_platform_service, PixelfedService,
+    SettingsService, SnapshotService, SocialPlatform,
 };
 use dioxus::prelude::*;
+use std::collections::HashMap;
 
 fn parse_checkbox(value: &str) -> bool {
     value
@@ -12,6 +18,33 @@ fn parse_checkbox(value: &str) -> bool {
         .unwrap_or_else(|_| matches!(value, "on" | "1"))
 }
 
+/// Map one bare handle to the form each platform's user search expects,
+/// e.g. `alice` → `alice@mastodon.social` or `alice.bsky.social`
+fn handle_for_platform(handle: &str, platform: Platform, settings: &AppSettings) -> String {
+    let trimmed = handle.trim().trim_start_matches('@');
+    // Drop an `@instance` or Bluesky domain suffix; dots in the name stay
+    let local = trimmed.split('@').next().unwrap_or(trimmed);
+    let name = if local.to_ascii_lowercase().ends_with(".bsky.social") {
+        &local[..local.len() - ".bsky.social".len()]
+    } else {
+        local
+    };
+
+    match platform {
+        Platform::Pixelfed => name.to_string(),
+        Platform::Mastodon => {
+            let host = url::Url::parse(&settings.api.mastodon.instance_url)
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_string));
+            match host {
+                Some(host) => format!("{}@{}", name, host),
+                None => name.to_string(),
+            }
+        }
+        Platform::Bluesky => format!("{}.bsky.social", name.to_ascii_lowercase()),
+    }
+}
+
 #[derive(Clone, Copy, PartialEq)]
 struct PlatformSelection {
     pixelfed: bool,
@@ -31,6 +64,311 @@ impl PlatformSelection {
     fn any(&self) -> bool {
         self.pixelfed || self.mastodon || self.bluesky
     }
+
+    fn includes(&self, platform: Platform) -> bool {
+        match platform {
+            Platform::Pixelfed => self.pixelfed,
+            Platform::Mastodon => self.mastodon,
+            Platform::Bluesky => self.bluesky,
+        }
+    }
+
+    fn include(&mut self, platform: Platform) {
+        match platform {
+            Platform::Pixelfed => self.pixelfed = true,
+            Platform::Mastodon => self.mastodon = true,
+            Platform::Bluesky => self.bluesky = true,
+        }
+    }
+}
+
+/// A one-off anonymous search of another instance in place of one
+/// platform's configured instance
+#[derive(Clone, PartialEq)]
+struct InstanceOverride {
+    platform: Platform,
+    url: String,
+}
+
+impl InstanceOverride {
+    /// The override from the search form; None while its field is empty
+    fn from_input(platform: Platform, url: &str) -> Result<Option<Self>, String> {
+        if url.trim().is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            platform,
+            url: normalize_instance_url(url)?,
+        }))
+    }
+}
+
+/// One query per line: `#tag` is a hashtag, `@user` or a bare name is a
+/// user. Blank lines and lone `#`/`@` are ignored.
+fn parse_query_list(text: &str) -> Vec<(SearchType, String)> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            if line.starts_with('#') {
+                (SearchType::Hashtag, line.to_string())
+            } else {
+                (SearchType::User, line.to_string())
+            }
+        })
+        .filter(|(search_type, query)| search_type.query_problem(query).is_none())
+        .collect()
+}
+
+/// Everything one query produced across the selected platforms
+struct SearchRun {
+    groups: Vec<PlatformSearchResults>,
+    summary_parts: Vec<String>,
+    total_count: usize,
+    any_enabled: bool,
+}
+
+/// Swap the accounts picked for this run into a settings snapshot
+fn apply_account_choice(settings: &mut AppSettings, choice: &HashMap<Platform, String>) {
+    for (platform, name) in choice {
+        if !settings.api.switch_account(*platform, name) {
+            log::warn!("Saved {} account '{}' not found", platform.name(), name);
+        }
+    }
+}
+
+/// Per-platform totals from a count-only search
+#[derive(Debug, Clone, PartialEq)]
+struct CountLine {
+    label: String,
+    posts: usize,
+    media_posts: usize,
+    media_files: u64,
+    partial: bool,
+    error: Option<String>,
+}
+
+impl CountLine {
+    fn from_group(group: &PlatformSearchResults) -> Self {
+        Self {
+            label: group.label.clone(),
+            posts: group.results.len(),
+            media_posts: group
+                .results
+                .iter()
+                .filter(|result| !result.media.is_empty())
+                .count(),
+            media_files: group
+                .results
+                .iter()
+                .map(|result| result.media.len() as u64)
+                .sum(),
+            partial: group.partial_error.is_some(),
+            error: group.error.clone(),
+        }
+    }
+}
+
+/// Wait the configured courtesy delay before the next platform's requests
+async fn pause_between_platforms(settings: &AppSettings) {
+    let delay = settings.search.platform_delay_secs;
+    if delay > 0 {
+        log::info!("Waiting {}s before searching the next platform", delay);
+        tokio::time::sleep(std::time::Duration::from_secs(delay as u64)).await;
+    }
+}
+
+/// An enabled, selected platform waiting for its turn to search
+struct PlannedSearch {
+    platform: Platform,
+    label: String,
+    query: String,
+    service: Box<dyn SocialPlatform>,
+}
+
+/// Where a platform search run reports back to the user
+struct SearchFeedback<'a> {
+    toasts: &'a UseState<Vec<Toast>>,
+    status: &'a UseState<String>,
+}
+
+/// Run one query on every platform, one result group per platform.
+/// Failures become error groups and toasts rather than aborting the run.
+/// Up to `max_concurrent_platform_searches` platforms search at once.
+async fn run_platform_searches(
+    settings_snapshot: &AppSettings,
+    selection: PlatformSelection,
+    context: &SearchContext,
+    same_handle: bool,
+    instance_override: Option<&InstanceOverride>,
+    feedback: SearchFeedback<'_>,
+) -> SearchRun {
+    let toasts = feedback.toasts;
+    // Services report slow phases, such as federated account lookups, here
+    let (status_sender, mut status_updates) = tokio::sync::mpsc::unbounded_channel::<String>();
+
+    // One handle mapped to each platform's format, labelled per group
+    let platform_query = |platform: Platform| {
+        if same_handle {
+            handle_for_platform(&context.query, platform, settings_snapshot)
+        } else {
+            context.query.clone()
+        }
+    };
+
+    // Each platform's group and summary line, in platform order
+    let mut slots: Vec<Option<(PlatformSearchResults, String)>> = Vec::new();
+    let mut planned: Vec<(usize, PlannedSearch)> = Vec::new();
+
+    for mut service in platform_services(settings_snapshot, Some(&status_sender)) {
+        let platform = service.platform();
+        if let Some(other) = instance_override.filter(|other| other.platform == platform) {
+            service = public_platform_service(
+                platform,
+                settings_snapshot,
+                &other.url,
+                Some(&status_sender),
+            );
+        }
+        let mut label = platform_display_name(platform, service.instance_url());
+        if !selection.includes(platform) {
+            slots.push(Some((
+                PlatformSearchResults::error(
+                    platform,
+                    label.clone(),
+                    "Skipped (not selected)".to_string(),
+                ),
+                format!("{} skipped", label),
+            )));
+            continue;
+        }
+
+        let query = platform_query(platform);
+        if same_handle {
+            label = format!("{} as {}", label, query);
+        }
+
+        if !service.is_enabled() {
+            slots.push(Some((
+                PlatformSearchResults::error(
+                    platform,
+                    label.clone(),
+                    "Disabled in settings".to_string(),
+                ),
+                format!("{} disabled", label),
+            )));
+            continue;
+        }
+
+        planned.push((
+            slots.len(),
+            PlannedSearch {
+                platform,
+                label,
+                query,
+                service,
+            },
+        ));
+        slots.push(None);
+    }
+
+    // The semaphore is fair, so platforms start in order; with a limit of
+    // one this is the old one-after-another behaviour
+    let limit = settings_snapshot
+        .search
+        .max_concurrent_platform_searches
+        .clamp(1, 3) as usize;
+    let semaphore = tokio::sync::Semaphore::new(limit);
+    let searches = planned.iter().enumerate().map(|(order, (_, search))| {
+        let semaphore = &semaphore;
+        let search_type = context.search_type.clone();
+        async move {
+            let _permit = semaphore.acquire().await;
+            if order > 0 {
+                pause_between_platforms(settings_snapshot).await;
+            }
+            measure_requests(search.service.search(
+                search.query.clone(),
+                search_type,
+                context.days_back,
+            ))
+            .await
+        }
+    });
+    let searches = futures_util::future::join_all(searches);
+    tokio::pin!(searches);
+    let outcomes = loop {
+        tokio::select! {
+            outcomes = &mut searches => break outcomes,
+            Some(message) = status_updates.recv() => feedback.status.set(message),
+        }
+    };
+
+    let any_enabled = !planned.is_empty();
+    let mut total_count: usize = 0;
+    for ((slot, search), (outcome, timing)) in planned.into_iter().zip(outcomes) {
+        let PlannedSearch {
+            platform, label, ..
+        } = search;
+        log::info!(
+            "{}: {} requests in {:.1}s",
+            label,
+            timing.requests,
+            timing.elapsed.as_secs_f64()
+        );
+        let entry = match outcome {
+            Ok(outcome) => {
+                let mut results = outcome.results;
+                let mut stats = outcome.stats;
+                timing.record(&mut stats);
+                stats.matched = results.len();
+                results.retain(|result| {
+                    !settings_snapshot.search.is_muted(result) && !context.excludes(result)
+                });
+                stats.kept = results.len();
+                results.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+                let count = results.len();
+                total_count += count;
+                let summary = if let Some(reason) = &outcome.stopped_early {
+                    push_toast(
+                        toasts,
+                        ToastLevel::Warn,
+                        format!("{} stopped early: {}", label, reason),
+                    );
+                    format!("{}: {} posts (partial)", label, count)
+                } else {
+                    format!("{}: {} posts", label, count)
+                };
+                let mut group = PlatformSearchResults::success(platform, label, results);
+                group.partial_error = outcome.stopped_early;
+                group.stats = Some(stats);
+                group.notice = outcome.notice;
+                (group, summary)
+            }
+            Err(err) => {
+                let error_msg = err.to_string();
+                push_toast(
+                    toasts,
+                    ToastLevel::Error,
+                    format!("{}: {}", label, error_msg),
+                );
+                let summary = format!("{} ⚠️ {}", label, error_msg);
+                (
+                    PlatformSearchResults::error(platform, label, error_msg),
+                    summary,
+                )
+            }
+        };
+        slots[slot] = Some(entry);
+    }
+
+    let (groups, summary_parts) = slots.into_iter().flatten().unzip();
+    SearchRun {
+        groups,
+        summary_parts,
+        total_count,
+        any_enabled,
+    }
 }
 
 #[derive(Props, PartialEq)]
@@ -40,36 +378,233 @@ pub struct SearchPanelProps {
     pub search_context: UseState<Option<SearchContext>>,
     pub is_searching: UseState<bool>,
     pub status_message: UseState<String>,
+    pub toasts: UseState<Vec<Toast>>,
 }
 
 pub fn SearchPanel(cx: Scope<SearchPanelProps>) -> Element {
     let search_query = use_state(cx, String::new);
     let days_back_input = use_state(cx, || "180".to_string());
+    let exclude_input = use_state(cx, String::new);
+    let override_platform = use_state(cx, || Platform::Mastodon);
+    let override_url = use_state(cx, String::new);
     let search_type = use_state(cx, || SearchType::User);
     let selection_overridden = use_state(cx, || false);
+    let same_handle_everywhere = use_state(cx, || false);
+    let count_only = use_state(cx, || false);
+    let count_report = use_state(cx, || None::<Vec<CountLine>>);
+    let account_choice = use_state(cx, HashMap::<Platform, String>::new);
+    let trending_tags = use_state(cx, Vec::<String>::new);
+    let loading_trending_tags = use_state(cx, || false);
     let platform_selection = use_state(cx, || {
         PlatformSelection::from_settings(&cx.props.app_state.get().settings)
     });
 
+    let handle_load_trending_tags = move |_| {
+        let settings = cx.props.app_state.get().settings.clone();
+        if !settings.api.pixelfed.enabled {
+            push_toast(
+                &cx.props.toasts,
+                ToastLevel::Warn,
+                "Enable Pixelfed in Settings to see trending tags",
+            );
+            return;
+        }
+        loading_trending_tags.set(true);
+        to_owned![trending_tags, loading_trending_tags, cx.props.toasts];
+        cx.spawn(async move {
+            match PixelfedService::new(&settings).trending_tags().await {
+                Ok(tags) if tags.is_empty() => {
+                    push_toast(&toasts, ToastLevel::Info, "No trending tags right now")
+                }
+                Ok(tags) => trending_tags.set(tags),
+                Err(err) => push_toast(
+                    &toasts,
+                    ToastLevel::Error,
+                    format!("Could not load trending tags: {}", err),
+                ),
+            }
+            loading_trending_tags.set(false);
+        });
+    };
+
+    let handle_import = |_| {
+        to_owned![
+            days_back_input,
+            exclude_input,
+            cx.props.search_results,
+            cx.props.search_context,
+            cx.props.is_searching,
+            cx.props.status_message,
+            cx.props.toasts,
+            cx.props.app_state,
+            platform_selection,
+            selection_overridden,
+            account_choice,
+            override_platform,
+            override_url
+        ];
+
+        cx.spawn(async move {
+            let instance_override =
+                match InstanceOverride::from_input(*override_platform.get(), override_url.get()) {
+                    Ok(instance_override) => instance_override,
+                    Err(err) => {
+                        push_toast(&toasts, ToastLevel::Warn, err);
+                        return;
+                    }
+                };
+
+            let Some(file) = rfd::AsyncFileDialog::new()
+                .set_title("Import query list")
+                .add_filter("Text", &["txt"])
+                .pick_file()
+                .await
+            else {
+                return;
+            };
+
+            let queries = match tokio::fs::read_to_string(file.path()).await {
+                Ok(text) => parse_query_list(&text),
+                Err(err) => {
+                    push_toast(
+                        &toasts,
+                        ToastLevel::Error,
+                        format!("Could not read {}: {}", file.path().display(), err),
+                    );
+                    return;
+                }
+            };
+
+            if queries.is_empty() {
+                push_toast(
+                    &toasts,
+                    ToastLevel::Warn,
+                    format!("{} has no queries", file.file_name()),
+                );
+                return;
+            }
+
+            let parsed_days = days_back_input
+                .get()
+                .parse::<u32>()
+                .unwrap_or(180)
+                .clamp(1, 3650);
+            let mut settings_snapshot = app_state.get().settings.clone();
+            apply_account_choice(&mut settings_snapshot, account_choice.get());
+            let mut selection = if *selection_overridden.get() {
+                *platform_selection.get()
+            } else {
+                PlatformSelection::from_settings(&settings_snapshot)
+            };
+            if let Some(other) = &instance_override {
+                selection.include(other.platform);
+            }
+
+            if !selection.any() {
+                push_toast(
+                    &toasts,
+                    ToastLevel::Warn,
+                    "Select at least one platform to search.",
+                );
+                return;
+            }
+
+            is_searching.set(true);
+            search_results.set(Vec::new());
+            search_context.set(None);
+
+            let mut all_groups: Vec<PlatformSearchResults> = Vec::new();
+            let mut total_posts = 0usize;
+
+            for (index, (query_type, query)) in queries.iter().enumerate() {
+                status_message.set(format!(
+                    "Searching {} of {}: {}",
+                    index + 1,
+                    queries.len(),
+                    query
+                ));
+
+                let context = SearchContext::new(query.clone(), query_type.clone(), parsed_days)
+                    .with_exclusions(exclude_input.get());
+                let mut run = run_platform_searches(
+                    &settings_snapshot,
+                    selection,
+                    &context,
+                    false,
+                    instance_override.as_ref(),
+                    SearchFeedback {
+                        toasts: &toasts,
+                        status: &status_message,
+                    },
+                )
+                .await;
+
+                // Only keep groups for platforms that actually ran
+                run.groups.retain(|group| {
+                    selection.includes(group.platform)
+                        && group.error.as_deref() != Some("Disabled in settings")
+                });
+                for group in &mut run.groups {
+                    group.label = format!("{} · {}", group.label, query);
+                }
+
+                SnapshotService::apply_and_store(&context, &mut run.groups);
+
+                total_posts += run.total_count;
+                all_groups.extend(run.groups);
+                search_results.set(all_groups.clone());
+            }
+
+            push_toast(
+                &toasts,
+                ToastLevel::Success,
+                format!(
+                    "Ran {} queries from {}: {} posts",
+                    queries.len(),
+                    file.file_name(),
+                    total_posts
+                ),
+            );
+            status_message.set(String::new());
+            is_searching.set(false);
+        });
+    };
+
     let handle_search = |_| {
         to_owned![
             search_query,
             days_back_input,
+            exclude_input,
             search_type,
             cx.props.search_results,
             cx.props.search_context,
             cx.props.is_searching,
             cx.props.status_message,
+            cx.props.toasts,
             cx.props.app_state,
             platform_selection,
-            selection_overridden
+            selection_overridden,
+            same_handle_everywhere,
+            count_only,
+            count_report,
+            account_choice,
+            override_platform,
+            override_url
         ];
 
         cx.spawn(async move {
-            if search_query.get().trim().is_empty() {
-                status_message.set("Please enter a search query".to_string());
+            if let Some(problem) = search_type.get().query_problem(search_query.get()) {
+                push_toast(&toasts, ToastLevel::Warn, problem);
                 return;
             }
+            let instance_override =
+                match InstanceOverride::from_input(*override_platform.get(), override_url.get()) {
+                    Ok(instance_override) => instance_override,
+                    Err(err) => {
+                        push_toast(&toasts, ToastLevel::Warn, err);
+                        return;
+                    }
+                };
 
             let input_value = days_back_input.get().clone();
             let parsed_days = input_value.parse::<u32>().unwrap_or(180).clamp(1, 3650);
@@ -77,176 +612,95 @@ pub fn SearchPanel(cx: Scope<SearchPanelProps>) -> Element {
             if normalized_days != input_value {
                 days_back_input.set(normalized_days);
             }
-            let settings_snapshot = app_state.get().settings.clone();
+            let mut settings_snapshot = app_state.get().settings.clone();
+            let count_mode = *count_only.get();
+            settings_snapshot.search.count_only = count_mode;
+            apply_account_choice(&mut settings_snapshot, account_choice.get());
             let default_selection = PlatformSelection::from_settings(&settings_snapshot);
-            let selection = if *selection_overridden.get() {
+            let mut selection = if *selection_overridden.get() {
                 *platform_selection.get()
             } else {
                 default_selection
             };
+            if let Some(other) = &instance_override {
+                selection.include(other.platform);
+            }
 
             if !selection.any() {
-                status_message.set("Select at least one platform to search.".to_string());
+                push_toast(
+                    &toasts,
+                    ToastLevel::Warn,
+                    "Select at least one platform to search.",
+                );
                 is_searching.set(false);
                 search_context.set(None);
                 return;
             }
 
             is_searching.set(true);
-            status_message.set("Searching...".to_string());
+            status_message.set(if count_mode {
+                "Counting...".to_string()
+            } else {
+                "Searching...".to_string()
+            });
             search_results.set(Vec::new());
+            count_report.set(None);
 
             let query_value = search_query.get().clone();
             let search_type_value = search_type.get().clone();
             let context_snapshot =
-                SearchContext::new(query_value.clone(), search_type_value.clone(), parsed_days);
-
-            let mut summary_parts: Vec<String> = Vec::new();
-            let mut grouped_results: Vec<PlatformSearchResults> = Vec::new();
-            let mut total_count: usize = 0;
-            let mut any_enabled = false;
-
-            // Pixelfed
-            if selection.pixelfed {
-                let service = PixelfedService::new(&settings_snapshot);
-                let platform = Platform::Pixelfed;
-                let label = platform_display_name(platform, service.instance_url());
-
-                if !service.is_enabled() {
-                    summary_parts.push(format!("{} disabled", label));
-                    grouped_results.push(PlatformSearchResults::error(
-                        platform,
-                        label,
-                        "Disabled in settings".to_string(),
-                    ));
-                } else {
-                    any_enabled = true;
-                    match service
-                        .search(query_value.clone(), search_type_value.clone(), parsed_days)
-                        .await
-                    {
-                        Ok(mut results) => {
-                            results.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-                            let count = results.len();
-                            total_count += count;
-                            summary_parts.push(format!("{}: {} posts", label, count));
-                            grouped_results
-                                .push(PlatformSearchResults::success(platform, label, results));
-                        }
-                        Err(err) => {
-                            let error_msg = err.to_string();
-                            summary_parts.push(format!("{} ⚠️ {}", label, error_msg));
-                            grouped_results
-                                .push(PlatformSearchResults::error(platform, label, error_msg));
-                        }
-                    }
-                }
-            } else {
-                let label = platform_display_name(
-                    Platform::Pixelfed,
-                    settings_snapshot.api.pixelfed.instance_url.as_str(),
-                );
-                summary_parts.push(format!("{} skipped", label));
-                grouped_results.push(PlatformSearchResults::error(
-                    Platform::Pixelfed,
-                    label,
-                    "Skipped (not selected)".to_string(),
-                ));
-            }
+                SearchContext::new(query_value.clone(), search_type_value.clone(), parsed_days)
+                    .with_exclusions(exclude_input.get());
 
-            // Mastodon
-            if selection.mastodon {
-                let service = MastodonService::new(&settings_snapshot);
-                let platform = Platform::Mastodon;
-                let label = platform_display_name(platform, service.instance_url());
-
-                if !service.is_enabled() {
-                    summary_parts.push(format!("{} disabled", label));
-                    grouped_results.push(PlatformSearchResults::error(
-                        platform,
-                        label,
-                        "Disabled in settings".to_string(),
-                    ));
-                } else {
-                    any_enabled = true;
-                    match service
-                        .search(query_value.clone(), search_type_value.clone(), parsed_days)
-                        .await
-                    {
-                        Ok(mut results) => {
-                            results.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-                            let count = results.len();
-                            total_count += count;
-                            summary_parts.push(format!("{}: {} posts", label, count));
-                            grouped_results
-                                .push(PlatformSearchResults::success(platform, label, results));
-                        }
-                        Err(err) => {
-                            let error_msg = err.to_string();
-                            summary_parts.push(format!("{} ⚠️ {}", label, error_msg));
-                            grouped_results
-                                .push(PlatformSearchResults::error(platform, label, error_msg));
-                        }
-                    }
-                }
-            } else {
-                let label = platform_display_name(
-                    Platform::Mastodon,
-                    settings_snapshot.api.mastodon.instance_url.as_str(),
-                );
-                summary_parts.push(format!("{} skipped", label));
-                grouped_results.push(PlatformSearchResults::error(
-                    Platform::Mastodon,
-                    label,
-                    "Skipped (not selected)".to_string(),
-                ));
-            }
+            // One handle mapped to each platform's format, labelled per group
+            let same_handle =
+                search_type_value == SearchType::User && *same_handle_everywhere.get();
+            let SearchRun {
+                groups: mut grouped_results,
+                summary_parts,
+                total_count,
+                any_enabled,
+            } = run_platform_searches(
+                &settings_snapshot,
+                selection,
+                &context_snapshot,
+                same_handle,
+                instance_override.as_ref(),
+                SearchFeedback {
+                    toasts: &toasts,
+                    status: &status_message,
+                },
+            )
+            .await;
 
-            // Bluesky
-            if selection.bluesky {
-                let service = BlueskyService::new(&settings_snapshot);
-                let platform = Platform::Bluesky;
-                let label = platform_display_name(platform, service.instance_url());
-
-                if !service.is_enabled() {
-                    summary_parts.push(format!("{} disabled", label));
-                    grouped_results.push(PlatformSearchResults::error(
-                        platform,
-                        label,
-                        "Disabled in settings".to_string(),
-                    ));
-                } else {
-                    any_enabled = true;
-                    match service
-                        .search(query_value.clone(), search_type_value.clone(), parsed_days)
-                        .await
-                    {
-                        Ok(mut results) => {
-                            results.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-                            let count = results.len();
-                            total_count += count;
-                            summary_parts.push(format!("{}: {} posts", label, count));
-                            grouped_results
-                                .push(PlatformSearchResults::success(platform, label, results));
-                        }
-                        Err(err) => {
-                            let error_msg = err.to_string();
-                            summary_parts.push(format!("{} ⚠️ {}", label, error_msg));
-                            grouped_results
-                                .push(PlatformSearchResults::error(platform, label, error_msg));
-                        }
-                    }
-                }
-            } else {
-                let label = platform_display_name(Platform::Bluesky, "https://bsky.social");
-                summary_parts.push(format!("{} skipped", label));
-                grouped_results.push(PlatformSearchResults::error(
-                    Platform::Bluesky,
-                    label,
-                    "Skipped (not selected)".to_string(),
-                ));
+            // Totals only: nothing is loaded into the results, so nothing can be downloaded
+            if count_mode && any_enabled {
+                let lines: Vec<CountLine> = grouped_results
+                    .iter()
+                    .filter(|group| selection.includes(group.platform))
+                    .map(CountLine::from_group)
+                    .collect();
+                let media_files: u64 = lines.iter().map(|line| line.media_files).sum();
+                push_toast(
+                    &toasts,
+                    ToastLevel::Info,
+                    format!(
+                        "Counted {} posts with {} media files",
+                        total_count, media_files
+                    ),
+                );
+                count_report.set(Some(lines));
+                search_context.set(None);
+                status_message.set(String::new());
+                is_searching.set(false);
+                return;
             }
 
+            SnapshotService::apply_and_store(&context_snapshot, &mut grouped_results);
+            let new_count: usize = grouped_results
+                .iter()
+                .map(|group| group.new_ids.len())
+                .sum();
             search_results.set(grouped_results);
 
             let summary_suffix = if summary_parts.is_empty() {
@@ -257,16 +711,37 @@ pub fn SearchPanel(cx: Scope<SearchPanelProps>) -> Element {
 
             if any_enabled {
                 if total_count > 0 {
-                    status_message.set(format!("Fetched {} posts{}", total_count, summary_suffix));
+                    let new_suffix = if new_count > 0 {
+                        format!(" ({} new since last search)", new_count)
+                    } else {
+                        String::new()
+                    };
+                    push_toast(
+                        &toasts,
+                        ToastLevel::Success,
+                        format!(
+                            "Fetched {} posts{}{}",
+                            total_count, new_suffix, summary_suffix
+                        ),
+                    );
                 } else {
-                    status_message.set(format!("No posts found{}", summary_suffix));
+                    push_toast(
+                        &toasts,
+                        ToastLevel::Info,
+                        format!("No posts found{}", summary_suffix),
+                    );
                 }
                 search_context.set(Some(context_snapshot));
             } else {
-                status_message.set("Selected platforms are disabled in Settings.".to_string());
+                push_toast(
+                    &toasts,
+                    ToastLevel::Warn,
+                    "Selected platforms are disabled in Settings.",
+                );
                 search_context.set(None);
             }
 
+            status_message.set(String::new());
             is_searching.set(false);
         });
     };
@@ -307,25 +782,193 @@ pub fn SearchPanel(cx: Scope<SearchPanelProps>) -> Element {
                         }
                         "#️⃣ Hashtag"
                     }
+                    label {
+                        class: "radio-label",
+                        input {
+                            r#type: "radio",
+                            name: "search_type",
+                            checked: matches!(*search_type.get(), SearchType::Bookmarks),
+                            onchange: move |_| search_type.set(SearchType::Bookmarks),
+                        }
+                        "🔖 Bookmarks"
+                    }
+                    label {
+                        class: "radio-label",
+                        input {
+                            r#type: "radio",
+                            name: "search_type",
+                            checked: matches!(*search_type.get(), SearchType::Favourites),
+                            onchange: move |_| search_type.set(SearchType::Favourites),
+                        }
+                        "⭐ Favourites"
+                    }
+                    label {
+                        class: "radio-label",
+                        input {
+                            r#type: "radio",
+                            name: "search_type",
+                            checked: matches!(*search_type.get(), SearchType::PostUrl),
+                            onchange: move |_| search_type.set(SearchType::PostUrl),
+                        }
+                        "🔗 Post URL"
+                    }
+                    label {
+                        class: "radio-label",
+                        input {
+                            r#type: "radio",
+                            name: "search_type",
+                            checked: matches!(*search_type.get(), SearchType::Feed),
+                            onchange: move |_| search_type.set(SearchType::Feed),
+                        }
+                        "📰 Feed"
+                    }
+                    label {
+                        class: "radio-label",
+                        input {
+                            r#type: "radio",
+                            name: "search_type",
+                            checked: matches!(*search_type.get(), SearchType::Trending),
+                            onchange: move |_| search_type.set(SearchType::Trending),
+                        }
+                        "🔥 Trending"
+                    }
                 }
             }
 
-            div {
-                class: "form-group",
-                label {
-                    match *search_type.get() {
-                        SearchType::User => "Username:",
-                        SearchType::Hashtag => "Hashtag:",
+            if search_type.get().needs_query() {
+                rsx! {
+                    div {
+                        class: "form-group",
+                        label {
+                            match *search_type.get() {
+                                SearchType::Hashtag => "Hashtag:",
+                                SearchType::PostUrl => "Post URL:",
+                                SearchType::Feed => "Feed URI:",
+                                _ => "Username:",
+                            }
+                        }
+                        input {
+                            r#type: "text",
+                            value: "{search_query}",
+                            placeholder: match *search_type.get() {
+                                SearchType::Hashtag => "#hashtag (or #cats #dogs -#nsfw)",
+                                SearchType::PostUrl => "https://mastodon.social/@user/109876543210",
+                                SearchType::Feed => "at://did:plc:…/app.bsky.feed.generator/photography",
+                                _ => "@username",
+                            },
+                            oninput: move |evt| search_query.set(evt.value.clone()),
+                        }
+                        if *search_type.get() == SearchType::User {
+                            rsx! {
+                                label {
+                                    class: "checkbox-label",
+                                    input {
+                                        r#type: "checkbox",
+                                        checked: **same_handle_everywhere,
+                                        onchange: move |evt| same_handle_everywhere.set(parse_checkbox(&evt.value)),
+                                    }
+                                    " Same handle on every platform"
+                                }
+                                if **same_handle_everywhere && !search_query.trim().is_empty() {
+                                    let handle = search_query.get().clone();
+                                    let settings = &cx.props.app_state.get().settings;
+                                    let pixelfed = handle_for_platform(&handle, Platform::Pixelfed, settings);
+                                    let mastodon = handle_for_platform(&handle, Platform::Mastodon, settings);
+                                    let bluesky = handle_for_platform(&handle, Platform::Bluesky, settings);
+                                    rsx! {
+                                        small { "Searches 🟣 {pixelfed} · 🐘 {mastodon} · 🦋 {bluesky}" }
+                                    }
+                                }
+                            }
+                        }
+                        if *search_type.get() == SearchType::Hashtag && TagQuery::parse(search_query.get()).is_combined() {
+                            let combination = cx.props.app_state.get().settings.search.tag_combination;
+                            rsx! {
+                                div {
+                                    class: "tag-combination",
+                                    select {
+                                        value: "{combination.api_param()}",
+                                        onchange: move |evt| {
+                                            let mut settings = cx.props.app_state.get().settings.clone();
+                                            settings.search.tag_combination = TagCombination::from_api_param(&evt.value);
+                                            cx.props.app_state.set(AppState { settings: settings.clone() });
+                                            cx.spawn(async move {
+                                                if let Err(err) = SettingsService::save_settings(&settings).await {
+                                                    log::error!("Failed to save tag combination: {}", err);
+                                                }
+                                            });
+                                        },
+                                        TagCombination::ALL.iter().map(|option| rsx! {
+                                            option {
+                                                key: "{option.api_param()}",
+                                                value: "{option.api_param()}",
+                                                "{option.label()}"
+                                            }
+                                        })
+                                    }
+                                    small { "Tags prefixed with - are excluded. Mastodon combines the tags server-side; Pixelfed and Bluesky search the tags one at a time and check each post's text for the rest." }
+                                }
+                            }
+                        }
+                        if *search_type.get() == SearchType::Hashtag {
+                            rsx! {
+                                LiveMonitor {
+                                    hashtag: search_query.get().clone(),
+                                    app_state: cx.props.app_state.clone(),
+                                    search_results: cx.props.search_results.clone(),
+                                    toasts: cx.props.toasts.clone(),
+                                }
+                            }
+                        }
+                        if *search_type.get() == SearchType::PostUrl {
+                            rsx! {
+                                small { "Mastodon only. Grabs the media from one post; replies and parents in the thread are included unless turned off in Settings → Search. Days back is ignored." }
+                            }
+                        }
+                        if *search_type.get() == SearchType::Feed {
+                            rsx! {
+                                small { "Bluesky only. Paste a custom feed's AT-URI or its bsky.app link (https://bsky.app/profile/…/feed/…)." }
+                            }
+                        }
                     }
                 }
-                input {
-                    r#type: "text",
-                    value: "{search_query}",
-                    placeholder: match *search_type.get() {
-                        SearchType::User => "@username",
-                        SearchType::Hashtag => "#hashtag",
-                    },
-                    oninput: move |evt| search_query.set(evt.value.clone()),
+            } else if *search_type.get() == SearchType::Trending {
+                rsx! {
+                    div {
+                        class: "form-group",
+                        small { "Pixelfed only. Posts from your instance's discover page; days back picks the daily, monthly or yearly ranking." }
+                        div {
+                            class: "trending-tags",
+                            button {
+                                class: "trending-tags-btn",
+                                disabled: **loading_trending_tags,
+                                onclick: handle_load_trending_tags,
+                                if **loading_trending_tags { "Loading…" } else { "🏷️ Load trending tags" }
+                            }
+                            trending_tags.iter().map(|tag| {
+                                let tag = tag.clone();
+                                rsx! {
+                                    button {
+                                        key: "{tag}",
+                                        class: "tag-chip",
+                                        title: "Search this hashtag",
+                                        onclick: move |_| {
+                                            search_query.set(format!("#{}", tag));
+                                            search_type.set(SearchType::Hashtag);
+                                        },
+                                        "#{tag}"
+                                    }
+                                }
+                            })
+                        }
+                    }
+                }
+            } else {
+                rsx! {
+                    div {
+                        class: "form-group",
+                        small { "Searches your own account's saved posts. Bookmarks are Mastodon only and need the bookmarks/favourites scopes; Favourites also reads your Bluesky likes." }
+                    }
                 }
             }
 
@@ -344,6 +987,32 @@ pub fn SearchPanel(cx: Scope<SearchPanelProps>) -> Element {
                 small { "Default: 180 days (about 6 months)" }
             }
 
+            div {
+                class: "form-group",
+                label { "Exclude posts containing:" }
+                input {
+                    r#type: "text",
+                    value: "{exclude_input}",
+                    placeholder: "giveaway, promo code, dm for collab",
+                    oninput: move |evt| exclude_input.set(evt.value.clone()),
+                }
+                small { "Comma-separated words or phrases, not case-sensitive. Matching posts are dropped on every platform." }
+            }
+
+            div {
+                class: "form-group",
+                label {
+                    class: "checkbox-label",
+                    input {
+                        r#type: "checkbox",
+                        checked: **count_only,
+                        onchange: move |evt| count_only.set(parse_checkbox(&evt.value)),
+                    }
+                    " Count only"
+                }
+                small { "Pages through the window keeping just dates and media counts, then reports totals. Nothing is loaded for download and mute and exclusion filters are not applied." }
+            }
+
             div {
                 class: "form-group",
                 label { "Platforms to search:" }
@@ -395,6 +1064,72 @@ pub fn SearchPanel(cx: Scope<SearchPanelProps>) -> Element {
                 small { "Toggle platforms per search. Configure credentials in Settings." }
             }
 
+            Platform::ALL.iter().filter_map(|platform| {
+                let platform = *platform;
+                let api = &cx.props.app_state.get().settings.api;
+                let active = api.active_account_name(platform).to_string();
+                let others: Vec<String> = api
+                    .account_names(platform)
+                    .into_iter()
+                    .filter(|name| *name != active)
+                    .collect();
+                if others.is_empty() {
+                    return None;
+                }
+                let chosen = account_choice.get().get(&platform).cloned().unwrap_or_default();
+                let active_label = if active.is_empty() { "current account".to_string() } else { active };
+                Some(rsx! {
+                    div {
+                        key: "{platform.name()}",
+                        class: "form-group",
+                        label { "{platform.emoji()} {platform.name()} account:" }
+                        select {
+                            value: "{chosen}",
+                            onchange: move |evt| {
+                                let mut choice = account_choice.get().clone();
+                                if evt.value.is_empty() {
+                                    choice.remove(&platform);
+                                } else {
+                                    choice.insert(platform, evt.value.clone());
+                                }
+                                account_choice.set(choice);
+                            },
+                            option { value: "", "Active ({active_label})" }
+                            others.iter().map(|name| rsx! {
+                                option { key: "{name}", value: "{name}", "{name}" }
+                            })
+                        }
+                    }
+                })
+            })
+
+            div {
+                class: "form-group",
+                label { "Search another instance (optional):" }
+                div {
+                    class: "instance-override",
+                    select {
+                        value: "{override_platform.get().name()}",
+                        onchange: move |evt| {
+                            override_platform.set(if evt.value == Platform::Pixelfed.name() {
+                                Platform::Pixelfed
+                            } else {
+                                Platform::Mastodon
+                            });
+                        },
+                        option { value: "{Platform::Mastodon.name()}", "Mastodon" }
+                        option { value: "{Platform::Pixelfed.name()}", "Pixelfed" }
+                    }
+                    input {
+                        r#type: "text",
+                        value: "{override_url}",
+                        placeholder: "e.g. fosstodon.org",
+                        oninput: move |evt| override_url.set(evt.value.clone()),
+                    }
+                }
+                small { "Searches this instance instead of the one in Settings, for this search only. No sign-in is used, so only public posts and accounts the instance already knows are found." }
+            }
+
             button {
                 class: "search-btn primary",
                 disabled: *cx.props.is_searching.get(),
@@ -405,6 +1140,65 @@ pub fn SearchPanel(cx: Scope<SearchPanelProps>) -> Element {
                     "🔍 Start Search"
                 }
             }
+
+            button {
+                class: "import-btn",
+                disabled: *cx.props.is_searching.get(),
+                title: "Run every line of a text file as its own search: #tag for hashtags, @user or a bare name for users",
+                onclick: handle_import,
+                "📂 Import query list"
+            }
+
+            if let Some(lines) = count_report.get() {
+                rsx! {
+                    div {
+                        class: "count-report",
+                        h3 { "Counts for the last {days_back_input} days" }
+                        lines.iter().map(|line| {
+                            let detail = match &line.error {
+                                Some(error) => format!("⚠️ {}", error),
+                                None => format!(
+                                    "{} posts · {} with media · {} media files{}",
+                                    line.posts,
+                                    line.media_posts,
+                                    line.media_files,
+                                    if line.partial { " (partial)" } else { "" }
+                                ),
+                            };
+                            rsx! {
+                                div {
+                                    key: "{line.label}",
+                                    class: "count-line",
+                                    strong { "{line.label}" }
+                                    span { "{detail}" }
+                                }
+                            }
+                        })
+                    }
+                }
+            }
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handle_keeps_dotted_usernames() {
+        let settings = AppSettings::default();
+        assert_eq!(
+            handle_for_platform("john.doe", Platform::Pixelfed, &settings),
+            "john.doe"
+        );
+        assert_eq!(
+            handle_for_platform("@john.doe@pixelfed.social", Platform::Pixelfed, &settings),
+            "john.doe"
+        );
+        assert_eq!(
+            handle_for_platform("John.Doe.bsky.social", Platform::Bluesky, &settings),
+            "john.doe.bsky.social"
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use dioxus::prelude::*;
+
+static NEXT_TOAST_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastLevel {
+    Info,
+    Success,
+    Warn,
+    Error,
+}
+
+impl ToastLevel {
+    fn class(&self) -> &'static str {
+        match self {
+            ToastLevel::Info => "toast info",
+            ToastLevel::Success => "toast success",
+            ToastLevel::Warn => "toast warn",
+            ToastLevel::Error => "toast error",
+        }
+    }
+
+    fn icon(&self) -> &'static str {
+        match self {
+            ToastLevel::Info => "ℹ️",
+            ToastLevel::Success => "✅",
+            ToastLevel::Warn => "⚠️",
+            ToastLevel::Error => "❌",
+        }
+    }
+
+    /// How long the toast stays up; problems linger so they can be read
+    fn lifetime(&self) -> Duration {
+        match self {
+            ToastLevel::Info | ToastLevel::Success => Duration::from_secs(5),
+            ToastLevel::Warn => Duration::from_secs(8),
+            ToastLevel::Error => Duration::from_secs(12),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Toast {
+    pub id: u64,
+    pub level: ToastLevel,
+    pub message: String,
+}
+
+/// Queue a notification; it dismisses itself after its level's lifetime
+pub fn push_toast(toasts: &UseState<Vec<Toast>>, level: ToastLevel, message: impl Into<String>) {
+    let toast = Toast {
+        id: NEXT_TOAST_ID.fetch_add(1, Ordering::Relaxed),
+        level,
+        message: message.into(),
+    };
+    toasts.with_mut(|queue| queue.push(toast));
+}
+
+fn dismiss_toast(toasts: &UseState<Vec<Toast>>, id: u64) {
+    toasts.with_mut(|queue| queue.retain(|toast| toast.id != id));
+}
+
+#[derive(Props, PartialEq)]
+pub struct ToastStackProps {
+    pub toasts: UseState<Vec<Toast>>,
+}
+
+/// Stack of active notifications, newest at the bottom
+pub fn ToastStack(cx: Scope<ToastStackProps>) -> Element {
+    if cx.props.toasts.is_empty() {
+        return None;
+    }
+
+    cx.render(rsx! {
+        div {
+            class: "toast-stack",
+            cx.props.toasts.iter().map(|toast| rsx! {
+                ToastItem {
+                    key: "{toast.id}",
+                    toast: toast.clone(),
+                    toasts: cx.props.toasts.clone(),
+                }
+            })
+        }
+    })
+}
+
+#[derive(Props, PartialEq)]
+struct ToastItemProps {
+    toast: Toast,
+    toasts: UseState<Vec<Toast>>,
+}
+
+fn ToastItem(cx: Scope<ToastItemProps>) -> Element {
+    let id = cx.props.toast.id;
+
+    use_future(cx, (), |_| {
+        to_owned![cx.props.toasts];
+        let lifetime = cx.props.toast.level.lifetime();
+        async move {
+            tokio::time::sleep(lifetime).await;
+            dismiss_toast(&toasts, id);
+        }
+    });
+
+    let level = cx.props.toast.level;
+
+    cx.render(rsx! {
+        div {
+            class: level.class(),
+            span { class: "toast-icon", level.icon() }
+            span { class: "toast-message", "{cx.props.toast.message}" }
+            button {
+                class: "toast-close",
+                title: "Dismiss",
+                onclick: move |_| dismiss_toast(&cx.props.toasts, id),
+                "✕"
+            }
+        }
+    })
+}
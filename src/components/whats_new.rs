@@ -0,0 +1,87 @@
+use dioxus::prelude::*;
+
+use crate::models::AppState;
+use crate::services::SettingsService;
+use crate::utils::is_newer_version;
+
+/// Notable changes per release, newest first. Add an entry when bumping
+/// the version in Cargo.toml.
+const RELEASE_NOTES: &[(&str, &[&str])] = &[(
+    "0.1.0",
+    &[
+        "Bluesky likes can be searched as favourites, and user searches save the account's bio and follower counts with the metadata.",
+        "Boosts name who boosted them, and you can choose whose folder they are filed in.",
+        "Export just the media links (links.txt and links.csv) for use with other downloaders.",
+        "Large downloads ask for confirmation first, with an estimate of their size.",
+        "Each platform's results show how many posts were fetched, matched and kept.",
+        "Private and limited accounts are flagged instead of coming back silently empty.",
+        "Network settings: connection pooling, a custom CA certificate and compressed responses.",
+    ],
+)];
+
+/// The release notes the user has not seen yet, newest first
+fn unseen_notes(last_seen: &str) -> Vec<(&'static str, &'static [&'static str])> {
+    RELEASE_NOTES
+        .iter()
+        .filter(|(version, _)| is_newer_version(version, last_seen))
+        .copied()
+        .collect()
+}
+
+#[derive(Props, PartialEq)]
+pub struct WhatsNewProps {
+    pub app_state: UseState<AppState>,
+    pub visible: UseState<bool>,
+}
+
+/// Record the running version as seen so the panel stays closed
+fn dismiss(cx: &ScopeState, app_state: &UseState<AppState>, visible: &UseState<bool>) {
+    let mut settings = app_state.get().settings.clone();
+    settings.last_seen_version = env!("CARGO_PKG_VERSION").to_string();
+    app_state.set(AppState {
+        settings: settings.clone(),
+    });
+    visible.set(false);
+
+    cx.spawn(async move {
+        if let Err(err) = SettingsService::save_settings(&settings).await {
+            log::error!("Failed to save the last seen version: {}", err);
+        }
+    });
+}
+
+/// "What's new" notes shown once after the app is updated
+pub fn WhatsNew(cx: Scope<WhatsNewProps>) -> Element {
+    let notes = unseen_notes(&cx.props.app_state.get().settings.last_seen_version);
+    let version = env!("CARGO_PKG_VERSION");
+
+    cx.render(rsx! {
+        div {
+            class: "confirm-overlay",
+            div {
+                class: "confirm-card whats-new",
+                h2 { "What's new in Fedi Sleuth {version}" }
+                if notes.is_empty() {
+                    rsx! { p { "Bug fixes and small improvements." } }
+                }
+                notes.iter().map(|(version, changes)| rsx! {
+                    div {
+                        key: "{version}",
+                        h3 { "{version}" }
+                        ul {
+                            changes.iter().map(|change| rsx! { li { "{change}" } })
+                        }
+                    }
+                })
+                div {
+                    class: "confirm-actions",
+                    button {
+                        class: "primary",
+                        onclick: move |_| dismiss(cx, &cx.props.app_state, &cx.props.visible),
+                        "Got it"
+                    }
+                }
+            }
+        }
+    })
+}
@@ -7,6 +7,7 @@ use dioxus::prelude::*;
 use dioxus_desktop::{Config, WindowBuilder};
 
 mod app;
+mod cli;
 mod components;
 mod config;
 mod models;
@@ -14,16 +15,29 @@ mod services;
 mod utils;
 
 use app::App;
+use services::SettingsService;
 
 fn main() {
-    // Initialize logging with info level, suppress tao windowing warnings
+    // Initialize logging, suppress tao windowing warnings. The logger accepts
+    // everything; the configured level is applied as the global max level so
+    // it can be changed from settings without restarting.
+    let log_level = SettingsService::load_settings_blocking()
+        .map(|settings| settings.log_level)
+        .unwrap_or_default();
     env_logger::Builder::from_default_env()
-        .filter_level(log::LevelFilter::Info)
+        .filter_level(log::LevelFilter::Trace)
         .filter_module(
             "tao::platform_impl::platform::event_loop",
             log::LevelFilter::Error,
         )
         .init();
+    SettingsService::apply_log_level(log_level);
+
+    // Headless mode for scripts: search (and optionally download) then exit
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if cli::requested(&args) {
+        std::process::exit(cli::run(&args));
+    }
 
     // Launch the Dioxus desktop app
     dioxus_desktop::launch_cfg(
@@ -1,5 +1,10 @@
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use crate::utils::sanitize_path_component;
 
 #[derive(Debug, Clone)]
 pub struct AppState {
@@ -14,21 +19,50 @@ impl Default for AppState {
     }
 }
 
+/// Per-platform instance URLs live in `api`; `home_instances` only seeds
+/// them for new or empty platform configs. Settings files from before this
+/// split still carry a top-level `instance_url`, which is ignored on load.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppSettings {
-    pub instance_url: String,
+    #[serde(default)]
+    pub home_instances: HomeInstances,
     pub appearance: AppearanceSettings,
     pub api: ApiSettings,
     pub download: DownloadSettings,
+    #[serde(default)]
+    pub search: SearchSettings,
+    #[serde(default)]
+    pub network: NetworkSettings,
+    #[serde(default)]
+    pub log_level: LogLevel,
+    /// Show the setup guide on launch. Only fresh installs start with this
+    /// set; settings saved by older versions deserialize it as false.
+    #[serde(default)]
+    pub first_run: bool,
+    /// Verify each enabled platform's credentials in the background at launch
+    #[serde(default)]
+    pub verify_credentials_on_startup: bool,
+    /// Version whose "What's new" notes were last dismissed; empty in
+    /// settings saved before it was tracked
+    #[serde(default)]
+    pub last_seen_version: String,
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
+        let home_instances = HomeInstances::default();
         Self {
-            instance_url: "pixelfed.social".to_string(),
+            api: ApiSettings::with_home_instances(&home_instances),
+            home_instances,
             appearance: AppearanceSettings::default(),
-            api: ApiSettings::default(),
             download: DownloadSettings::default(),
+            search: SearchSettings::default(),
+            network: NetworkSettings::default(),
+            log_level: LogLevel::default(),
+            first_run: true,
+            verify_credentials_on_startup: false,
+            // Fresh installs have nothing new to catch up on
+            last_seen_version: env!("CARGO_PKG_VERSION").to_string(),
         }
     }
 }
@@ -36,8 +70,11 @@ impl Default for AppSettings {
 impl AppSettings {
     /// Migrate from old single-platform settings to multi-platform
     pub fn migrate_from_legacy(instance_url: String, old_api: LegacyApiSettings) -> Self {
+        let home_instances = HomeInstances {
+            pixelfed: instance_url.clone(),
+            ..HomeInstances::default()
+        };
         Self {
-            instance_url: instance_url.clone(),
             appearance: AppearanceSettings::default(),
             api: ApiSettings {
                 pixelfed: PlatformAuth {
@@ -47,18 +84,23 @@ impl AppSettings {
                     client_id: old_api.client_id,
                     client_secret: old_api.client_secret,
                     access_token: old_api.access_token,
+                    request_write_scope: false,
+                    account_name: String::new(),
+                    custom_headers: Vec::new(),
                 },
-                mastodon: PlatformAuth {
-                    enabled: false,
-                    instance_url: "mastodon.social".to_string(),
-                    app_name: "Fedi Sleuth".to_string(),
-                    client_id: String::new(),
-                    client_secret: String::new(),
-                    access_token: None,
-                },
+                mastodon: PlatformAuth::new_disabled(&home_instances.mastodon),
                 bluesky: BlueskyAuth::default(),
+                saved_accounts: Vec::new(),
+                oauth_redirect_port: None,
             },
+            home_instances,
             download: DownloadSettings::default(),
+            search: SearchSettings::default(),
+            network: NetworkSettings::default(),
+            log_level: LogLevel::default(),
+            first_run: false,
+            verify_credentials_on_startup: false,
+            last_seen_version: String::new(),
         }
     }
 }
@@ -67,6 +109,8 @@ impl AppSettings {
 pub struct AppearanceSettings {
     pub theme: Theme,
     pub accent_color: String,
+    #[serde(default)]
+    pub date_display: DateDisplay,
 }
 
 impl Default for AppearanceSettings {
@@ -74,6 +118,7 @@ impl Default for AppearanceSettings {
         Self {
             theme: Theme::System,
             accent_color: "#0078d4".to_string(), // Windows 11 default blue
+            date_display: DateDisplay::default(),
         }
     }
 }
@@ -95,6 +140,75 @@ impl std::fmt::Display for Theme {
     }
 }
 
+/// How post dates are shown in the results
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DateDisplay {
+    /// Local date and time, e.g. "2025-10-25 14:32"
+    #[default]
+    Absolute,
+    /// Age of the post, e.g. "3 days ago"
+    Relative,
+}
+
+impl std::fmt::Display for DateDisplay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DateDisplay::Absolute => write!(f, "absolute"),
+            DateDisplay::Relative => write!(f, "relative"),
+        }
+    }
+}
+
+/// Most verbose log messages that are written
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 5] = [
+        LogLevel::Error,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
+        LogLevel::Trace,
+    ];
+
+    pub fn from_name(name: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|level| level.to_string() == name)
+            .unwrap_or_default()
+    }
+
+    pub fn to_filter(self) -> log::LevelFilter {
+        match self {
+            LogLevel::Error => log::LevelFilter::Error,
+            LogLevel::Warn => log::LevelFilter::Warn,
+            LogLevel::Info => log::LevelFilter::Info,
+            LogLevel::Debug => log::LevelFilter::Debug,
+            LogLevel::Trace => log::LevelFilter::Trace,
+        }
+    }
+}
+
+impl std::fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogLevel::Error => write!(f, "error"),
+            LogLevel::Warn => write!(f, "warn"),
+            LogLevel::Info => write!(f, "info"),
+            LogLevel::Debug => write!(f, "debug"),
+            LogLevel::Trace => write!(f, "trace"),
+        }
+    }
+}
+
 // ============================================================================
 // Multi-Platform API Settings
 // ============================================================================
@@ -109,35 +223,210 @@ pub struct LegacyApiSettings {
     pub access_token: Option<String>,
 }
 
+/// The active account per platform, which is what the services read, plus
+/// any other named accounts the user can switch to
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiSettings {
     pub pixelfed: PlatformAuth,
     pub mastodon: PlatformAuth,
     pub bluesky: BlueskyAuth,
+    /// Stored accounts for every platform, including a copy of each named
+    /// active account
+    #[serde(default)]
+    pub saved_accounts: Vec<SavedAccount>,
+    /// Fixed localhost port for the OAuth callback, so the redirect URI can
+    /// be allowed through a firewall or pre-registered. None picks a free port.
+    #[serde(default)]
+    pub oauth_redirect_port: Option<u16>,
 }
 
 impl Default for ApiSettings {
     fn default() -> Self {
+        Self::with_home_instances(&HomeInstances::default())
+    }
+}
+
+impl ApiSettings {
+    /// Fresh platform configs pointing at the user's home instances
+    pub fn with_home_instances(home: &HomeInstances) -> Self {
         Self {
-            pixelfed: PlatformAuth {
-                enabled: false,
-                instance_url: "pixelfed.social".to_string(),
-                app_name: "Fedi Sleuth".to_string(),
-                client_id: String::new(),
-                client_secret: String::new(),
-                access_token: None,
-            },
-            mastodon: PlatformAuth {
-                enabled: false,
-                instance_url: "mastodon.social".to_string(),
-                app_name: "Fedi Sleuth".to_string(),
-                client_id: String::new(),
-                client_secret: String::new(),
-                access_token: None,
-            },
+            pixelfed: PlatformAuth::new_disabled(&home.pixelfed),
+            mastodon: PlatformAuth::new_disabled(&home.mastodon),
             bluesky: BlueskyAuth::default(),
+            saved_accounts: Vec::new(),
+            oauth_redirect_port: None,
         }
     }
+
+    /// Whether the platform is switched on in settings
+    pub fn is_enabled(&self, platform: Platform) -> bool {
+        match platform {
+            Platform::Pixelfed => self.pixelfed.enabled,
+            Platform::Mastodon => self.mastodon.enabled,
+            Platform::Bluesky => self.bluesky.enabled,
+        }
+    }
+
+    /// Enabled with what a search needs to sign in: an OAuth token, or a
+    /// Bluesky session or app password
+    pub fn is_signed_in(&self, platform: Platform) -> bool {
+        match platform {
+            Platform::Pixelfed => self.pixelfed.is_authenticated(),
+            Platform::Mastodon => self.mastodon.is_authenticated(),
+            Platform::Bluesky => {
+                self.bluesky.is_authenticated()
+                    || (self.bluesky.enabled
+                        && !self.bluesky.handle.trim().is_empty()
+                        && !self.bluesky.app_password.trim().is_empty())
+            }
+        }
+    }
+
+    /// Name of the active account; empty until the user names it
+    pub fn active_account_name(&self, platform: Platform) -> &str {
+        match platform {
+            Platform::Pixelfed => &self.pixelfed.account_name,
+            Platform::Mastodon => &self.mastodon.account_name,
+            Platform::Bluesky => &self.bluesky.account_name,
+        }
+    }
+
+    pub fn custom_headers(&self, platform: Platform) -> &[(String, String)] {
+        match platform {
+            Platform::Pixelfed => &self.pixelfed.custom_headers,
+            Platform::Mastodon => &self.mastodon.custom_headers,
+            Platform::Bluesky => &self.bluesky.custom_headers,
+        }
+    }
+
+    pub fn custom_headers_mut(&mut self, platform: Platform) -> &mut Vec<(String, String)> {
+        match platform {
+            Platform::Pixelfed => &mut self.pixelfed.custom_headers,
+            Platform::Mastodon => &mut self.mastodon.custom_headers,
+            Platform::Bluesky => &mut self.bluesky.custom_headers,
+        }
+    }
+
+    fn active_account(&self, platform: Platform) -> AccountAuth {
+        match platform {
+            Platform::Pixelfed => AccountAuth::OAuth(self.pixelfed.clone()),
+            Platform::Mastodon => AccountAuth::OAuth(self.mastodon.clone()),
+            Platform::Bluesky => AccountAuth::Bluesky(self.bluesky.clone()),
+        }
+    }
+
+    /// Names of the stored accounts for `platform`
+    pub fn account_names(&self, platform: Platform) -> Vec<String> {
+        self.saved_accounts
+            .iter()
+            .filter(|account| account.platform == platform)
+            .map(|account| account.name.clone())
+            .collect()
+    }
+
+    /// Name the active account and store a copy of it, replacing any stored
+    /// account with the same name
+    pub fn save_active_account(&mut self, platform: Platform, name: &str) {
+        let name = name.trim();
+        if name.is_empty() {
+            return;
+        }
+        match platform {
+            Platform::Pixelfed => self.pixelfed.account_name = name.to_string(),
+            Platform::Mastodon => self.mastodon.account_name = name.to_string(),
+            Platform::Bluesky => self.bluesky.account_name = name.to_string(),
+        }
+        self.store_active_account(platform);
+    }
+
+    /// Refresh the stored copy of the active account, e.g. after sign-in
+    pub fn store_active_account(&mut self, platform: Platform) {
+        let name = self.active_account_name(platform).to_string();
+        if name.is_empty() {
+            return;
+        }
+        let auth = self.active_account(platform);
+        match self
+            .saved_accounts
+            .iter_mut()
+            .find(|account| account.platform == platform && account.name == name)
+        {
+            Some(account) => account.auth = auth,
+            None => self.saved_accounts.push(SavedAccount {
+                name,
+                platform,
+                auth,
+            }),
+        }
+    }
+
+    /// Make a stored account the active one. The current account's changes
+    /// are stored first so nothing is lost by switching back.
+    pub fn switch_account(&mut self, platform: Platform, name: &str) -> bool {
+        self.store_active_account(platform);
+        let Some(account) = self
+            .saved_accounts
+            .iter()
+            .find(|account| account.platform == platform && account.name == name)
+            .cloned()
+        else {
+            return false;
+        };
+        match (platform, account.auth) {
+            (Platform::Pixelfed, AccountAuth::OAuth(auth)) => self.pixelfed = auth,
+            (Platform::Mastodon, AccountAuth::OAuth(auth)) => self.mastodon = auth,
+            (Platform::Bluesky, AccountAuth::Bluesky(auth)) => self.bluesky = auth,
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn remove_account(&mut self, platform: Platform, name: &str) {
+        self.saved_accounts
+            .retain(|account| !(account.platform == platform && account.name == name));
+    }
+
+    /// Point platforms with no instance URL at the home instances
+    pub fn fill_empty_instances(&mut self, home: &HomeInstances) {
+        if self.pixelfed.instance_url.trim().is_empty() {
+            self.pixelfed.instance_url = home.pixelfed.clone();
+        }
+        if self.mastodon.instance_url.trim().is_empty() {
+            self.mastodon.instance_url = home.mastodon.clone();
+        }
+    }
+}
+
+/// The instances a user calls home, used as the starting instance URL for
+/// new platform configs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HomeInstances {
+    pub pixelfed: String,
+    pub mastodon: String,
+}
+
+impl Default for HomeInstances {
+    fn default() -> Self {
+        Self {
+            pixelfed: "pixelfed.social".to_string(),
+            mastodon: "mastodon.social".to_string(),
+        }
+    }
+}
+
+/// A named account kept for switching
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedAccount {
+    pub name: String,
+    pub platform: Platform,
+    pub auth: AccountAuth,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum AccountAuth {
+    OAuth(PlatformAuth),
+    Bluesky(BlueskyAuth),
 }
 
 /// OAuth 2.0 authentication for Pixelfed and Mastodon
@@ -149,9 +438,33 @@ pub struct PlatformAuth {
     pub client_id: String,
     pub client_secret: String,
     pub access_token: Option<String>,
+    /// Also request the `write` scope. Off by default; searching and
+    /// archiving only ever need read access.
+    #[serde(default)]
+    pub request_write_scope: bool,
+    /// Label for this account when several are saved
+    #[serde(default)]
+    pub account_name: String,
+    /// Extra headers sent with every request to this instance
+    #[serde(default)]
+    pub custom_headers: Vec<(String, String)>,
 }
 
 impl PlatformAuth {
+    pub fn new_disabled(instance_url: &str) -> Self {
+        Self {
+            enabled: false,
+            instance_url: instance_url.to_string(),
+            app_name: "Fedi Sleuth".to_string(),
+            client_id: String::new(),
+            client_secret: String::new(),
+            access_token: None,
+            request_write_scope: false,
+            account_name: String::new(),
+            custom_headers: Vec::new(),
+        }
+    }
+
     pub fn is_authenticated(&self) -> bool {
         self.enabled
             && self.access_token.is_some()
@@ -168,6 +481,12 @@ pub struct BlueskyAuth {
     pub did: Option<String>,
     pub access_jwt: Option<String>,
     pub refresh_jwt: Option<String>,
+    /// Label for this account when several are saved
+    #[serde(default)]
+    pub account_name: String,
+    /// Extra headers sent with every Bluesky request
+    #[serde(default)]
+    pub custom_headers: Vec<(String, String)>,
 }
 
 impl Default for BlueskyAuth {
@@ -179,6 +498,8 @@ impl Default for BlueskyAuth {
             did: None,
             access_jwt: None,
             refresh_jwt: None,
+            account_name: String::new(),
+            custom_headers: Vec::new(),
         }
     }
 }
@@ -198,6 +519,8 @@ pub enum Platform {
 }
 
 impl Platform {
+    pub const ALL: [Platform; 3] = [Platform::Pixelfed, Platform::Mastodon, Platform::Bluesky];
+
     pub fn name(&self) -> &str {
         match self {
             Platform::Pixelfed => "Pixelfed",
@@ -224,91 +547,1088 @@ impl Platform {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DownloadSettings {
     pub base_path: String,
     pub max_concurrent: u32,
     pub organize_by_date: bool,
+    /// Nest each post's files under an author folder inside the platform folder
+    pub organize_by_author: bool,
+    /// Prefix filenames with a sequence number in posting order, oldest first
+    pub sequence_prefix: bool,
+    /// Write each post's text and stats next to its media
+    pub save_metadata_sidecar: bool,
+    pub sidecar_format: SidecarFormat,
+    /// Cut post text in sidecars to this many characters; 0 keeps it all
+    pub max_sidecar_text_chars: u32,
+    /// Download full-size attachments, falling back to previews only when no original exists
+    pub prefer_original_over_preview: bool,
+    /// Stamp the search and source along the bottom of contact sheets
+    pub contact_sheet_watermark: bool,
+    /// Skip media files larger than this many megabytes
+    pub max_file_size_mb: Option<u32>,
+    /// Download identical media once and hard-link the other copies
+    pub dedupe_across_platforms: bool,
+    /// Re-encode downloaded images to this format; `None` keeps the originals
+    pub convert_images_to: Option<ImageFormat>,
+    /// Give up on a connection that sends nothing for this many seconds
+    pub timeout_secs: u32,
+    /// Extra attempts for a file that failed with a network or server error
+    pub retries: u32,
+    /// What to do when a file with the same name is already on disk
+    pub on_collision: CollisionPolicy,
+    /// Folder layout under `base_path`, e.g. `{date}/{query}/{platform}`
+    pub download_path_template: String,
+    /// Abort the whole download when a file fails instead of carrying on
+    pub stop_on_error: bool,
+    /// Ask before downloading this many files or more; 0 never asks
+    pub confirm_above_files: u32,
+    /// Whose folder boosted posts go in when organizing by author
+    pub boost_filing: BoostFiling,
 }
 
 impl Default for DownloadSettings {
     fn default() -> Self {
-        let downloads_dir = dirs::download_dir()
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_else(|| "./downloads".to_string());
-
         Self {
-            base_path: downloads_dir,
+            base_path: default_download_dir(),
             max_concurrent: 3,
             organize_by_date: true,
+            organize_by_author: false,
+            sequence_prefix: false,
+            save_metadata_sidecar: false,
+            sidecar_format: SidecarFormat::Json,
+            max_sidecar_text_chars: 100_000,
+            prefer_original_over_preview: true,
+            contact_sheet_watermark: true,
+            max_file_size_mb: None,
+            dedupe_across_platforms: false,
+            convert_images_to: None,
+            timeout_secs: 30,
+            retries: 2,
+            on_collision: CollisionPolicy::default(),
+            download_path_template: DEFAULT_PATH_TEMPLATE.to_string(),
+            stop_on_error: false,
+            confirm_above_files: 500,
+            boost_filing: BoostFiling::default(),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+/// Where downloads go until the user picks a folder: the OS download
+/// folder, else ~/Downloads, else a folder in the app's data directory.
+/// The working directory, which for a packaged app may be anywhere, is
+/// only the last resort.
+fn default_download_dir() -> String {
+    let candidates = [
+        ("system download folder", dirs::download_dir(), false),
+        (
+            "home Downloads folder",
+            dirs::home_dir().map(|home| home.join("Downloads")),
+            false,
+        ),
+        (
+            "app data folder",
+            dirs::data_dir().map(|data| data.join("pixelfed-rust").join("downloads")),
+            true,
+        ),
+    ];
+
+    for (label, dir, create) in candidates {
+        let Some(dir) = dir else {
+            continue;
+        };
+        if is_writable_dir(&dir, create) {
+            log::info!("Default download folder: {} ({})", dir.display(), label);
+            return dir.to_string_lossy().to_string();
+        }
+        log::debug!(
+            "Skipping {} {}: missing or not writable",
+            label,
+            dir.display()
+        );
+    }
+
+    log::warn!("No writable download folder found; falling back to ./downloads");
+    "./downloads".to_string()
+}
+
+/// Whether files can be written to `dir`, creating it first if `create`
+fn is_writable_dir(dir: &std::path::Path, create: bool) -> bool {
+    if create && std::fs::create_dir_all(dir).is_err() {
+        return false;
+    }
+    if !dir.is_dir() {
+        return false;
+    }
+    let probe = dir.join(".fedi-sleuth-write-test");
+    let writable = std::fs::write(&probe, b"").is_ok();
+    let _ = std::fs::remove_file(&probe);
+    writable
+}
+
+/// The layout downloads have always used: a date folder, one folder per
+/// search, and a folder per platform inside it
+pub const DEFAULT_PATH_TEMPLATE: &str = "{date}/{type}-{query}-{days}-{time}/{platform}";
+
+/// Tokens a download path template may use
+pub const PATH_TEMPLATE_TOKENS: [&str; 6] = [
+    "{date}",
+    "{query}",
+    "{type}",
+    "{platform}",
+    "{days}",
+    "{time}",
+];
+
+impl DownloadSettings {
+    /// Why the path template cannot be used, if it cannot
+    pub fn path_template_problem(&self) -> Option<String> {
+        let template = self.download_path_template.trim();
+        if template.is_empty() {
+            return Some("The template is empty".to_string());
+        }
+        if template.starts_with(['/', '\\']) || template.chars().nth(1) == Some(':') {
+            return Some("Use a relative path; it is placed under the download folder".to_string());
+        }
+
+        let segments: Vec<&str> = template.split(['/', '\\']).collect();
+        if segments.iter().any(|segment| segment.trim() == "..") {
+            return Some("\"..\" would leave the download folder".to_string());
+        }
+
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            let Some(len) = rest[start..].find('}') else {
+                return Some("A { is never closed".to_string());
+            };
+            let token = &rest[start..start + len + 1];
+            if !PATH_TEMPLATE_TOKENS.contains(&token) {
+                return Some(format!("Unknown token {}", token));
+            }
+            rest = &rest[start + len + 1..];
+        }
+
+        // Platform folders sit inside the search folder, so {platform} must
+        // be a folder of its own at the end
+        let (last, folders) = segments.split_last()?;
+        if folders.iter().any(|segment| segment.contains("{platform}"))
+            || (last.contains("{platform}") && last.trim() != "{platform}")
+        {
+            return Some("{platform} can only be the last folder on its own".to_string());
+        }
+        if self.platform_folders() && folders.iter().all(|segment| segment.trim().is_empty()) {
+            return Some("Add at least one folder before {platform}".to_string());
+        }
+        None
+    }
+
+    /// Whether each platform's files go in a folder of their own
+    pub fn platform_folders(&self) -> bool {
+        self.download_path_template
+            .trim()
+            .rsplit(['/', '\\'])
+            .next()
+            .is_some_and(|last| last.trim() == "{platform}")
+    }
+}
+
+/// File format for per-post metadata sidecars
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SidecarFormat {
+    Json,
+    Text,
+}
+
+impl SidecarFormat {
+    pub fn extension(&self) -> &str {
+        match self {
+            SidecarFormat::Json => "json",
+            SidecarFormat::Text => "txt",
+        }
+    }
+}
+
+/// Whose author folder a boosted post is filed under
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum BoostFiling {
+    /// The account that wrote the post
+    #[default]
+    Original,
+    /// The account that boosted it into the results
+    Booster,
+}
+
+impl BoostFiling {
+    pub const ALL: [BoostFiling; 2] = [BoostFiling::Original, BoostFiling::Booster];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            BoostFiling::Original => "original",
+            BoostFiling::Booster => "booster",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|filing| filing.key() == key)
+            .unwrap_or_default()
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BoostFiling::Original => "The original author",
+            BoostFiling::Booster => "The account that boosted it",
+        }
+    }
+}
+
+/// Handling of a download whose filename already exists
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CollisionPolicy {
+    /// Replace the existing file
+    #[default]
+    Overwrite,
+    /// Keep the existing file and skip the download
+    Skip,
+    /// Save under the first free name: `name_1.jpg`, `name_2.jpg`, …
+    Rename,
+}
+
+impl CollisionPolicy {
+    pub const ALL: [CollisionPolicy; 3] = [
+        CollisionPolicy::Overwrite,
+        CollisionPolicy::Skip,
+        CollisionPolicy::Rename,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            CollisionPolicy::Overwrite => "overwrite",
+            CollisionPolicy::Skip => "skip",
+            CollisionPolicy::Rename => "rename",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|policy| policy.key() == key)
+            .unwrap_or_default()
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CollisionPolicy::Overwrite => "Overwrite the existing file",
+            CollisionPolicy::Skip => "Keep the existing file, skip the download",
+            CollisionPolicy::Rename => "Keep both, add _1, _2, … to the new name",
+        }
+    }
+}
+
+/// Target format for converting downloaded images
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ImageFormat {
+    Jpeg,
+    WebP,
+}
+
+impl ImageFormat {
+    pub fn extension(&self) -> &str {
+        match self {
+            ImageFormat::Jpeg => "jpg",
+            ImageFormat::WebP => "webp",
+        }
+    }
+
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "jpg" => Some(ImageFormat::Jpeg),
+            "webp" => Some(ImageFormat::WebP),
+            _ => None,
+        }
+    }
+}
+
+/// Connection reuse for the HTTP clients the services build
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkSettings {
+    /// Idle connections kept open per host for reuse
+    pub pool_max_idle_per_host: usize,
+    /// Seconds an idle pooled connection is kept; 0 keeps it indefinitely
+    pub pool_idle_timeout_secs: u64,
+    /// Seconds between TCP keep-alive probes; 0 turns them off
+    pub tcp_keepalive_secs: u64,
+    /// PEM or DER certificate trusted in addition to the system roots, for
+    /// instances behind a private CA
+    pub custom_ca_path: String,
+    /// Skip TLS certificate checks entirely. Insecure; for local test
+    /// instances with self-signed certificates only.
+    pub accept_invalid_certs: bool,
+    /// Follow HTTP redirects. API calls only ever follow them within the
+    /// same host, so the sign-in token is not sent elsewhere.
+    pub follow_redirects: bool,
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        Self {
+            pool_max_idle_per_host: 8,
+            pool_idle_timeout_secs: 90,
+            tcp_keepalive_secs: 60,
+            custom_ca_path: String::new(),
+            accept_invalid_certs: false,
+            follow_redirects: true,
+        }
+    }
+}
+
+/// Search behaviour shared by all platform services
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchSettings {
+    /// Maximum number of requests allowed in flight against a single instance host
+    pub max_requests_per_instance: u32,
+    /// Authors whose posts are hidden from search results (case-insensitive)
+    pub muted_authors: Vec<String>,
+    /// Hashtags (without `#`) whose posts are hidden from search results
+    pub muted_tags: Vec<String>,
+    /// Which posts Bluesky returns for a user search
+    pub bluesky_author_filter: BlueskyFeedFilter,
+    /// Mastodon user searches skip text-only posts server-side
+    pub mastodon_only_media: bool,
+    /// Post URL searches also fetch the rest of the thread
+    pub include_thread_context: bool,
+    /// How extra tags in a multi-tag hashtag search combine on Mastodon
+    pub tag_combination: TagCombination,
+    /// Courtesy pause in seconds before querying the next platform; 0 for none
+    pub platform_delay_secs: u32,
+    /// How many platforms search at the same time (1–3)
+    pub max_concurrent_platform_searches: u32,
+    /// Most result pages fetched per search before stopping
+    pub max_pages: u32,
+    /// Posts requested per page, clamped to each platform's maximum
+    pub page_size: u32,
+    /// Stop paging after this many pages in a row add no results; 0 never
+    pub max_empty_pages: u32,
+    /// Set per run, never saved: services keep only ids, dates and media
+    /// counts so large windows can be sized up cheaply
+    #[serde(skip)]
+    pub count_only: bool,
+}
+
+impl Default for SearchSettings {
+    fn default() -> Self {
+        Self {
+            max_requests_per_instance: 2,
+            muted_authors: Vec::new(),
+            muted_tags: Vec::new(),
+            bluesky_author_filter: BlueskyFeedFilter::default(),
+            mastodon_only_media: false,
+            include_thread_context: true,
+            tag_combination: TagCombination::default(),
+            platform_delay_secs: 0,
+            max_concurrent_platform_searches: 3,
+            max_pages: 120,
+            page_size: 40,
+            max_empty_pages: 5,
+            count_only: false,
+        }
+    }
+}
+
+/// Combination semantics for the extra tags of a Mastodon tag timeline
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TagCombination {
+    /// Posts with the first tag or any of the others (`any[]`)
+    #[default]
+    Any,
+    /// Posts with every listed tag (`all[]`)
+    All,
+}
+
+impl TagCombination {
+    pub const ALL: [TagCombination; 2] = [TagCombination::Any, TagCombination::All];
+
+    /// Name of the repeated query parameter for the extra tags
+    pub fn api_param(&self) -> &'static str {
+        match self {
+            TagCombination::Any => "any[]",
+            TagCombination::All => "all[]",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TagCombination::Any => "Any of these tags",
+            TagCombination::All => "All of these tags",
+        }
+    }
+
+    pub fn from_api_param(value: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|combination| combination.api_param() == value)
+            .unwrap_or_default()
+    }
+}
+
+/// A hashtag query split into tags, e.g. `#cats #dogs -#nsfw`.
+/// Tags prefixed with `-` are excluded.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TagQuery {
+    pub tags: Vec<String>,
+    pub excluded: Vec<String>,
+}
+
+impl TagQuery {
+    pub fn parse(query: &str) -> Self {
+        let mut parsed = Self::default();
+        for token in query.split(|c: char| c.is_whitespace() || c == ',') {
+            let (excluded, tag) = match token.strip_prefix('-') {
+                Some(rest) => (true, rest),
+                None => (false, token),
+            };
+            let tag = tag.trim_start_matches('#');
+            if tag.is_empty() {
+                continue;
+            }
+            if excluded {
+                parsed.excluded.push(tag.to_string());
+            } else {
+                parsed.tags.push(tag.to_string());
+            }
+        }
+        parsed
+    }
+
+    /// The tag used where only one can be searched
+    pub fn primary(&self) -> Option<&str> {
+        self.tags.first().map(String::as_str)
+    }
+
+    pub fn is_combined(&self) -> bool {
+        self.tags.len() > 1 || !self.excluded.is_empty()
+    }
+
+    /// Whether a post found through the first tag fits the rest of the query,
+    /// judged by the hashtags in its text: none of the excluded tags and,
+    /// with `All`, every other tag too
+    pub fn keeps(&self, result: &SearchResult, combination: TagCombination) -> bool {
+        let tags = result.hashtags();
+        let has = |wanted: &String| tags.iter().any(|tag| tag.eq_ignore_ascii_case(wanted));
+        if self.excluded.iter().any(|tag| has(tag)) {
+            return false;
+        }
+        combination == TagCombination::Any || self.tags.iter().skip(1).all(|tag| has(tag))
+    }
+}
+
+/// Server-side filter for Bluesky's `getAuthorFeed`
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum BlueskyFeedFilter {
+    /// Only posts with images or video; far fewer pages for media archiving
+    #[default]
+    PostsWithMedia,
+    PostsNoReplies,
+    PostsWithReplies,
+    PostsAndAuthorThreads,
+}
+
+impl BlueskyFeedFilter {
+    pub const ALL: [BlueskyFeedFilter; 4] = [
+        BlueskyFeedFilter::PostsWithMedia,
+        BlueskyFeedFilter::PostsNoReplies,
+        BlueskyFeedFilter::PostsWithReplies,
+        BlueskyFeedFilter::PostsAndAuthorThreads,
+    ];
+
+    /// Value of the `filter` query parameter
+    pub fn api_value(&self) -> &'static str {
+        match self {
+            BlueskyFeedFilter::PostsWithMedia => "posts_with_media",
+            BlueskyFeedFilter::PostsNoReplies => "posts_no_replies",
+            BlueskyFeedFilter::PostsWithReplies => "posts_with_replies",
+            BlueskyFeedFilter::PostsAndAuthorThreads => "posts_and_author_threads",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BlueskyFeedFilter::PostsWithMedia => "Posts with media only",
+            BlueskyFeedFilter::PostsNoReplies => "Posts, excluding replies",
+            BlueskyFeedFilter::PostsWithReplies => "Posts and replies",
+            BlueskyFeedFilter::PostsAndAuthorThreads => "Posts and the author's own threads",
+        }
+    }
+
+    pub fn from_api_value(value: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|filter| filter.api_value() == value)
+            .unwrap_or_default()
+    }
+}
+
+impl SearchSettings {
+    /// Add an author to the mute list. Returns false if already muted.
+    pub fn mute_author(&mut self, author: &str) -> bool {
+        let author = author.trim();
+        if author.is_empty()
+            || self
+                .muted_authors
+                .iter()
+                .any(|muted| muted.eq_ignore_ascii_case(author))
+        {
+            return false;
+        }
+        self.muted_authors.push(author.to_string());
+        true
+    }
+
+    /// Add a hashtag to the mute list. Returns false if already muted.
+    pub fn mute_tag(&mut self, tag: &str) -> bool {
+        let tag = tag.trim().trim_start_matches('#');
+        if tag.is_empty()
+            || self
+                .muted_tags
+                .iter()
+                .any(|muted| muted.eq_ignore_ascii_case(tag))
+        {
+            return false;
+        }
+        self.muted_tags.push(tag.to_string());
+        true
+    }
+
+    /// Whether a result matches a muted author or carries a muted hashtag
+    pub fn is_muted(&self, result: &SearchResult) -> bool {
+        if self
+            .muted_authors
+            .iter()
+            .any(|muted| muted.eq_ignore_ascii_case(result.author.trim()))
+        {
+            return true;
+        }
+
+        if self.muted_tags.is_empty() {
+            return false;
+        }
+
+        result.hashtags().iter().any(|tag| {
+            self.muted_tags
+                .iter()
+                .any(|muted| muted.eq_ignore_ascii_case(tag))
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SearchType {
     User,
     Hashtag,
+    /// The authenticated account's bookmarked posts (query is ignored)
+    Bookmarks,
+    /// The authenticated account's favourited posts (query is ignored)
+    Favourites,
+    /// A single post, and optionally its thread, addressed by its web URL
+    PostUrl,
+    /// A Bluesky custom feed (feed generator), addressed by its AT-URI
+    Feed,
+    /// Posts trending on a Pixelfed instance (query is ignored)
+    Trending,
 }
 
 impl SearchType {
+    /// Whether this search type uses the query text
+    pub fn needs_query(&self) -> bool {
+        matches!(
+            self,
+            SearchType::User | SearchType::Hashtag | SearchType::PostUrl | SearchType::Feed
+        )
+    }
+
+    /// Why `query` cannot be searched with this type, if it cannot. A lone
+    /// `#` or `@` counts as empty.
+    pub fn query_problem(&self, query: &str) -> Option<&'static str> {
+        if !self.needs_query() {
+            return None;
+        }
+        let trimmed = query.trim();
+        if trimmed.is_empty() {
+            return Some("Please enter a search query");
+        }
+        match self {
+            SearchType::Hashtag if TagQuery::parse(trimmed).primary().is_none() => {
+                Some("Enter a hashtag after the #")
+            }
+            SearchType::User if trimmed.trim_matches('@').trim().is_empty() => {
+                Some("Enter a username after the @")
+            }
+            _ => None,
+        }
+    }
+
+    /// Short identifier used in folder names
+    pub fn slug(&self) -> &str {
+        match self {
+            SearchType::User => "user",
+            SearchType::Hashtag => "hashtag",
+            SearchType::Bookmarks => "bookmarks",
+            SearchType::Favourites => "favourites",
+            SearchType::PostUrl => "post",
+            SearchType::Feed => "feed",
+            SearchType::Trending => "trending",
+        }
+    }
+
     pub fn get_folder_prefix(&self, query: &str) -> String {
         match self {
             SearchType::User => {
-                // Remove @ symbols and clean username
-                query.trim_start_matches('@').replace('@', "_at_")
+                // Remove @ symbols; DIDs and other separators become underscores
+                sanitize_path_component(&query.trim_start_matches('@').replace('@', "_at_"))
             }
             SearchType::Hashtag => {
-                // Remove # symbol
-                query.trim_start_matches('#').to_string()
+                // Remove # symbols; combined tags become "cats+dogs"
+                let tags = TagQuery::parse(query).tags;
+                if tags.is_empty() {
+                    query.trim_start_matches('#').to_string()
+                } else {
+                    tags.join("+")
+                }
+            }
+            SearchType::PostUrl => {
+                // Keep the host and the trailing id, e.g. "mastodon.social_109876"
+                let trimmed = query
+                    .trim()
+                    .trim_start_matches("https://")
+                    .trim_start_matches("http://")
+                    .trim_end_matches('/');
+                let host = trimmed.split('/').next().unwrap_or("");
+                let id = trimmed.rsplit('/').next().unwrap_or("");
+                sanitize_path_component(&format!("{}_{}", host, id))
+            }
+            SearchType::Feed => {
+                // The feed's record key is its short name, e.g. "photography"
+                let name = query.trim().trim_end_matches('/').rsplit('/').next();
+                sanitize_path_component(name.unwrap_or("feed"))
+            }
+            SearchType::Bookmarks | SearchType::Favourites | SearchType::Trending => {
+                self.slug().to_string()
             }
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// An account's bio and counts as they were when it was searched, kept
+/// with its posts for the archival record
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountProfile {
+    pub handle: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub followers: u64,
+    pub following: u64,
+    pub posts: u64,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// A poll on a Mastodon or Pixelfed post; the question is the post text
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Poll {
+    #[serde(default, deserialize_with = "lenient")]
+    pub options: Vec<PollOption>,
+    /// Votes cast; null while an instance hides counts until the poll ends
+    #[serde(default, deserialize_with = "lenient")]
+    pub votes_count: Option<u64>,
+    /// People who voted, which differs from votes on multiple-choice polls
+    #[serde(default, deserialize_with = "lenient")]
+    pub voters_count: Option<u64>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub multiple: bool,
+    #[serde(default, deserialize_with = "lenient")]
+    pub expires_at: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub expired: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PollOption {
+    #[serde(default, deserialize_with = "lenient")]
+    pub title: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub votes_count: Option<u64>,
+}
+
+impl Poll {
+    /// Share of the vote for `option`, 0-100, when the counts are public.
+    /// Multiple-choice polls are measured against voters, as Mastodon does.
+    pub fn percent(&self, option: &PollOption) -> Option<u64> {
+        let votes = option.votes_count?;
+        let total = if self.multiple {
+            self.voters_count.or(self.votes_count)?
+        } else {
+            self.votes_count?
+        };
+        Some(if total == 0 { 0 } else { votes * 100 / total })
+    }
+
+    /// e.g. "42 votes · closed"
+    pub fn summary(&self) -> String {
+        let votes = match self.votes_count {
+            Some(1) => "1 vote".to_string(),
+            Some(count) => format!("{} votes", count),
+            None => "Votes hidden until the poll ends".to_string(),
+        };
+        let state = if self.expired { "closed" } else { "open" };
+        if self.multiple {
+            format!("{} · {} · multiple choice", votes, state)
+        } else {
+            format!("{} · {}", votes, state)
+        }
+    }
+}
+
+/// One attachment of a post
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MediaItem {
+    /// The file to download, or the page for link cards
+    pub url: String,
+    /// As the platform reports it: "image", "video", "gifv", "external", …
+    pub media_type: String,
+    /// Alt text
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub width: Option<u32>,
+    #[serde(default)]
+    pub height: Option<u32>,
+    /// A smaller rendition, when the platform provides one
+    #[serde(default)]
+    pub preview_url: Option<String>,
+}
+
+impl MediaItem {
+    pub fn is_video(&self) -> bool {
+        matches!(self.media_type.as_str(), "video" | "gifv")
+    }
+
+    /// A link card rather than a file
+    pub fn is_link(&self) -> bool {
+        self.media_type == "external"
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SearchResult {
     pub platform: Platform,
     pub id: String,
     pub author: String,
     pub content: String,
     pub created_at: DateTime<Utc>,
-    pub media_urls: Vec<String>,
-    pub media_types: Vec<String>,
-    pub media_count: u32,
+    /// Attachments; the count is always `media.len()`. Older exports also
+    /// carry a `media_count`, which is ignored.
+    #[serde(default)]
+    pub media: Vec<MediaItem>,
     pub likes: u32,
     pub shares: u32,
     pub url: String,
+    /// The author's content warning (spoiler text), if any
+    #[serde(default)]
+    pub content_warning: Option<String>,
+    /// Who boosted (reblogged, reposted) the post into the results, when it
+    /// arrived as a boost; `author` is then the original author
+    #[serde(default)]
+    pub reblogged_by: Option<String>,
+    /// The searched account's profile, on user searches that fetch it
+    #[serde(default)]
+    pub account_profile: Option<AccountProfile>,
+    /// The post's poll, with the vote counts at the time of the search
+    #[serde(default)]
+    pub poll: Option<Poll>,
 }
 
+impl SearchResult {
+    /// Result for a count-only search: no author, text or media URLs. The
+    /// attachments keep only their kind, for the counts.
+    pub fn count_stub(
+        platform: Platform,
+        id: String,
+        created_at: DateTime<Utc>,
+        media: &[MediaItem],
+    ) -> Self {
+        Self {
+            platform,
+            id,
+            author: String::new(),
+            content: String::new(),
+            created_at,
+            media: media
+                .iter()
+                .map(|item| MediaItem {
+                    media_type: item.media_type.clone(),
+                    ..MediaItem::default()
+                })
+                .collect(),
+            likes: 0,
+            shares: 0,
+            url: String::new(),
+            content_warning: None,
+            reblogged_by: None,
+            account_profile: None,
+            poll: None,
+        }
+    }
+
+    /// The name a post is filed under when organizing by author
+    pub fn filing_author(&self, filing: BoostFiling) -> &str {
+        match (&self.reblogged_by, filing) {
+            (Some(booster), BoostFiling::Booster) => booster,
+            _ => &self.author,
+        }
+    }
+
+    /// Hashtags mentioned in the post text, without the leading `#`
+    pub fn hashtags(&self) -> Vec<String> {
+        // Runs for every result on every render, so compiled once
+        static HASHTAG: OnceLock<regex::Regex> = OnceLock::new();
+        let re = HASHTAG.get_or_init(|| regex::Regex::new(r"#(\w+)").unwrap());
+        let mut tags: Vec<String> = Vec::new();
+        for capture in re.captures_iter(&self.content) {
+            let tag = capture[1].to_string();
+            if !tags
+                .iter()
+                .any(|existing| existing.eq_ignore_ascii_case(&tag))
+            {
+                tags.push(tag);
+            }
+        }
+        tags
+    }
+
+    /// Attachment counts by kind
+    pub fn media_breakdown(&self) -> MediaBreakdown {
+        let mut breakdown = MediaBreakdown::default();
+        for item in &self.media {
+            if item.is_video() {
+                breakdown.videos += 1;
+            } else if item.is_link() {
+                breakdown.links += 1;
+            } else {
+                breakdown.images += 1;
+            }
+        }
+        breakdown
+    }
+}
+
+/// How many of a post's attachments are images, videos and link cards
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MediaBreakdown {
+    pub images: usize,
+    pub videos: usize,
+    pub links: usize,
+}
+
+impl MediaBreakdown {
+    pub fn is_empty(&self) -> bool {
+        self.images + self.videos + self.links == 0
+    }
+}
+
+/// Read a field that other server software may send as null or with a
+/// different type, falling back to the default instead of failing the post
+fn lenient<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned + Default,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(serde_json::from_value(value).unwrap_or_default())
+}
+
+/// Read an id sent as a string or, by some servers, as a number
+fn string_id<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(id) => Ok(id),
+        serde_json::Value::Number(id) => Ok(id.to_string()),
+        other => Err(serde::de::Error::custom(format!(
+            "expected a string or numeric id, got {}",
+            other
+        ))),
+    }
+}
+
+/// A status from the Mastodon-compatible API. Pixelfed, Mastodon, Pleroma,
+/// Akkoma and GoToSocial all send this shape with small differences, so
+/// every field except the id and account tolerates nulls and type changes.
+/// Unknown fields (e.g. Pleroma's `pleroma` object) are ignored.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PixelfedPost {
+    #[serde(deserialize_with = "string_id")]
     pub id: String,
     pub account: PixelfedAccount,
+    #[serde(default, deserialize_with = "lenient")]
     pub content: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
     pub created_at: Option<String>,
-    #[serde(default)]
+    #[serde(default, deserialize_with = "lenient")]
     pub media_attachments: Vec<PixelfedMediaAttachment>,
-    #[serde(default)]
+    #[serde(default, deserialize_with = "lenient")]
     pub favourites_count: Option<u32>,
-    #[serde(default)]
+    #[serde(default, deserialize_with = "lenient")]
     pub reblogs_count: Option<u32>,
+    #[serde(default, deserialize_with = "lenient")]
     pub url: Option<String>,
+    /// Content warning text; empty when the post has none
+    #[serde(default, deserialize_with = "lenient")]
+    pub spoiler_text: Option<String>,
+    /// The original post when this status is a boost of it
+    #[serde(default, deserialize_with = "lenient")]
+    pub reblog: Option<Box<PixelfedPost>>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub poll: Option<Poll>,
+}
+
+impl PixelfedPost {
+    /// Parse a page of statuses one by one, so a single status in a shape
+    /// this app does not understand is skipped instead of failing the page
+    pub fn parse_page(values: Vec<serde_json::Value>) -> Vec<PixelfedPost> {
+        values
+            .into_iter()
+            .filter_map(|value| match serde_json::from_value(value) {
+                Ok(post) => Some(post),
+                Err(err) => {
+                    log::warn!("Skipping a status that could not be read: {}", err);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// The content warning, if the author set one
+    pub fn content_warning(&self) -> Option<String> {
+        self.spoiler_text
+            .as_deref()
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .map(str::to_string)
+    }
+}
+
+/// Response of Mastodon's `/api/v1/statuses/{id}/context`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusContext {
+    #[serde(default)]
+    pub ancestors: Vec<PixelfedPost>,
+    #[serde(default)]
+    pub descendants: Vec<PixelfedPost>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PixelfedAccount {
+    #[serde(deserialize_with = "string_id")]
     pub id: String,
+    #[serde(default, deserialize_with = "lenient")]
     pub username: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
     pub display_name: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
     pub url: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PixelfedMediaAttachment {
+    /// Some servers leave remote attachments without an id
+    #[serde(default, deserialize_with = "lenient_id")]
     pub id: String,
+    #[serde(default, deserialize_with = "lenient")]
     pub r#type: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
     pub url: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
     pub preview_url: Option<String>,
+    /// Alt text
+    #[serde(default, deserialize_with = "lenient")]
+    pub description: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub meta: Option<AttachmentMeta>,
+}
+
+/// The `meta` object of an attachment; only the original size is used
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AttachmentMeta {
+    #[serde(default, deserialize_with = "lenient")]
+    pub original: Option<AttachmentSize>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AttachmentSize {
+    #[serde(default, deserialize_with = "lenient")]
+    pub width: Option<u32>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub height: Option<u32>,
+}
+
+/// `string_id` that falls back to an empty id instead of failing
+fn lenient_id<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(string_id(value).unwrap_or_default())
+}
+
+impl PixelfedMediaAttachment {
+    /// This attachment as a result's media item, downloading `url`
+    pub fn to_media_item(&self, url: &str) -> MediaItem {
+        let size = self.meta.as_ref().and_then(|meta| meta.original.as_ref());
+        MediaItem {
+            url: url.to_string(),
+            media_type: self.r#type.clone().unwrap_or_default(),
+            description: self
+                .description
+                .as_deref()
+                .map(str::trim)
+                .filter(|text| !text.is_empty())
+                .map(str::to_string),
+            width: size.and_then(|size| size.width),
+            height: size.and_then(|size| size.height),
+            preview_url: self
+                .preview_url
+                .as_deref()
+                .map(str::trim)
+                .filter(|preview| !preview.is_empty() && *preview != url)
+                .map(str::to_string),
+        }
+    }
+
+    /// Pick the URL to download, falling back to the other variant when the
+    /// preferred one is missing or empty
+    pub fn download_url(&self, prefer_original: bool) -> Option<&str> {
+        let original = self
+            .url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty());
+        let preview = self
+            .preview_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty());
+
+        if prefer_original {
+            original.or(preview)
+        } else {
+            preview.or(original)
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -329,6 +1649,8 @@ pub struct SearchContext {
     pub query: String,
     pub search_type: SearchType,
     pub days_back: u32,
+    /// Lowercased phrases; posts whose content contains one are dropped
+    pub exclude_terms: Vec<String>,
 }
 
 impl SearchContext {
@@ -337,7 +1659,29 @@ impl SearchContext {
             query,
             search_type,
             days_back,
+            exclude_terms: Vec::new(),
+        }
+    }
+
+    /// Drop posts containing any phrase of a comma-separated list
+    pub fn with_exclusions(mut self, list: &str) -> Self {
+        self.exclude_terms = list
+            .split(',')
+            .map(|term| term.trim().to_lowercase())
+            .filter(|term| !term.is_empty())
+            .collect();
+        self
+    }
+
+    /// Whether a result's content contains an excluded phrase, ignoring case
+    pub fn excludes(&self, result: &SearchResult) -> bool {
+        if self.exclude_terms.is_empty() {
+            return false;
         }
+        let content = result.content.to_lowercase();
+        self.exclude_terms
+            .iter()
+            .any(|term| content.contains(term.as_str()))
     }
 
     pub fn get_folder_name(&self) -> String {
@@ -345,6 +1689,99 @@ impl SearchContext {
     }
 }
 
+/// How a platform search narrowed what it fetched down to its results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    /// API pages requested
+    pub pages: u32,
+    /// Posts the API returned, before any filtering
+    pub seen: usize,
+    /// Posts inside the date range that matched the search
+    pub matched: usize,
+    /// Posts left after the mute list and exclusion filter
+    pub kept: usize,
+    /// HTTP requests sent, retries included
+    pub requests: u32,
+    /// Time spent waiting on those requests, in milliseconds
+    pub request_ms: u64,
+    /// Wall-clock time of the whole platform search, in milliseconds
+    pub elapsed_ms: u64,
+}
+
+impl SearchStats {
+    /// Mean time per request, in milliseconds
+    pub fn average_request_ms(&self) -> Option<u64> {
+        (self.requests > 0).then(|| self.request_ms / self.requests as u64)
+    }
+}
+
+/// Posts returned by one platform search
+#[derive(Debug, Clone, Default)]
+pub struct SearchOutcome {
+    pub results: Vec<SearchResult>,
+    /// Set when pagination hit an error after some pages had already been
+    /// fetched; `results` holds everything collected before it
+    pub stopped_early: Option<String>,
+    /// Pages and raw posts fetched; `matched` and `kept` are filled in by
+    /// the caller
+    pub stats: SearchStats,
+    /// Shown with the results, e.g. when the searched account is private
+    pub notice: Option<String>,
+}
+
+impl SearchOutcome {
+    pub fn complete(results: Vec<SearchResult>) -> Self {
+        Self {
+            results,
+            stopped_early: None,
+            stats: SearchStats::default(),
+            notice: None,
+        }
+    }
+
+    pub fn partial(results: Vec<SearchResult>, reason: String) -> Self {
+        Self {
+            results,
+            stopped_early: Some(reason),
+            stats: SearchStats::default(),
+            notice: None,
+        }
+    }
+
+    /// Record one request that returned `seen` posts, for searches that
+    /// do not page
+    pub fn single_page(mut self, seen: usize) -> Self {
+        self.stats.pages = 1;
+        self.stats.seen = seen;
+        self
+    }
+
+    /// Results cut short by the page limit in Settings
+    pub fn page_limit(results: Vec<SearchResult>, max_pages: u32) -> Self {
+        Self::partial(
+            results,
+            format!("Stopped at the {}-page limit set in Settings", max_pages),
+        )
+    }
+
+    /// Results cut short because paging stopped turning up posts
+    pub fn unproductive(results: Vec<SearchResult>, empty_pages: u32) -> Self {
+        Self::partial(
+            results,
+            format!(
+                "Stopped after {} pages in a row with no matching posts",
+                empty_pages
+            ),
+        )
+    }
+}
+
+impl From<Vec<SearchResult>> for SearchOutcome {
+    fn from(results: Vec<SearchResult>) -> Self {
+        Self::complete(results)
+    }
+}
+
 /// Results grouped by platform
 #[derive(Debug, Clone)]
 pub struct PlatformSearchResults {
@@ -352,6 +1789,14 @@ pub struct PlatformSearchResults {
     pub label: String,
     pub results: Vec<SearchResult>,
     pub error: Option<String>,
+    /// Why pagination stopped early, when the results are incomplete
+    pub partial_error: Option<String>,
+    /// Ids not returned by the previous run of the same search
+    pub new_ids: HashSet<String>,
+    /// What the search fetched and filtered, when it ran
+    pub stats: Option<SearchStats>,
+    /// Caveat about the results from the platform search
+    pub notice: Option<String>,
 }
 
 impl PlatformSearchResults {
@@ -361,6 +1806,10 @@ impl PlatformSearchResults {
             label,
             results,
             error: None,
+            partial_error: None,
+            new_ids: HashSet::new(),
+            stats: None,
+            notice: None,
         }
     }
 
@@ -370,9 +1819,17 @@ impl PlatformSearchResults {
             label,
             results: Vec::new(),
             error: Some(error),
+            partial_error: None,
+            new_ids: HashSet::new(),
+            stats: None,
+            notice: None,
         }
     }
 
+    pub fn is_new(&self, id: &str) -> bool {
+        self.new_ids.contains(id)
+    }
+
     pub fn is_success(&self) -> bool {
         self.error.is_none()
     }
@@ -381,3 +1838,161 @@ impl PlatformSearchResults {
         self.results.len()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A status as Pleroma 2.5 sends it, with its `pleroma` extension objects
+    /// and an attachment that has no `meta`
+    const PLEROMA_STATUS: &str = r#"{
+        "id": "AR8mNk5rYqWZhpJpVI",
+        "uri": "https://pleroma.example/objects/8b0c5a7e-2f6d-4c55-9a43-1b7d3f6c2a10",
+        "url": "https://pleroma.example/notice/AR8mNk5rYqWZhpJpVI",
+        "account": {
+            "id": "9vHbQ2hZ5ZbLqXq7Gq",
+            "username": "lain",
+            "acct": "lain",
+            "display_name": "lain",
+            "url": "https://pleroma.example/users/lain",
+            "pleroma": { "is_admin": true, "relationship": {} }
+        },
+        "content": "<p>new <a href=\"https://pleroma.example/tag/photo\">#photo</a></p>",
+        "created_at": "2023-01-05T10:00:00.000Z",
+        "in_reply_to_id": null,
+        "reblog": null,
+        "reblogs_count": 2,
+        "favourites_count": 5,
+        "replies_count": 0,
+        "sensitive": false,
+        "spoiler_text": "",
+        "visibility": "public",
+        "media_attachments": [
+            {
+                "id": "1092384756",
+                "type": "image",
+                "url": "https://pleroma.example/media/3f2a.jpg",
+                "remote_url": "https://pleroma.example/media/3f2a.jpg",
+                "preview_url": "https://pleroma.example/media/3f2a.jpg",
+                "text_url": "https://pleroma.example/media/3f2a.jpg",
+                "description": "a cat",
+                "pleroma": { "mime_type": "image/jpeg" }
+            }
+        ],
+        "mentions": [],
+        "tags": [{ "name": "photo", "url": "https://pleroma.example/tag/photo" }],
+        "emojis": [],
+        "card": null,
+        "poll": null,
+        "pleroma": {
+            "local": true,
+            "conversation_id": 1234,
+            "content": { "text/plain": "new #photo" },
+            "spoiler_text": { "text/plain": "" },
+            "expires_at": null,
+            "emoji_reactions": [{ "name": "👍", "count": 1, "me": false }]
+        }
+    }"#;
+
+    /// An Akkoma repeat: the wrapper has an empty content and the original
+    /// post, with a numeric attachment id and string counts, sits in `reblog`
+    const AKKOMA_REPEAT: &str = r#"{
+        "id": "AR9xTzPq0lSeBq2Qxs",
+        "url": "https://akkoma.example/notice/AR9xTzPq0lSeBq2Qxs",
+        "account": { "id": "AR9wkPHqtt4C7Slnsy", "username": "booster", "acct": "booster" },
+        "content": "",
+        "created_at": "2023-02-10T08:30:00.000Z",
+        "reblogs_count": 0,
+        "favourites_count": 0,
+        "spoiler_text": "",
+        "media_attachments": [],
+        "akkoma": { "source": null },
+        "pleroma": { "local": true, "emoji_reactions": [] },
+        "reblog": {
+            "id": "AR9xQ4GxHvTqvM6R4S",
+            "url": "https://remote.example/notice/AR9xQ4GxHvTqvM6R4S",
+            "account": { "id": "AR9xQ2nGk8aVvB1Hjs", "username": "artist", "acct": "artist@remote.example" },
+            "content": "<p>sketch</p>",
+            "created_at": "2023-02-09T22:15:00.000Z",
+            "reblogs_count": "3",
+            "favourites_count": null,
+            "spoiler_text": "wip",
+            "media_attachments": [
+                {
+                    "id": 7788,
+                    "type": "image",
+                    "url": "https://remote.example/media/sketch.png",
+                    "preview_url": null,
+                    "description": null,
+                    "meta": { "original": { "width": 1200, "height": "800" } }
+                }
+            ],
+            "poll": {
+                "id": "poll1",
+                "options": [{ "title": "yes", "votes_count": 4 }, { "title": "no", "votes_count": null }],
+                "votes_count": 4,
+                "voters_count": null,
+                "multiple": false,
+                "expires_at": null,
+                "expired": false
+            },
+            "pleroma": { "local": false }
+        }
+    }"#;
+
+    #[test]
+    fn pleroma_status_deserializes() {
+        let post: PixelfedPost = serde_json::from_str(PLEROMA_STATUS).unwrap();
+
+        assert_eq!(post.id, "AR8mNk5rYqWZhpJpVI");
+        assert_eq!(post.account.username.as_deref(), Some("lain"));
+        assert_eq!(post.favourites_count, Some(5));
+        assert_eq!(post.reblogs_count, Some(2));
+        assert!(post.reblog.is_none());
+        assert!(post.poll.is_none());
+        assert_eq!(post.content_warning(), None);
+
+        let attachment = &post.media_attachments[0];
+        assert_eq!(attachment.id, "1092384756");
+        assert_eq!(attachment.r#type.as_deref(), Some("image"));
+        assert_eq!(attachment.description.as_deref(), Some("a cat"));
+        assert!(attachment.meta.is_none());
+    }
+
+    #[test]
+    fn akkoma_repeat_deserializes_with_its_original() {
+        let post: PixelfedPost = serde_json::from_str(AKKOMA_REPEAT).unwrap();
+        assert_eq!(post.account.username.as_deref(), Some("booster"));
+
+        let original = post.reblog.as_deref().unwrap();
+        assert_eq!(original.id, "AR9xQ4GxHvTqvM6R4S");
+        // Type changes fall back to empty rather than failing the status
+        assert_eq!(original.reblogs_count, None);
+        assert_eq!(original.favourites_count, None);
+        assert_eq!(original.content_warning().as_deref(), Some("wip"));
+
+        let attachment = &original.media_attachments[0];
+        assert_eq!(attachment.id, "7788");
+        assert_eq!(attachment.preview_url, None);
+        let size = attachment.meta.as_ref().unwrap().original.as_ref().unwrap();
+        assert_eq!(size.width, Some(1200));
+        assert_eq!(size.height, None);
+
+        let poll = original.poll.as_ref().unwrap();
+        assert_eq!(poll.options.len(), 2);
+        assert_eq!(poll.options[1].votes_count, None);
+    }
+
+    #[test]
+    fn page_with_an_unreadable_status_keeps_the_rest() {
+        let values = vec![
+            serde_json::from_str(PLEROMA_STATUS).unwrap(),
+            serde_json::json!({ "id": "no-account" }),
+            serde_json::from_str(AKKOMA_REPEAT).unwrap(),
+        ];
+
+        let posts = PixelfedPost::parse_page(values);
+        assert_eq!(posts.len(), 2);
+        assert_eq!(posts[1].id, "AR9xTzPq0lSeBq2Qxs");
+    }
+}
@@ -9,7 +9,27 @@ use serde_json::Value;
 use std::collections::HashMap;
 use url::Url;
 
-use crate::models::PlatformAuth;
+use crate::models::{NetworkSettings, Platform, PlatformAuth};
+use crate::services::http_util::{check_challenge, client_builder, instance_headers, parse_json};
+
+/// Scopes requested when no platform-specific set is given
+pub const DEFAULT_SCOPES: &str = "read";
+
+/// Space-separated OAuth scopes requested for a platform.
+///
+/// Read access is always requested; `write` is only added when the user
+/// opts in. Mastodon also gets the granular bookmarks/favourites scopes.
+pub fn oauth_scopes(platform: Platform, request_write: bool) -> String {
+    let mut scopes = vec![DEFAULT_SCOPES];
+    if platform == Platform::Mastodon {
+        scopes.push("read:bookmarks");
+        scopes.push("read:favourites");
+    }
+    if request_write {
+        scopes.push("write");
+    }
+    scopes.join(" ")
+}
 
 pub struct AuthService {
     client: Option<BasicClient>,
@@ -17,6 +37,7 @@ pub struct AuthService {
     platform_auth: PlatformAuth,
     instance_url: String,
     redirect_uri: String,
+    scopes: String,
 }
 
 #[allow(dead_code)]
@@ -55,13 +76,30 @@ impl AuthService {
 
         Ok(Self {
             client,
-            http_client: Client::new(),
+            http_client: Client::builder()
+                .default_headers(instance_headers(&platform_auth.custom_headers))
+                .build()?,
             platform_auth,
             instance_url: instance_url.to_string(),
             redirect_uri: redirect_uri.to_string(),
+            scopes: DEFAULT_SCOPES.to_string(),
         })
     }
 
+    /// Apply the connection and certificate settings to the instance requests
+    pub fn with_network(mut self, network: &NetworkSettings) -> Result<Self> {
+        self.http_client = client_builder(network)
+            .default_headers(instance_headers(&self.platform_auth.custom_headers))
+            .build()?;
+        Ok(self)
+    }
+
+    /// Override the space-separated scopes used for registration and authorization
+    pub fn with_scopes(mut self, scopes: &str) -> Self {
+        self.scopes = scopes.trim().to_string();
+        self
+    }
+
     /// Register a new OAuth application with the Pixelfed instance
     pub async fn register_app(&self, app_name: &str) -> Result<(String, String)> {
         let url = format!("{}/api/v1/apps", self.instance_url);
@@ -69,7 +107,7 @@ impl AuthService {
         let mut params = HashMap::new();
         params.insert("client_name", app_name);
         params.insert("redirect_uris", self.redirect_uri.as_str());
-        params.insert("scopes", "read write");
+        params.insert("scopes", self.scopes.as_str());
         params.insert("website", "https://github.com/pixelfed/rust-client");
 
         let response = self
@@ -79,6 +117,7 @@ impl AuthService {
             .header("User-Agent", "PixelfedRustClient/1.0")
             .send()
             .await?;
+        let response = check_challenge(response)?;
 
         if !response.status().is_success() {
             return Err(anyhow::anyhow!(
@@ -87,7 +126,7 @@ impl AuthService {
             ));
         }
 
-        let app_data: Value = response.json().await?;
+        let app_data: Value = parse_json(response, "app registration").await?;
 
         let client_id = app_data["client_id"]
             .as_str()
@@ -113,6 +152,7 @@ impl AuthService {
             .header("User-Agent", "PixelfedRustClient/1.0")
             .send()
             .await?;
+        let response = check_challenge(response)?;
 
         if !response.status().is_success() {
             return Ok(false); // Assume no OAuth support if instance endpoint fails
@@ -133,6 +173,7 @@ impl AuthService {
             .header("User-Agent", "PixelfedRustClient/1.0")
             .send()
             .await?;
+        let response = check_challenge(response)?;
 
         if !response.status().is_success() {
             return Err(anyhow::anyhow!(
@@ -141,7 +182,7 @@ impl AuthService {
             ));
         }
 
-        let user_data: Value = response.json().await?;
+        let user_data: Value = parse_json(response, "account credentials").await?;
         Ok(user_data)
     }
 
@@ -153,8 +194,11 @@ impl AuthService {
 
         let (auth_url, csrf_token) = client
             .authorize_url(CsrfToken::new_random)
-            .add_scope(Scope::new("read".to_string()))
-            .add_scope(Scope::new("write".to_string()))
+            .add_scopes(
+                self.scopes
+                    .split_whitespace()
+                    .map(|scope| Scope::new(scope.to_string())),
+            )
             .url();
 
         Ok((auth_url, csrf_token))
@@ -191,6 +235,7 @@ impl AuthService {
             .header("User-Agent", "Fedi-Sleuth/1.0")
             .send()
             .await?;
+        let response = check_challenge(response)?;
 
         let status = response.status();
 
@@ -203,7 +248,7 @@ impl AuthService {
             ));
         }
 
-        let token_data: Value = response.json().await?;
+        let token_data: Value = parse_json(response, "token response").await?;
         let access_token = token_data
             .get("access_token")
             .and_then(|value| value.as_str())
@@ -232,6 +277,7 @@ impl AuthService {
             .header("User-Agent", "Fedi-Sleuth/1.0")
             .send()
             .await?;
+        let response = check_challenge(response)?;
 
         if !response.status().is_success() {
             return Err(anyhow::anyhow!(
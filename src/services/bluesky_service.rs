@@ -3,34 +3,56 @@
 // ============================================================================
 // Provides search capabilities against the Bluesky social network using the
 // SocialPlatform trait interface. Authenticates with app passwords and calls
-// the official ATProto endpoints (createSession, getAuthorFeed, searchPosts).
+// the official ATProto endpoints (createSession, getAuthorFeed, getFeed,
+// searchPosts).
 // ============================================================================
 
+use std::sync::atomic::{AtomicI64, Ordering};
 use std::time::Duration as StdDuration;
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use async_trait::async_trait;
 use chrono::{DateTime, Duration, Utc};
 use reqwest::{Client, Response};
 use serde::Deserialize;
 use serde_json::Value;
-use tokio::time::{sleep, Duration as TokioDuration};
 
-use crate::models::{AppSettings, BlueskyAuth, Platform, SearchResult};
-use crate::services::SocialPlatform;
+use crate::models::{
+    AccountProfile, AppSettings, BlueskyAuth, BlueskyFeedFilter, MediaItem, Platform,
+    SearchOutcome, SearchResult, TagCombination, TagQuery,
+};
+use crate::services::http_util::{
+    api_client_builder, custom_headers, parse_json, send_and_parse, send_checked, PageSize,
+    SearchError,
+};
+use crate::services::paginator::{paginate, Page, PageLimits};
+use crate::services::{search_tags_separately, HostLimiter, SocialPlatform};
+use crate::utils::{cap_content, parse_post_date};
 
 const BLUESKY_API_BASE: &str = "https://bsky.social";
 const BLUESKY_WEB_BASE: &str = "https://bsky.app";
+/// Feed and search endpoints allow up to 100 posts per page
+const MAX_PAGE_SIZE: u32 = 100;
+/// Longest wait for a rate-limit window; a longer reset is treated as bogus
+const MAX_RATE_LIMIT_WAIT: StdDuration = StdDuration::from_secs(300);
 
 pub struct BlueskyService {
     client: Client,
     auth: BlueskyAuth,
+    requests_per_instance: u32,
+    author_filter: BlueskyFeedFilter,
+    count_only: bool,
+    page_limits: PageLimits,
+    page_size: PageSize,
+    tag_combination: TagCombination,
+    /// Unix time the exhausted rate-limit window resets; 0 when not limited
+    rate_limit_reset: AtomicI64,
 }
 
 impl BlueskyService {
     pub fn new(settings: &AppSettings) -> Self {
-        let client = Client::builder()
-            .user_agent("Fedi-Sleuth/0.1.0")
+        let client = api_client_builder(&settings.network)
+            .default_headers(custom_headers(&settings.api.bluesky.custom_headers))
             .timeout(StdDuration::from_secs(45))
             .build()
             .expect("Failed to create HTTP client");
@@ -38,6 +60,13 @@ impl BlueskyService {
         Self {
             client,
             auth: settings.api.bluesky.clone(),
+            requests_per_instance: settings.search.max_requests_per_instance,
+            author_filter: settings.search.bluesky_author_filter,
+            count_only: settings.search.count_only,
+            page_limits: PageLimits::from_settings(&settings.search),
+            page_size: PageSize::new(settings.search.page_size, MAX_PAGE_SIZE),
+            tag_combination: settings.search.tag_combination,
+            rate_limit_reset: AtomicI64::new(0),
         }
     }
 
@@ -57,253 +86,414 @@ impl BlueskyService {
         Ok(())
     }
 
+    /// Log in once to confirm the handle and app password still work
+    pub async fn verify_credentials(&self) -> Result<()> {
+        self.create_session().await.map(|_| ())
+    }
+
     async fn create_session(&self) -> Result<BlueskySession> {
         self.ensure_enabled()?;
 
         let url = format!("{}/xrpc/com.atproto.server.createSession", BLUESKY_API_BASE);
 
-        let response = self
-            .client
-            .post(&url)
-            .json(&serde_json::json!({
-                "identifier": self.auth.handle.trim(),
-                "password": self.auth.app_password.trim()
-            }))
-            .send()
-            .await
-            .with_context(|| "Failed to contact Bluesky session endpoint")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(anyhow::anyhow!(
-                "Bluesky login failed: {}. Response: {}",
-                status,
-                body
-            ));
-        }
+        let _permit = HostLimiter::global()
+            .acquire(&url, self.requests_per_instance)
+            .await;
 
-        let session: CreateSessionResponse = response
-            .json()
+        let request = self.client.post(&url).json(&serde_json::json!({
+            "identifier": self.auth.handle.trim(),
+            "password": self.auth.app_password.trim()
+        }));
+        let session: CreateSessionResponse = send_and_parse(request, "Bluesky session response")
             .await
-            .with_context(|| "Failed to decode Bluesky session response")?;
+            .map_err(|err| anyhow::anyhow!("Bluesky login failed: {}", err))?;
 
         Ok(BlueskySession {
             access_jwt: session.access_jwt,
+            did: session.did,
         })
     }
 
+    /// Note the rate-limit budget from a response. Once it is spent, the
+    /// next request waits for the window to reset instead of earning a 429.
+    fn track_rate_limit(&self, response: &Response) {
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<i64>().ok())
+        };
+        let Some(remaining) = header("ratelimit-remaining") else {
+            return;
+        };
+        log::debug!(
+            "Bluesky rate limit: {} requests left in this window",
+            remaining
+        );
+        if let (true, Some(reset)) = (remaining <= 0, header("ratelimit-reset")) {
+            self.rate_limit_reset.store(reset, Ordering::Relaxed);
+        }
+    }
+
+    /// Sleep until the rate-limit window resets, if the budget is spent
+    async fn wait_for_rate_limit(&self) {
+        let reset = self.rate_limit_reset.swap(0, Ordering::Relaxed);
+        let seconds = reset - Utc::now().timestamp();
+        if reset == 0 || seconds <= 0 {
+            return;
+        }
+        let wait = StdDuration::from_secs(seconds as u64 + 1).min(MAX_RATE_LIMIT_WAIT);
+        log::info!(
+            "Bluesky rate limit reached; waiting {}s for the window to reset",
+            wait.as_secs()
+        );
+        tokio::time::sleep(wait).await;
+    }
+
     async fn api_get(
         &self,
         session: &BlueskySession,
         path: &str,
         query: &[(&str, String)],
     ) -> Result<Response> {
-        let mut request = self
-            .client
-            .get(format!("{}{}", BLUESKY_API_BASE, path))
-            .bearer_auth(&session.access_jwt);
-
-        let params: Vec<(&str, &str)> = query
-            .iter()
-            .map(|(key, value)| (*key, value.as_str()))
-            .collect();
-
-        if !params.is_empty() {
-            request = request.query(&params);
-        }
+        let url = format!("{}{}", BLUESKY_API_BASE, path);
+        let _permit = HostLimiter::global()
+            .acquire(&url, self.requests_per_instance)
+            .await;
 
-        let response = request
-            .send()
-            .await
-            .with_context(|| format!("Failed to fetch Bluesky endpoint {}", path))?;
+        let paged = query.iter().any(|(key, _)| *key == "limit");
+        loop {
+            self.wait_for_rate_limit().await;
+            let mut request = self.client.get(&url).bearer_auth(&session.access_jwt);
+
+            // Paged requests follow the page size, which may have fallen back
+            let limit = self.page_size.get().to_string();
+            let params: Vec<(&str, &str)> = query
+                .iter()
+                .map(|(key, value)| match *key {
+                    "limit" => (*key, limit.as_str()),
+                    _ => (*key, value.as_str()),
+                })
+                .collect();
+
+            if !params.is_empty() {
+                request = request.query(&params);
+            }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(anyhow::anyhow!(
-                "Bluesky request failed: {}. Response: {}",
-                status,
-                body
-            ));
+            // send_checked waits out a 429 itself, using ratelimit-reset
+            match send_checked(request).await {
+                Ok(response) => {
+                    self.track_rate_limit(&response);
+                    return Ok(response);
+                }
+                Err(err)
+                    if paged
+                        && err
+                            .status()
+                            .is_some_and(|status| self.page_size.fall_back(status)) =>
+                {
+                    continue
+                }
+                Err(err) => {
+                    log::warn!("Bluesky request to {} failed: {}", path, err);
+                    // Kept typed so callers can tell a refusal from a failure
+                    return Err(anyhow::Error::new(err));
+                }
+            }
         }
+    }
 
-        Ok(response)
+    async fn api_get_json<T: serde::de::DeserializeOwned>(
+        &self,
+        session: &BlueskySession,
+        path: &str,
+        query: &[(&str, String)],
+        what: &str,
+    ) -> Result<T> {
+        let response = self.api_get(session, path, query).await?;
+        Ok(parse_json(response, what).await?)
     }
 
     async fn search_user_posts_internal(
         &self,
         handle: &str,
         cutoff_date: DateTime<Utc>,
-    ) -> Result<Vec<SearchResult>> {
+    ) -> Result<SearchOutcome> {
         let session = self.create_session().await?;
-        let mut results = Vec::new();
-        let mut cursor: Option<String> = None;
-        let mut pages = 0u32;
-
-        loop {
-            pages += 1;
-            if pages > 120 {
-                log::warn!("Bluesky author feed aborted after {} pages", pages);
-                break;
+        let query = vec![
+            ("actor", handle.to_string()),
+            ("filter", self.author_filter.api_value().to_string()),
+        ];
+
+        let mut outcome = self
+            .collect_feed(
+                &session,
+                "/xrpc/app.bsky.feed.getAuthorFeed",
+                query,
+                "Bluesky author feed",
+                cutoff_date,
+                true,
+            )
+            .await?;
+
+        // The profile only enriches the record; the posts stand without it
+        match self.fetch_profile(&session, handle).await {
+            Ok(profile) => {
+                for result in &mut outcome.results {
+                    result.account_profile = Some(profile.clone());
+                }
             }
+            Err(err) => log::warn!("Could not fetch the Bluesky profile of {}: {}", handle, err),
+        }
+        Ok(outcome)
+    }
 
-            let mut query = vec![("actor", handle.to_string()), ("limit", "30".to_string())];
+    /// Bio and follower counts of `actor` as they are now
+    async fn fetch_profile(&self, session: &BlueskySession, actor: &str) -> Result<AccountProfile> {
+        let profile: BlueskyProfileDetailed = self
+            .api_get_json(
+                session,
+                "/xrpc/app.bsky.actor.getProfile",
+                &[("actor", actor.to_string())],
+                "Bluesky profile",
+            )
+            .await?;
+        Ok(AccountProfile {
+            handle: profile.handle,
+            display_name: profile.display_name.filter(|name| !name.is_empty()),
+            description: profile.description.filter(|bio| !bio.trim().is_empty()),
+            followers: profile.followers_count,
+            following: profile.follows_count,
+            posts: profile.posts_count,
+            fetched_at: Utc::now(),
+        })
+    }
 
-            if let Some(ref value) = cursor {
-                query.push(("cursor", value.clone()));
+    /// Posts the signed-in account has liked, newest like first
+    async fn search_likes_internal(&self, cutoff_date: DateTime<Utc>) -> Result<SearchOutcome> {
+        let session = self.create_session().await?;
+        let query = vec![("actor", session.did.clone())];
+
+        // Likes are ordered by when they were given, not by post date
+        self.collect_feed(
+            &session,
+            "/xrpc/app.bsky.feed.getActorLikes",
+            query,
+            "Bluesky likes",
+            cutoff_date,
+            false,
+        )
+        .await
+        .map_err(|err| {
+            // Likes are private to their owner; others get 403 or a 400
+            let refused = err
+                .downcast_ref::<SearchError>()
+                .and_then(SearchError::status)
+                .is_some_and(|status| status.as_u16() == 400 || status.as_u16() == 403);
+            if refused {
+                anyhow::anyhow!(
+                    "Bluesky only shows likes to the account that made them ({}). Sign in as that account.",
+                    err
+                )
+            } else {
+                err
             }
+        })
+    }
 
-            let response = self
-                .api_get(&session, "/xrpc/app.bsky.feed.getAuthorFeed", &query)
-                .await?;
-
-            let response: BlueskyFeedResponse = response
-                .json()
-                .await
-                .with_context(|| "Failed to decode Bluesky author feed")?;
+    async fn search_feed_internal(
+        &self,
+        feed: &str,
+        cutoff_date: DateTime<Utc>,
+    ) -> Result<SearchOutcome> {
+        let session = self.create_session().await?;
+        let feed_uri = self.resolve_feed_uri(&session, feed).await?;
+        log::info!("Reading Bluesky feed {}", feed_uri);
+
+        // Custom feeds are usually ranked rather than chronological
+        self.collect_feed(
+            &session,
+            "/xrpc/app.bsky.feed.getFeed",
+            vec![("feed", feed_uri)],
+            "Bluesky custom feed",
+            cutoff_date,
+            false,
+        )
+        .await
+    }
 
-            let BlueskyFeedResponse {
-                feed,
-                cursor: next_cursor,
-            } = response;
+    /// Turn a feed AT-URI or a bsky.app feed link into an AT-URI
+    async fn resolve_feed_uri(&self, session: &BlueskySession, input: &str) -> Result<String> {
+        let trimmed = input.trim().trim_end_matches('/');
+        if trimmed.starts_with("at://") {
+            return Ok(trimmed.to_string());
+        }
 
-            if feed.is_empty() {
-                break;
+        // https://bsky.app/profile/{handle or did}/feed/{rkey}
+        let path = trimmed
+            .trim_start_matches("https://")
+            .trim_start_matches("http://");
+        let segments: Vec<&str> = path.split('/').collect();
+
+        match segments.as_slice() {
+            [_, "profile", actor, "feed", rkey] => {
+                let did = if actor.starts_with("did:") {
+                    actor.to_string()
+                } else {
+                    let resolved: ResolveHandleResponse = self
+                        .api_get_json(
+                            session,
+                            "/xrpc/com.atproto.identity.resolveHandle",
+                            &[("handle", actor.to_string())],
+                            "Bluesky handle resolution",
+                        )
+                        .await?;
+                    resolved.did
+                };
+                Ok(format!("at://{}/app.bsky.feed.generator/{}", did, rkey))
             }
+            _ => Err(anyhow::anyhow!(
+                "'{}' is not a feed. Use an AT-URI (at://did/app.bsky.feed.generator/name) or a bsky.app feed link.",
+                input.trim()
+            )),
+        }
+    }
 
-            let mut processed_any = false;
-            let mut found_old_post = false;
-
-            for item in feed {
-                let post = item.post;
-
-                if let Some(result) = Self::convert_post(&post, cutoff_date) {
-                    processed_any = true;
-                    results.push(result);
-                } else if let Some(created_at) = Self::parse_created_at(&post) {
-                    if created_at < cutoff_date {
-                        found_old_post = true;
-                    }
-                }
+    /// Page through a feed endpoint that returns `{ feed, cursor }`, keeping
+    /// posts newer than the cutoff. Chronological feeds stop at the first
+    /// older post; ranked feeds stop once a whole page is older.
+    async fn collect_feed(
+        &self,
+        session: &BlueskySession,
+        path: &str,
+        base_query: Vec<(&str, String)>,
+        what: &str,
+        cutoff_date: DateTime<Utc>,
+        chronological: bool,
+    ) -> Result<SearchOutcome> {
+        paginate(what, self.page_limits, |cursor: Option<String>| {
+            let mut query = base_query.clone();
+            query.push(("limit", self.page_size.get().to_string()));
+            if let Some(value) = cursor {
+                query.push(("cursor", value));
             }
 
-            if found_old_post {
-                break;
-            }
+            async move {
+                let BlueskyFeedResponse { feed, cursor: next } =
+                    self.api_get_json(session, path, &query, what).await?;
+                if feed.is_empty() {
+                    return Ok(Page::default());
+                }
 
-            if let Some(next_cursor) = next_cursor {
-                if cursor
-                    .as_ref()
-                    .map(|value| value == &next_cursor)
-                    .unwrap_or(false)
-                {
-                    break;
+                let mut page = Page {
+                    next,
+                    seen: feed.len(),
+                    ..Page::default()
+                };
+                let mut found_old_post = false;
+                for item in feed {
+                    let reposted_by = item.reposted_by();
+                    let Some(post) = item.post.into_post() else {
+                        continue;
+                    };
+
+                    if let Some(mut result) = self.convert_post(&post, cutoff_date) {
+                        result.reblogged_by = reposted_by;
+                        page.results.push(result);
+                    } else if let Some(created_at) = Self::parse_created_at(&post) {
+                        if created_at < cutoff_date {
+                            found_old_post = true;
+                        }
+                    }
                 }
-                cursor = Some(next_cursor);
-            } else {
-                break;
-            }
 
-            if !processed_any {
-                break;
+                // Ranked feeds mix dates, so only a page with nothing newer ends them
+                page.reached_cutoff = found_old_post && (chronological || page.results.is_empty());
+                Ok(page)
             }
-
-            sleep(TokioDuration::from_millis(100)).await;
-        }
-
-        Ok(results)
+        })
+        .await
     }
 
     async fn search_hashtag_posts_internal(
         &self,
         hashtag: &str,
         cutoff_date: DateTime<Utc>,
-    ) -> Result<Vec<SearchResult>> {
+    ) -> Result<SearchOutcome> {
         let session = self.create_session().await?;
-        let mut results = Vec::new();
-        let mut cursor: Option<String> = None;
-        let mut pages = 0u32;
+        let session = &session;
         let query_string = format!("#{hashtag}");
 
-        loop {
-            pages += 1;
-            if pages > 120 {
-                log::warn!("Bluesky search aborted after {} pages", pages);
-                break;
-            }
-
-            let mut query = vec![("q", query_string.clone()), ("limit", "30".to_string())];
-
-            if let Some(ref value) = cursor {
-                query.push(("cursor", value.clone()));
-            }
-
-            let response = self
-                .api_get(&session, "/xrpc/app.bsky.feed.searchPosts", &query)
-                .await?;
-
-            let response: BlueskySearchResponse = response
-                .json()
-                .await
-                .with_context(|| "Failed to decode Bluesky hashtag search")?;
-
-            let BlueskySearchResponse {
-                posts,
-                cursor: next_cursor,
-            } = response;
-
-            if posts.is_empty() {
-                break;
-            }
-
-            let mut processed_any = false;
-            let mut found_old_post = false;
-
-            for post in posts {
-                if let Some(result) = Self::convert_post(&post, cutoff_date) {
-                    processed_any = true;
-                    results.push(result);
-                } else if let Some(created_at) = Self::parse_created_at(&post) {
-                    if created_at < cutoff_date {
-                        found_old_post = true;
-                    }
+        paginate(
+            "Bluesky search",
+            self.page_limits,
+            |cursor: Option<String>| {
+                let mut query = vec![
+                    ("q", query_string.clone()),
+                    ("limit", self.page_size.get().to_string()),
+                ];
+                if let Some(value) = cursor {
+                    query.push(("cursor", value));
                 }
-            }
 
-            if found_old_post {
-                break;
-            }
+                async move {
+                    let BlueskySearchResponse {
+                        posts,
+                        cursor: next,
+                    } = self
+                        .api_get_json(
+                            session,
+                            "/xrpc/app.bsky.feed.searchPosts",
+                            &query,
+                            "Bluesky hashtag search",
+                        )
+                        .await?;
+                    if posts.is_empty() {
+                        return Ok(Page::default());
+                    }
 
-            if let Some(next_cursor) = next_cursor {
-                if cursor
-                    .as_ref()
-                    .map(|value| value == &next_cursor)
-                    .unwrap_or(false)
-                {
-                    break;
+                    let mut page = Page {
+                        next,
+                        seen: posts.len(),
+                        ..Page::default()
+                    };
+                    for post in posts.into_iter().filter_map(MaybePostView::into_post) {
+                        if let Some(result) = self.convert_post(&post, cutoff_date) {
+                            page.results.push(result);
+                        } else if let Some(created_at) = Self::parse_created_at(&post) {
+                            if created_at < cutoff_date {
+                                page.reached_cutoff = true;
+                            }
+                        }
+                    }
+                    Ok(page)
                 }
-                cursor = Some(next_cursor);
-            } else {
-                break;
-            }
-
-            if !processed_any {
-                break;
-            }
-
-            sleep(TokioDuration::from_millis(100)).await;
-        }
-
-        Ok(results)
+            },
+        )
+        .await
     }
 
-    fn convert_post(post: &BlueskyPostView, cutoff_date: DateTime<Utc>) -> Option<SearchResult> {
+    fn convert_post(
+        &self,
+        post: &BlueskyPostView,
+        cutoff_date: DateTime<Utc>,
+    ) -> Option<SearchResult> {
         let created_at = Self::parse_created_at(post)?;
         if created_at < cutoff_date {
             return None;
         }
 
+        let media = Self::extract_media(post.embed.as_ref());
+        if self.count_only {
+            return Some(SearchResult::count_stub(
+                Platform::Bluesky,
+                post.uri.clone(),
+                created_at,
+                &media,
+            ));
+        }
+
         let author = post
             .author
             .display_name
@@ -311,20 +501,20 @@ impl BlueskyService {
             .filter(|value| !value.is_empty())
             .unwrap_or(&post.author.handle);
 
-        let (media_urls, media_types, media_count) = Self::extract_media(post.embed.as_ref());
-
         Some(SearchResult {
             platform: Platform::Bluesky,
             id: post.uri.clone(),
             author: author.to_string(),
-            content: post.record.text.as_deref().unwrap_or("").trim().to_string(),
+            content: cap_content(post.record.text.as_deref().unwrap_or("").trim().to_string()),
             created_at,
-            media_urls,
-            media_types,
-            media_count,
+            media,
             likes: post.like_count.unwrap_or(0),
             shares: post.repost_count.unwrap_or(0),
             url: Self::web_url(&post.author.handle, &post.uri),
+            content_warning: None,
+            reblogged_by: None,
+            account_profile: None,
+            poll: None,
         })
     }
 
@@ -335,67 +525,117 @@ impl BlueskyService {
             .as_deref()
             .or_else(|| post.indexed_at.as_deref())?;
 
-        DateTime::parse_from_rfc3339(source)
-            .map(|value| value.with_timezone(&Utc))
-            .ok()
+        parse_post_date(source)
     }
 
-    fn extract_media(embed: Option<&Value>) -> (Vec<String>, Vec<String>, u32) {
-        let mut urls = Vec::new();
-        let mut types = Vec::new();
-
+    fn extract_media(embed: Option<&Value>) -> Vec<MediaItem> {
+        let mut media = Vec::new();
         if let Some(value) = embed {
-            Self::extract_media_recursive(value, &mut urls, &mut types);
+            Self::extract_media_recursive(value, &mut media);
         }
+        media
+    }
 
-        let count = urls.len() as u32;
-        (urls, types, count)
-    }
-
-    fn extract_media_recursive(value: &Value, urls: &mut Vec<String>, types: &mut Vec<String>) {
-        if let Some(object) = value.as_object() {
-            match object.get("$type").and_then(Value::as_str) {
-                Some("app.bsky.embed.images#view") => {
-                    if let Some(images) = object.get("images").and_then(Value::as_array) {
-                        for image in images {
-                            if let Some(fullsize) = image.get("fullsize").and_then(Value::as_str) {
-                                let trimmed = fullsize.trim();
-                                if !trimmed.is_empty() {
-                                    urls.push(trimmed.to_string());
-                                    types.push("image".to_string());
-                                }
-                            }
-                        }
+    fn extract_media_recursive(value: &Value, media: &mut Vec<MediaItem>) {
+        let Some(object) = value.as_object() else {
+            return;
+        };
+        match object.get("$type").and_then(Value::as_str) {
+            Some("app.bsky.embed.images#view") => {
+                for image in object
+                    .get("images")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                {
+                    if let Some(item) = Self::media_item(image, "fullsize", "thumb", "image") {
+                        media.push(item);
                     }
                 }
-                Some("app.bsky.embed.external#view") => {
-                    if let Some(external) = object.get("external") {
-                        if let Some(uri) = external.get("uri").and_then(Value::as_str) {
-                            let trimmed = uri.trim();
-                            if !trimmed.is_empty() {
-                                urls.push(trimmed.to_string());
-                                types.push("external".to_string());
-                            }
-                        }
+            }
+            Some("app.bsky.embed.external#view") => {
+                if let Some(external) = object.get("external") {
+                    if let Some(mut item) = Self::media_item(external, "uri", "thumb", "external") {
+                        // Link cards have a title and summary rather than alt text
+                        item.description = text_field(external, "title");
+                        media.push(item);
                     }
                 }
-                Some("app.bsky.embed.video#view") => {
-                    if let Some(playlist) = object.get("playlist").and_then(Value::as_str) {
-                        let trimmed = playlist.trim();
-                        if !trimmed.is_empty() {
-                            urls.push(trimmed.to_string());
-                            types.push("video".to_string());
-                        }
-                    }
+            }
+            Some("app.bsky.embed.video#view") => {
+                if let Some(item) = Self::media_item(value, "playlist", "thumbnail", "video") {
+                    media.push(item);
                 }
-                Some("app.bsky.embed.recordWithMedia#view") => {
-                    if let Some(media) = object.get("media") {
-                        Self::extract_media_recursive(media, urls, types);
-                    }
+            }
+            Some("app.bsky.embed.recordWithMedia#view") => {
+                if let Some(inner) = object.get("media") {
+                    Self::extract_media_recursive(inner, media);
                 }
-                _ => {}
             }
+            _ => {}
+        }
+    }
+
+    /// One embed entry as a media item, if it has a usable `url_key`
+    fn media_item(
+        entry: &Value,
+        url_key: &str,
+        preview_key: &str,
+        media_type: &str,
+    ) -> Option<MediaItem> {
+        let url = text_field(entry, url_key)?;
+        let aspect = entry.get("aspectRatio");
+        let dimension = |key: &str| {
+            aspect
+                .and_then(|ratio| ratio.get(key))
+                .and_then(Value::as_u64)
+                .and_then(|value| u32::try_from(value).ok())
+        };
+        Some(MediaItem {
+            url,
+            media_type: media_type.to_string(),
+            description: text_field(entry, "alt"),
+            width: dimension("width"),
+            height: dimension("height"),
+            preview_url: text_field(entry, preview_key),
+        })
+    }
+
+    /// Accept `@alice.example.com`, `did:plc:…` or a bare `alice`, which is
+    /// assumed to live on bsky.social
+    fn normalize_handle(input: &str) -> Result<String> {
+        let cleaned = input.trim().trim_start_matches('@');
+
+        if cleaned.starts_with("did:") {
+            return Ok(cleaned.to_string());
+        }
+
+        let handle = cleaned.to_ascii_lowercase();
+        let is_label = |label: &str| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        };
+
+        if handle.contains('.') {
+            if handle.split('.').all(is_label) {
+                return Ok(handle);
+            }
+        } else if is_label(&handle) {
+            let assumed = format!("{}.bsky.social", handle);
+            log::info!(
+                "Bluesky handle '{}' has no domain; assuming {}",
+                cleaned,
+                assumed
+            );
+            return Ok(assumed);
         }
+
+        Err(anyhow::anyhow!(
+            "'{}' is not a valid Bluesky handle. Bluesky handles must include a domain, e.g. alice.bsky.social",
+            cleaned
+        ))
     }
 
     fn web_url(handle: &str, uri: &str) -> String {
@@ -425,28 +665,62 @@ impl SocialPlatform for BlueskyService {
         BLUESKY_API_BASE
     }
 
-    async fn search_user(&self, username: &str, days_back: u32) -> Result<Vec<SearchResult>> {
+    async fn search_user(&self, username: &str, days_back: u32) -> Result<SearchOutcome> {
         let cutoff_date = Utc::now() - Duration::days(days_back as i64);
-        let cleaned = username.trim().trim_start_matches('@');
-        self.search_user_posts_internal(cleaned, cutoff_date).await
+        let handle = Self::normalize_handle(username)?;
+        self.search_user_posts_internal(&handle, cutoff_date).await
     }
 
-    async fn search_hashtag(&self, hashtag: &str, days_back: u32) -> Result<Vec<SearchResult>> {
+    async fn search_hashtag(&self, hashtag: &str, days_back: u32) -> Result<SearchOutcome> {
         let cutoff_date = Utc::now() - Duration::days(days_back as i64);
-        let cleaned = hashtag.trim().trim_start_matches('#');
-        self.search_hashtag_posts_internal(cleaned, cutoff_date)
-            .await
+        // searchPosts takes a single tag and no exclusions
+        let query = TagQuery::parse(hashtag);
+        search_tags_separately(
+            &query,
+            self.tag_combination,
+            self.count_only,
+            |tag| async move { self.search_hashtag_posts_internal(&tag, cutoff_date).await },
+        )
+        .await
+    }
+
+    async fn search_feed(&self, feed: &str, days_back: u32) -> Result<SearchOutcome> {
+        let cutoff_date = Utc::now() - Duration::days(days_back as i64);
+        self.search_feed_internal(feed, cutoff_date).await
+    }
+
+    async fn search_favourites(&self, days_back: u32) -> Result<SearchOutcome> {
+        let cutoff_date = Utc::now() - Duration::days(days_back as i64);
+        self.search_likes_internal(cutoff_date).await
     }
 }
 
+/// A non-empty, trimmed string field of an embed entry
+fn text_field(entry: &Value, key: &str) -> Option<String> {
+    entry
+        .get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
+}
+
 #[derive(Debug, Deserialize)]
 struct CreateSessionResponse {
     #[serde(rename = "accessJwt")]
     access_jwt: String,
+    did: String,
 }
 
 struct BlueskySession {
     access_jwt: String,
+    /// The signed-in account, for endpoints that act on it
+    did: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResolveHandleResponse {
+    did: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -459,17 +733,80 @@ struct BlueskyFeedResponse {
 
 #[derive(Debug, Deserialize)]
 struct BlueskyFeedItem {
-    post: BlueskyPostView,
+    post: MaybePostView,
+    /// Why the post is in the feed; a repost names the reposting account
+    #[serde(default)]
+    reason: Option<Value>,
+}
+
+impl BlueskyFeedItem {
+    /// The reposting account's name when the post is in the feed as a repost
+    fn reposted_by(&self) -> Option<String> {
+        let reason = self.reason.as_ref()?;
+        if reason.get("$type").and_then(Value::as_str) != Some("app.bsky.feed.defs#reasonRepost") {
+            return None;
+        }
+        let by = reason.get("by")?;
+        text_field(by, "displayName").or_else(|| text_field(by, "handle"))
+    }
 }
 
 #[derive(Debug, Deserialize)]
 struct BlueskySearchResponse {
     #[serde(default)]
-    posts: Vec<BlueskyPostView>,
+    posts: Vec<MaybePostView>,
     #[serde(default)]
     cursor: Option<String>,
 }
 
+/// `$type` of the stubs the AppView puts where a post can no longer be shown
+const UNAVAILABLE_POST_TYPES: [&str; 2] = [
+    "app.bsky.feed.defs#notFoundPost",
+    "app.bsky.feed.defs#blockedPost",
+];
+
+/// A post slot that may hold a `#notFoundPost` or `#blockedPost` stub
+/// (deleted, taken down or blocked) instead of a full post view. Anything
+/// else must decode as a post; a post that does not is an error, not a stub.
+#[derive(Debug)]
+enum MaybePostView {
+    Post(BlueskyPostView),
+    Unavailable { uri: String, kind: String },
+}
+
+impl<'de> Deserialize<'de> for MaybePostView {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        match value.get("$type").and_then(Value::as_str) {
+            Some(kind) if UNAVAILABLE_POST_TYPES.contains(&kind) => {
+                Ok(MaybePostView::Unavailable {
+                    uri: value
+                        .get("uri")
+                        .and_then(Value::as_str)
+                        .unwrap_or("?")
+                        .to_string(),
+                    kind: kind.to_string(),
+                })
+            }
+            _ => serde_json::from_value(value)
+                .map(MaybePostView::Post)
+                .map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+impl MaybePostView {
+    fn into_post(self) -> Option<BlueskyPostView> {
+        match self {
+            MaybePostView::Post(post) => Some(post),
+            MaybePostView::Unavailable { uri, kind } => {
+                log::debug!("Skipping unavailable Bluesky post {} ({})", uri, kind);
+                None
+            }
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct BlueskyPostView {
     uri: String,
@@ -496,6 +833,25 @@ struct BlueskyProfileView {
     display_name: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct BlueskyProfileDetailed {
+    handle: String,
+    #[serde(rename = "displayName")]
+    #[serde(default)]
+    display_name: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(rename = "followersCount")]
+    #[serde(default)]
+    followers_count: u64,
+    #[serde(rename = "followsCount")]
+    #[serde(default)]
+    follows_count: u64,
+    #[serde(rename = "postsCount")]
+    #[serde(default)]
+    posts_count: u64,
+}
+
 #[derive(Debug, Deserialize)]
 struct BlueskyRecord {
     #[serde(default)]
@@ -504,3 +860,119 @@ struct BlueskyRecord {
     #[serde(default)]
     created_at: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_handle_strips_at_and_lowercases() {
+        assert_eq!(
+            BlueskyService::normalize_handle("@Alice.Example.com").unwrap(),
+            "alice.example.com"
+        );
+        assert_eq!(
+            BlueskyService::normalize_handle("  alice.bsky.social ").unwrap(),
+            "alice.bsky.social"
+        );
+    }
+
+    #[test]
+    fn normalize_handle_assumes_bsky_social_for_bare_names() {
+        assert_eq!(
+            BlueskyService::normalize_handle("alice").unwrap(),
+            "alice.bsky.social"
+        );
+        assert_eq!(
+            BlueskyService::normalize_handle("@bob-smith").unwrap(),
+            "bob-smith.bsky.social"
+        );
+    }
+
+    #[test]
+    fn normalize_handle_passes_dids_through() {
+        assert_eq!(
+            BlueskyService::normalize_handle("did:plc:z72i7hdynmk6r22z27h6tvur").unwrap(),
+            "did:plc:z72i7hdynmk6r22z27h6tvur"
+        );
+        assert_eq!(
+            BlueskyService::normalize_handle("@did:web:example.com").unwrap(),
+            "did:web:example.com"
+        );
+    }
+
+    #[test]
+    fn normalize_handle_rejects_invalid_input() {
+        for input in [
+            "",
+            "@",
+            "alice@bsky.social",
+            "-alice",
+            "alice-.bsky.social",
+            "alice..bsky.social",
+            "alice.bsky.social.",
+            "al ice",
+        ] {
+            assert!(
+                BlueskyService::normalize_handle(input).is_err(),
+                "{input:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn search_page_skips_not_found_and_blocked_stubs() {
+        let json = r#"{
+            "posts": [
+                {
+                    "$type": "app.bsky.feed.defs#postView",
+                    "uri": "at://did:plc:abc/app.bsky.feed.post/3k1",
+                    "author": { "did": "did:plc:abc", "handle": "alice.bsky.social" },
+                    "record": { "text": "hello", "createdAt": "2024-05-01T12:00:00.000Z" },
+                    "likeCount": 3,
+                    "indexedAt": "2024-05-01T12:00:01.000Z"
+                },
+                {
+                    "$type": "app.bsky.feed.defs#notFoundPost",
+                    "uri": "at://did:plc:abc/app.bsky.feed.post/3k2",
+                    "notFound": true
+                },
+                {
+                    "$type": "app.bsky.feed.defs#blockedPost",
+                    "uri": "at://did:plc:def/app.bsky.feed.post/3k3",
+                    "blocked": true,
+                    "author": { "did": "did:plc:def" }
+                }
+            ],
+            "cursor": "next"
+        }"#;
+
+        let page: BlueskySearchResponse = serde_json::from_str(json).unwrap();
+        let posts: Vec<BlueskyPostView> = page
+            .posts
+            .into_iter()
+            .filter_map(MaybePostView::into_post)
+            .collect();
+
+        assert_eq!(posts.len(), 1);
+        assert_eq!(posts[0].author.handle, "alice.bsky.social");
+        assert_eq!(posts[0].record.text.as_deref(), Some("hello"));
+        assert_eq!(page.cursor.as_deref(), Some("next"));
+    }
+
+    #[test]
+    fn malformed_post_is_an_error_not_a_stub() {
+        // A post view missing its author must not be silently dropped
+        let json = r#"{
+            "posts": [
+                {
+                    "$type": "app.bsky.feed.defs#postView",
+                    "uri": "at://did:plc:abc/app.bsky.feed.post/3k1",
+                    "record": { "text": "hello" }
+                }
+            ]
+        }"#;
+
+        assert!(serde_json::from_str::<BlueskySearchResponse>(json).is_err());
+    }
+}
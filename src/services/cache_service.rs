@@ -0,0 +1,70 @@
+// ============================================================================
+// Cache Service - Purge cached files and stored sessions
+// ============================================================================
+// Cached files (such as preview thumbnails) live under the OS cache folder
+// and can always be fetched again; Bluesky session tokens live in the
+// settings. Clearing both frees disk space and forces a fresh sign-in.
+// ============================================================================
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use crate::models::{AccountAuth, AppSettings};
+
+pub struct CacheService;
+
+impl CacheService {
+    /// Folder for files that can be fetched again, e.g. thumbnails
+    pub fn cache_dir() -> Option<PathBuf> {
+        Some(dirs::cache_dir()?.join("pixelfed-rust"))
+    }
+
+    /// Delete the cache folder and return the bytes freed
+    pub fn clear_cache() -> Result<u64> {
+        let Some(dir) = Self::cache_dir().filter(|dir| dir.exists()) else {
+            return Ok(0);
+        };
+        let size = dir_size(&dir);
+        fs::remove_dir_all(&dir)?;
+        log::info!("Cleared cache {} ({} bytes)", dir.display(), size);
+        Ok(size)
+    }
+
+    /// Forget Bluesky session tokens for the active and saved accounts so
+    /// the next search signs in again. Returns how many were cleared.
+    pub fn clear_sessions(settings: &mut AppSettings) -> usize {
+        let mut cleared = 0;
+        let saved = settings
+            .api
+            .saved_accounts
+            .iter_mut()
+            .filter_map(|account| match &mut account.auth {
+                AccountAuth::Bluesky(auth) => Some(auth),
+                AccountAuth::OAuth(_) => None,
+            });
+        for auth in std::iter::once(&mut settings.api.bluesky).chain(saved) {
+            if auth.access_jwt.is_some() || auth.refresh_jwt.is_some() {
+                cleared += 1;
+            }
+            auth.access_jwt = None;
+            auth.refresh_jwt = None;
+        }
+        cleared
+    }
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
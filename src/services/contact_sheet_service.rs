@@ -0,0 +1,256 @@
+// ============================================================================
+// Contact Sheet Service - Thumbnail grid for a platform's results
+// ============================================================================
+// Composes the first media item of every post in a result group into one
+// grid image, labelled with author and date, with an optional source footer
+// stamped along the bottom. Videos get a placeholder tile.
+// ============================================================================
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use chrono::Utc;
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, Rgba, RgbaImage};
+use imageproc::drawing::{draw_filled_rect_mut, draw_text_mut};
+use imageproc::rect::Rect;
+use rusttype::{Font, Scale};
+
+use crate::models::{AppSettings, PlatformSearchResults, SearchContext, SearchResult};
+use crate::services::http_util::client_builder;
+use crate::services::DownloadService;
+
+const TILE_SIZE: u32 = 200;
+const LABEL_HEIGHT: u32 = 36;
+const FOOTER_HEIGHT: u32 = 32;
+const PADDING: u32 = 8;
+const COLUMNS: u32 = 6;
+const MAX_TILES: usize = 60;
+
+const BACKGROUND: Rgba<u8> = Rgba([24, 24, 27, 255]);
+const PLACEHOLDER: Rgba<u8> = Rgba([63, 63, 70, 255]);
+const TEXT: Rgba<u8> = Rgba([228, 228, 231, 255]);
+const MUTED_TEXT: Rgba<u8> = Rgba([161, 161, 170, 255]);
+
+/// Fonts tried in order for labels; the sheet is still written without
+/// labels if none of them exist on this system
+const FONT_CANDIDATES: &[&str] = &[
+    "C:\\Windows\\Fonts\\segoeui.ttf",
+    "C:\\Windows\\Fonts\\arial.ttf",
+    "/System/Library/Fonts/Supplemental/Arial.ttf",
+    "/Library/Fonts/Arial.ttf",
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/truetype/liberation/LiberationSans-Regular.ttf",
+];
+
+/// Thumbnail source for one tile
+enum TileImage {
+    Picture(DynamicImage),
+    Video,
+    Missing,
+}
+
+struct Tile {
+    image: TileImage,
+    author: String,
+    date: String,
+}
+
+pub struct ContactSheetService {
+    client: reqwest::Client,
+    settings: AppSettings,
+}
+
+impl ContactSheetService {
+    pub fn new(settings: AppSettings) -> Self {
+        Self {
+            client: client_builder(&settings.network)
+                .build()
+                .expect("Failed to create HTTP client"),
+            settings,
+        }
+    }
+
+    /// Build a contact sheet for one platform group and save it as a PNG in
+    /// the download root. Returns the path of the written image.
+    pub async fn generate(
+        &self,
+        context: Option<SearchContext>,
+        group: &PlatformSearchResults,
+    ) -> Result<PathBuf> {
+        let posts: Vec<&SearchResult> = group
+            .results
+            .iter()
+            .filter(|result| !result.media.is_empty())
+            .take(MAX_TILES)
+            .collect();
+
+        if posts.is_empty() {
+            return Err(anyhow::anyhow!(
+                "No media in {} to put on a sheet",
+                group.label
+            ));
+        }
+
+        let mut tiles = Vec::with_capacity(posts.len());
+        for result in posts {
+            tiles.push(Tile {
+                image: self.fetch_tile(result).await,
+                author: result.author.clone(),
+                date: result.created_at.format("%Y-%m-%d").to_string(),
+            });
+        }
+
+        let footer = self.settings.download.contact_sheet_watermark.then(|| {
+            format!(
+                "Source: {} · {} · generated {} by Fedi-Sleuth",
+                group.label,
+                context
+                    .as_ref()
+                    .map(|ctx| format!(
+                        "{} \"{}\", last {} day(s)",
+                        ctx.search_type.slug(),
+                        ctx.query,
+                        ctx.days_back
+                    ))
+                    .unwrap_or_else(|| "search".to_string()),
+                Utc::now().format("%Y-%m-%d %H:%M UTC")
+            )
+        });
+
+        let root =
+            DownloadService::new(self.settings.clone()).create_download_root(context.as_ref())?;
+        let path = root.join(format!(
+            "contact_sheet_{}.png",
+            group.platform.name().to_lowercase()
+        ));
+
+        let output = path.clone();
+        tokio::task::spawn_blocking(move || render_sheet(tiles, footer.as_deref(), &output))
+            .await??;
+
+        Ok(path)
+    }
+
+    async fn fetch_tile(&self, result: &SearchResult) -> TileImage {
+        let item = &result.media[0];
+        if item.is_video() {
+            return TileImage::Video;
+        }
+        // Tiles are small, so the preview rendition is enough
+        let url = item.preview_url.as_deref().unwrap_or(&item.url);
+
+        let bytes = match self.client.get(url).send().await {
+            Ok(response) if response.status().is_success() => response.bytes().await.ok(),
+            _ => None,
+        };
+
+        match bytes.and_then(|bytes| image::load_from_memory(&bytes).ok()) {
+            Some(image) => TileImage::Picture(image),
+            None => {
+                log::warn!("Could not load thumbnail {} for contact sheet", url);
+                TileImage::Missing
+            }
+        }
+    }
+}
+
+fn load_font() -> Option<Font<'static>> {
+    FONT_CANDIDATES
+        .iter()
+        .filter_map(|path| std::fs::read(path).ok())
+        .find_map(Font::try_from_vec)
+}
+
+fn render_sheet(tiles: Vec<Tile>, footer: Option<&str>, path: &Path) -> Result<()> {
+    let font = load_font();
+    if font.is_none() {
+        log::warn!("No system font found; contact sheet will be written without labels");
+    }
+
+    let rows = (tiles.len() as u32).div_ceil(COLUMNS);
+    let columns = COLUMNS.min(tiles.len() as u32);
+    let cell_height = TILE_SIZE + LABEL_HEIGHT;
+    let width = PADDING + columns * (TILE_SIZE + PADDING);
+    let footer_height = if footer.is_some() { FOOTER_HEIGHT } else { 0 };
+    let height = PADDING + rows * (cell_height + PADDING) + footer_height;
+
+    let mut sheet = RgbaImage::from_pixel(width, height, BACKGROUND);
+
+    for (index, tile) in tiles.iter().enumerate() {
+        let column = index as u32 % COLUMNS;
+        let row = index as u32 / COLUMNS;
+        let x = PADDING + column * (TILE_SIZE + PADDING);
+        let y = PADDING + row * (cell_height + PADDING);
+
+        match &tile.image {
+            TileImage::Picture(image) => {
+                // Crop to a centred square so every tile has the same shape
+                let thumbnail = image.resize_to_fill(TILE_SIZE, TILE_SIZE, FilterType::Triangle);
+                imageops::overlay(&mut sheet, &thumbnail.to_rgba8(), x as i64, y as i64);
+            }
+            TileImage::Video | TileImage::Missing => {
+                draw_filled_rect_mut(
+                    &mut sheet,
+                    Rect::at(x as i32, y as i32).of_size(TILE_SIZE, TILE_SIZE),
+                    PLACEHOLDER,
+                );
+                if let Some(font) = &font {
+                    let text = if matches!(tile.image, TileImage::Video) {
+                        "▶ video"
+                    } else {
+                        "unavailable"
+                    };
+                    draw_text_mut(
+                        &mut sheet,
+                        MUTED_TEXT,
+                        x as i32 + 12,
+                        y as i32 + TILE_SIZE as i32 / 2 - 10,
+                        Scale::uniform(20.0),
+                        font,
+                        text,
+                    );
+                }
+            }
+        }
+
+        if let Some(font) = &font {
+            let author: String = tile.author.chars().take(26).collect();
+            draw_text_mut(
+                &mut sheet,
+                TEXT,
+                x as i32,
+                (y + TILE_SIZE + 2) as i32,
+                Scale::uniform(15.0),
+                font,
+                &author,
+            );
+            draw_text_mut(
+                &mut sheet,
+                MUTED_TEXT,
+                x as i32,
+                (y + TILE_SIZE + 19) as i32,
+                Scale::uniform(13.0),
+                font,
+                &tile.date,
+            );
+        }
+    }
+
+    if let (Some(font), Some(footer)) = (&font, footer) {
+        draw_text_mut(
+            &mut sheet,
+            MUTED_TEXT,
+            PADDING as i32,
+            (height - FOOTER_HEIGHT + 8) as i32,
+            Scale::uniform(15.0),
+            font,
+            footer,
+        );
+    }
+
+    sheet.save(path)?;
+    Ok(())
+}
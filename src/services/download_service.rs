@@ -1,32 +1,634 @@
 use anyhow::Result;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use futures_util::stream::FuturesUnordered;
 use futures_util::StreamExt;
-use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::io::AsyncWriteExt;
 
-use crate::models::{AppSettings, PlatformSearchResults, SearchContext, SearchResult, SearchType};
+use crate::models::{
+    AccountProfile, AppSettings, CollisionPolicy, ImageFormat, Platform, PlatformSearchResults,
+    Poll, SearchContext, SearchResult, SidecarFormat,
+};
+use crate::services::http_util::client_builder;
+use crate::utils::{format_bytes, sanitize_path_component, truncate_text};
+
+const MANIFEST_FILE_NAME: &str = "download_manifest.json";
+/// Completed files between manifest saves during a run
+const MANIFEST_SAVE_INTERVAL: usize = 10;
+const JPEG_QUALITY: u8 = 90;
+
+/// One planned file in a download run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub url: String,
+    pub file: PathBuf,
+    pub completed: bool,
+    /// Left out on purpose (over the size cap, or kept an existing file),
+    /// so a resume does not fetch it again
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skipped: bool,
+    /// Index of an earlier entry with the same URL; this file is linked to
+    /// that one instead of being downloaded again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duplicate_of: Option<usize>,
+    /// The post the file belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post: Option<(Platform, String)>,
+}
+
+/// Record of a download run. Saved periodically while the run is going and
+/// removed once it finishes, so a manifest left on disk marks a run that
+/// never completed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DownloadManifest {
+    pub started_at: DateTime<Utc>,
+    pub interrupted: bool,
+    pub entries: Vec<ManifestEntry>,
+}
+
+impl DownloadManifest {
+    pub fn remaining(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| !entry.completed && !entry.skipped)
+            .count()
+    }
+}
+
+/// An unfinished run found in the download folder
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResumableDownload {
+    pub root: PathBuf,
+    pub manifest: DownloadManifest,
+}
+
+/// What happened to each planned file in a finished download run
+#[derive(Debug, Clone)]
+pub struct DownloadSummary {
+    pub root: PathBuf,
+    pub downloaded: usize,
+    /// Files left out on purpose, e.g. over the size cap
+    pub skipped: usize,
+    /// Repeated media hard-linked (or copied) instead of downloaded twice
+    pub duplicates: usize,
+    pub failed: usize,
+    /// Images kept in their original format because re-encoding failed
+    pub conversion_failed: usize,
+    /// Bytes written by this run
+    pub bytes: u64,
+    /// How each post's media fared, keyed by platform and post id
+    pub media_status: HashMap<(Platform, String), MediaStatus>,
+}
+
+/// How much of one post's media a download run retrieved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaStatus {
+    /// Every file was saved, or left out on purpose
+    Complete,
+    /// Some files failed
+    Partial,
+    /// Every file failed
+    Missing,
+}
+
+impl MediaStatus {
+    fn from_counts(failed: usize, total: usize) -> Self {
+        match failed {
+            0 => MediaStatus::Complete,
+            _ if failed < total => MediaStatus::Partial,
+            _ => MediaStatus::Missing,
+        }
+    }
+
+    pub fn has_failures(&self) -> bool {
+        *self != MediaStatus::Complete
+    }
+}
+
+/// Result of fetching a single file
+enum FileOutcome {
+    /// `file` is where it was saved, which differs from the planned path
+    /// when a name collision was resolved by renaming
+    Downloaded {
+        bytes: u64,
+        file: PathBuf,
+        conversion: Conversion,
+    },
+    Skipped,
+}
+
+/// Per-file limits and post-processing shared by every task of a run
+#[derive(Clone, Copy)]
+struct FileOptions {
+    max_bytes: Option<u64>,
+    convert_to: Option<ImageFormat>,
+    /// Longest wait for a response or the next chunk
+    stall_timeout: Duration,
+    on_collision: CollisionPolicy,
+}
+
+/// `path` with `_1`, `_2`, … added to the file stem until nothing on disk
+/// has that name
+fn free_path(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().into_owned());
+    (1..)
+        .map(|n| {
+            let name = match &extension {
+                Some(extension) => format!("{}_{}.{}", stem, n, extension),
+                None => format!("{}_{}", stem, n),
+            };
+            path.with_file_name(name)
+        })
+        .find(|candidate| !candidate.exists())
+        .expect("an unused file name")
+}
+
+/// The server went quiet partway through a request
+#[derive(Debug, thiserror::Error)]
+enum Stalled {
+    #[error("No response after {0}s")]
+    NoResponse(u64),
+    #[error("Download stalled: no data for {0}s")]
+    NoData(u64),
+}
+
+/// Only network failures, server errors and rate limiting are worth another
+/// attempt; local IO errors and other client errors fail the same way again
+fn is_retryable(err: &anyhow::Error) -> bool {
+    if err.downcast_ref::<Stalled>().is_some() {
+        return true;
+    }
+    match err.downcast_ref::<reqwest::Error>() {
+        Some(err) => match err.status() {
+            Some(status) => {
+                status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+            }
+            None => err.is_timeout() || err.is_connect() || err.is_body(),
+        },
+        None => false,
+    }
+}
+
+/// What the optional format conversion did with a downloaded file
+enum Conversion {
+    Unchanged,
+    Converted(PathBuf),
+    Failed,
+}
+
+struct ActiveDownload {
+    root: PathBuf,
+    manifest: DownloadManifest,
+    /// Files this run has created and not finished, by entry index. Only
+    /// these are removed when a run is abandoned; anything else on disk
+    /// may be the user's.
+    writing: HashMap<usize, PathBuf>,
+    /// Files completed since the manifest was last written
+    unsaved: usize,
+}
+
+/// Shared handle used to cancel a running download from outside the service
+/// (e.g. the UI or the window-close handler)
+#[derive(Clone, Default)]
+pub struct DownloadControl {
+    cancelled: Arc<AtomicBool>,
+    active: Arc<Mutex<Option<ActiveDownload>>>,
+}
+
+impl DownloadControl {
+    pub fn is_active(&self) -> bool {
+        self.lock_active().is_some()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Ask the running download to stop; in-flight files are abandoned
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Cancel and synchronously flush state. Used when the app is about to
+    /// exit and spawned tasks will not get a chance to clean up.
+    pub fn shutdown(&self) {
+        self.cancel();
+        self.flush_partial();
+    }
+
+    fn begin(&self, root: PathBuf, manifest: DownloadManifest) {
+        self.cancelled.store(false, Ordering::SeqCst);
+        *self.lock_active() = Some(ActiveDownload {
+            root,
+            manifest,
+            writing: HashMap::new(),
+            unsaved: 0,
+        });
+        self.save_progress();
+    }
+
+    /// Entry `index` is saved as `file`. Every few files the manifest is
+    /// written out so a crash loses little.
+    fn mark_completed(&self, index: usize, file: &Path) {
+        if let Some(active) = self.lock_active().as_mut() {
+            active.writing.remove(&index);
+            if let Some(entry) = active.manifest.entries.get_mut(index) {
+                entry.completed = true;
+                entry.file = file.to_path_buf();
+            }
+            active.unsaved += 1;
+            if active.unsaved >= MANIFEST_SAVE_INTERVAL {
+                active.unsaved = 0;
+                active.manifest.interrupted = true;
+                write_manifest(&active.root, &active.manifest);
+            }
+        }
+    }
+
+    fn mark_skipped(&self, index: usize) {
+        if let Some(active) = self.lock_active().as_mut() {
+            active.writing.remove(&index);
+            if let Some(entry) = active.manifest.entries.get_mut(index) {
+                entry.skipped = true;
+            }
+        }
+    }
+
+    /// Entries marked completed so far, including by earlier runs
+    fn completed_count(&self) -> usize {
+        self.lock_active().as_ref().map_or(0, |active| {
+            active
+                .manifest
+                .entries
+                .iter()
+                .filter(|entry| entry.completed)
+                .count()
+        })
+    }
+
+    /// Entry `index` is about to be written to `file`, which may be a
+    /// renamed path; the manifest records where it actually goes
+    fn start_writing(&self, index: usize, file: &Path) {
+        if let Some(active) = self.lock_active().as_mut() {
+            active.writing.insert(index, file.to_path_buf());
+            if let Some(entry) = active.manifest.entries.get_mut(index) {
+                entry.file = file.to_path_buf();
+            }
+        }
+    }
+
+    /// The partial file of entry `index` was removed after a failure
+    fn stop_writing(&self, index: usize) {
+        if let Some(active) = self.lock_active().as_mut() {
+            active.writing.remove(&index);
+        }
+    }
+
+    /// Write the manifest as it stands so a crash can be resumed
+    fn save_progress(&self) {
+        if let Some(active) = self.lock_active().as_mut() {
+            active.manifest.interrupted = true;
+            write_manifest(&active.root, &active.manifest);
+        }
+    }
+
+    /// The run completed; drop its manifest so it is not offered for resume
+    fn finish(&self) {
+        if let Some(active) = self.lock_active().take() {
+            let manifest_path = active.root.join(MANIFEST_FILE_NAME);
+            if manifest_path.exists() {
+                if let Err(err) = fs::remove_file(&manifest_path) {
+                    log::warn!(
+                        "Failed to remove download manifest {}: {}",
+                        manifest_path.display(),
+                        err
+                    );
+                }
+            }
+        }
+    }
+
+    /// Remove files this run left half written and record what was finished
+    fn flush_partial(&self) {
+        let Some(mut active) = self.lock_active().take() else {
+            return;
+        };
+
+        for file in active.writing.values() {
+            if file.exists() {
+                if let Err(err) = fs::remove_file(file) {
+                    log::warn!("Failed to remove partial file {}: {}", file.display(), err);
+                }
+            }
+        }
+
+        active.manifest.interrupted = true;
+        if write_manifest(&active.root, &active.manifest) {
+            log::info!(
+                "Saved partial download manifest to {}",
+                active.root.join(MANIFEST_FILE_NAME).display()
+            );
+        }
+    }
+
+    fn lock_active(&self) -> std::sync::MutexGuard<'_, Option<ActiveDownload>> {
+        self.active
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Link `target` to `original`, copying when the file system does not
+/// support links. An existing `target` is handled per `on_collision`.
+/// Returns the path written, or `None` when an existing file was kept.
+fn link_or_copy(
+    original: &Path,
+    target: &Path,
+    on_collision: CollisionPolicy,
+) -> std::io::Result<Option<PathBuf>> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let target = match on_collision {
+        _ if !target.exists() => target.to_path_buf(),
+        CollisionPolicy::Skip => return Ok(None),
+        CollisionPolicy::Rename => free_path(target),
+        CollisionPolicy::Overwrite => {
+            fs::remove_file(target)?;
+            target.to_path_buf()
+        }
+    };
+    if fs::hard_link(original, &target).is_err() {
+        fs::copy(original, &target)?;
+    }
+    Ok(Some(target))
+}
+
+/// Composite `image` onto a white background for formats without alpha, so
+/// transparent areas don't come out black.
+fn flatten_onto_white(image: &image::DynamicImage) -> image::RgbImage {
+    let rgba = image.to_rgba8();
+    image::RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let [r, g, b, a] = rgba.get_pixel(x, y).0;
+        let blend = |channel: u8| {
+            ((u16::from(channel) * u16::from(a) + 255 * (255 - u16::from(a)) + 127) / 255) as u8
+        };
+        image::Rgb([blend(r), blend(g), blend(b)])
+    })
+}
+
+/// Re-encode a downloaded image as `target`, replacing the original. Returns
+/// the new path, or `None` for videos, animated GIFs, unknown formats and
+/// images already in the target format.
+fn convert_image(path: &Path, target: ImageFormat) -> Result<Option<PathBuf>> {
+    let reader = image::io::Reader::open(path)?.with_guessed_format()?;
+    let source = match reader.format() {
+        Some(image::ImageFormat::Gif) | None => return Ok(None),
+        Some(format) => format,
+    };
+    let target_format = match target {
+        ImageFormat::Jpeg => image::ImageFormat::Jpeg,
+        ImageFormat::WebP => image::ImageFormat::WebP,
+    };
+    if source == target_format {
+        return Ok(None);
+    }
+
+    let decoded = reader.decode()?;
+    let converted = path.with_extension(target.extension());
+    // Encode next to the original so a failure never leaves a truncated file
+    let staging = path.with_extension(format!("{}.part", target.extension()));
+
+    let encoded = match target {
+        ImageFormat::Jpeg => fs::File::create(&staging)
+            .map_err(anyhow::Error::from)
+            .and_then(|file| {
+                image::codecs::jpeg::JpegEncoder::new_with_quality(
+                    std::io::BufWriter::new(file),
+                    JPEG_QUALITY,
+                )
+                .encode_image(&image::DynamicImage::ImageRgb8(flatten_onto_white(
+                    &decoded,
+                )))
+                .map_err(anyhow::Error::from)
+            }),
+        // WebP keeps transparency, so only the bit depth is normalised
+        ImageFormat::WebP => {
+            let normalised = if decoded.color().has_alpha() {
+                image::DynamicImage::ImageRgba8(decoded.to_rgba8())
+            } else {
+                image::DynamicImage::ImageRgb8(decoded.to_rgb8())
+            };
+            normalised
+                .save_with_format(&staging, target_format)
+                .map_err(anyhow::Error::from)
+        }
+    };
+    if let Err(err) = encoded {
+        let _ = fs::remove_file(&staging);
+        return Err(err);
+    }
+
+    fs::rename(&staging, &converted)?;
+    if converted != path {
+        fs::remove_file(path)?;
+    }
+    Ok(Some(converted))
+}
+
+/// Link later copies of identical content to the first one. Only files
+/// sharing a size with another can match, so only those are hashed.
+/// Returns how many files were replaced.
+fn link_identical_files(files: &[PathBuf]) -> usize {
+    let mut sized: Vec<(&Path, u64)> = Vec::with_capacity(files.len());
+    let mut files_of_size: HashMap<u64, usize> = HashMap::new();
+    for file in files {
+        match fs::metadata(file) {
+            Ok(meta) => {
+                sized.push((file.as_path(), meta.len()));
+                *files_of_size.entry(meta.len()).or_default() += 1;
+            }
+            Err(err) => log::warn!("Failed to read {} for dedupe: {}", file.display(), err),
+        }
+    }
+
+    let mut first_by_hash: HashMap<(u64, [u8; 32]), &Path> = HashMap::new();
+    let mut linked = 0usize;
+
+    for (file, size) in sized {
+        if files_of_size[&size] < 2 {
+            continue;
+        }
+        let digest = match hash_file(file) {
+            Ok(digest) => digest,
+            Err(err) => {
+                log::warn!("Failed to read {} for dedupe: {}", file.display(), err);
+                continue;
+            }
+        };
+
+        match first_by_hash.get(&(size, digest)) {
+            // Both files were written by this run, so replacing is safe
+            Some(original) => match link_or_copy(original, file, CollisionPolicy::Overwrite) {
+                Ok(_) => {
+                    log::info!(
+                        "{} has the same content as {}; linked",
+                        file.display(),
+                        original.display()
+                    );
+                    linked += 1;
+                }
+                Err(err) => log::warn!("Failed to link duplicate {}: {}", file.display(), err),
+            },
+            None => {
+                first_by_hash.insert((size, digest), file);
+            }
+        }
+    }
+
+    linked
+}
+
+/// SHA-256 of a file, streamed so large videos are never held in memory
+fn hash_file(path: &Path) -> std::io::Result<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut fs::File::open(path)?, &mut hasher)?;
+    Ok(hasher.finalize().into())
+}
+
+/// Sequence number of each result's first file when every file is numbered
+/// in posting order, oldest post first, plus the zero-padding width that
+/// keeps the numbers sorting as text
+fn chronological_sequence(results: &[SearchResult]) -> (Vec<usize>, usize) {
+    let mut order: Vec<usize> = (0..results.len()).collect();
+    order.sort_by(|a, b| {
+        let (a, b) = (&results[*a], &results[*b]);
+        a.created_at
+            .cmp(&b.created_at)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+
+    let mut starts = vec![0; results.len()];
+    let mut next = 1;
+    for index in order {
+        starts[index] = next;
+        next += results[index].media.len();
+    }
+    let width = (next - 1).max(1).to_string().len().max(4);
+    (starts, width)
+}
+
+/// Write `manifest` into `root`, logging failures. Returns whether it was saved.
+fn write_manifest(root: &Path, manifest: &DownloadManifest) -> bool {
+    let manifest_path = root.join(MANIFEST_FILE_NAME);
+    match serde_json::to_string_pretty(manifest) {
+        Ok(json) => match fs::write(&manifest_path, json) {
+            Ok(()) => true,
+            Err(err) => {
+                log::error!(
+                    "Failed to write download manifest {}: {}",
+                    manifest_path.display(),
+                    err
+                );
+                false
+            }
+        },
+        Err(err) => {
+            log::error!("Failed to serialize download manifest: {}", err);
+            false
+        }
+    }
+}
+
+/// Signed-in instance hosts and their access tokens. Media on these hosts
+/// may be gated behind auth; media anywhere else (CDNs, other instances)
+/// never gets a token.
+fn media_tokens(settings: &AppSettings) -> HashMap<String, String> {
+    let instances = [
+        (&settings.api.pixelfed, &settings.home_instances.pixelfed),
+        (&settings.api.mastodon, &settings.home_instances.mastodon),
+    ];
+
+    instances
+        .into_iter()
+        .filter(|(auth, _)| auth.enabled)
+        .filter_map(|(auth, home_instance)| {
+            let token = auth
+                .access_token
+                .as_deref()
+                .filter(|token| !token.is_empty())?;
+            let instance = match auth.instance_url.trim() {
+                "" => home_instance.trim(),
+                url => url,
+            };
+            let with_scheme = if instance.contains("://") {
+                instance.to_string()
+            } else {
+                format!("https://{}", instance)
+            };
+            let host = url::Url::parse(&with_scheme).ok()?.host_str()?.to_string();
+            Some((host, token.to_string()))
+        })
+        .collect()
+}
 
 pub struct DownloadService {
     client: reqwest::Client,
     settings: AppSettings,
+    media_tokens: HashMap<String, String>,
 }
 
 impl DownloadService {
     pub fn new(settings: AppSettings) -> Self {
+        // No overall timeout: large videos may legitimately take minutes.
+        // Stalls are caught per chunk in download_file instead.
+        // Media is saved byte for byte as served, and sizes from HEAD must
+        // match what is written, so no compression is negotiated here
+        let client = client_builder(&settings.network)
+            .no_gzip()
+            .no_deflate()
+            .no_brotli()
+            .connect_timeout(Duration::from_secs(
+                settings.download.timeout_secs.max(1) as u64
+            ))
+            .build()
+            .expect("Failed to create HTTP client");
+
         Self {
-            client: reqwest::Client::new(),
+            client,
+            media_tokens: media_tokens(&settings),
             settings,
         }
     }
 
+    /// Access token to send with `url`, only when it is on the exact host of
+    /// a signed-in instance. reqwest drops the header if the instance then
+    /// redirects to another host.
+    fn token_for(&self, url: &str) -> Option<String> {
+        let host = url::Url::parse(url).ok()?.host_str()?.to_ascii_lowercase();
+        self.media_tokens.get(&host).cloned()
+    }
+
     pub async fn download_all<F>(
         &self,
         context: Option<SearchContext>,
         groups: Vec<PlatformSearchResults>,
-        mut progress_callback: F,
-    ) -> Result<PathBuf>
+        control: &DownloadControl,
+        progress_callback: F,
+    ) -> Result<DownloadSummary>
     where
         F: FnMut(f64),
     {
@@ -43,139 +645,1007 @@ impl DownloadService {
             return Err(anyhow::anyhow!("No results to download"));
         }
 
-        let total_files: usize = results.iter().map(|result| result.media_urls.len()).sum();
+        let total_files: usize = results.iter().map(|result| result.media.len()).sum();
         if total_files == 0 {
             return Err(anyhow::anyhow!("No media attachments to download"));
         }
 
+        self.check_destination(&results).await?;
+
         let download_root = self.create_download_root(context.as_ref())?;
+        self.plan_and_run(download_root, results, control, progress_callback)
+            .await
+    }
+
+    /// Download posts into an existing folder, e.g. one created earlier with
+    /// `create_download_root` for posts that arrive over time
+    pub async fn download_into<F>(
+        &self,
+        download_root: &Path,
+        results: Vec<SearchResult>,
+        control: &DownloadControl,
+        progress_callback: F,
+    ) -> Result<DownloadSummary>
+    where
+        F: FnMut(f64),
+    {
+        if results.iter().all(|result| result.media.is_empty()) {
+            return Err(anyhow::anyhow!("No media attachments to download"));
+        }
+
+        self.check_destination(&results).await?;
+        fs::create_dir_all(download_root)?;
+        self.plan_and_run(
+            download_root.to_path_buf(),
+            results,
+            control,
+            progress_callback,
+        )
+        .await
+    }
+
+    /// Plan every file, write sidecars, then download the planned files
+    async fn plan_and_run<F>(
+        &self,
+        download_root: PathBuf,
+        results: Vec<SearchResult>,
+        control: &DownloadControl,
+        progress_callback: F,
+    ) -> Result<DownloadSummary>
+    where
+        F: FnMut(f64),
+    {
+        let total_files: usize = results.iter().map(|result| result.media.len()).sum();
+        let mut ensured_dirs: HashSet<PathBuf> = HashSet::new();
+
+        // Plan every file up front so an interrupted run can be recorded
+        let dedupe = self.settings.download.dedupe_across_platforms;
+        let mut first_by_url: HashMap<&str, usize> = HashMap::new();
+        let mut planned: Vec<ManifestEntry> = Vec::with_capacity(total_files);
+        let sequence = self
+            .settings
+            .download
+            .sequence_prefix
+            .then(|| chronological_sequence(&results));
+        for (result_index, result) in results.iter().enumerate() {
+            let post_dir = self.post_dir(&download_root, result);
+            for (media_index, item) in result.media.iter().enumerate() {
+                let media_url = &item.url;
+                let mut filename = Self::generate_filename(&result.id, media_index, media_url);
+                if let Some((starts, width)) = &sequence {
+                    filename = format!(
+                        "{:0width$}_{}",
+                        starts[result_index] + media_index,
+                        filename,
+                        width = *width
+                    );
+                }
+                let duplicate_of = if dedupe {
+                    match first_by_url.get(media_url.as_str()) {
+                        Some(first) => Some(*first),
+                        None => {
+                            first_by_url.insert(media_url.as_str(), planned.len());
+                            None
+                        }
+                    }
+                } else {
+                    None
+                };
+                planned.push(ManifestEntry {
+                    url: media_url.clone(),
+                    file: post_dir.join(filename),
+                    completed: false,
+                    skipped: false,
+                    duplicate_of,
+                    post: Some((result.platform, result.id.clone())),
+                });
+            }
+        }
+
+        if self.settings.download.save_metadata_sidecar {
+            for result in results.iter().filter(|result| !result.media.is_empty()) {
+                let post_dir = self.post_dir(&download_root, result);
+                if ensured_dirs.insert(post_dir.clone()) {
+                    fs::create_dir_all(&post_dir)?;
+                }
+                if let Err(err) = self.write_sidecar(result, &post_dir) {
+                    log::warn!("Failed to write metadata for post {}: {}", result.id, err);
+                }
+            }
+        }
+
+        let manifest = DownloadManifest {
+            started_at: Utc::now(),
+            interrupted: false,
+            entries: planned,
+        };
+        self.run_manifest(download_root, manifest, control, progress_callback)
+            .await
+    }
+
+    /// Look for a run that never finished in the download folder (and its
+    /// date subfolders). The most recently started one wins.
+    pub fn find_incomplete(&self) -> Option<ResumableDownload> {
+        let base_path = Path::new(&self.settings.download.base_path);
+        let mut candidates = Vec::new();
+        Self::collect_manifests(base_path, 2, &mut candidates);
+
+        candidates
+            .into_iter()
+            .filter(|found| found.manifest.interrupted && found.manifest.remaining() > 0)
+            .max_by_key(|found| found.manifest.started_at)
+    }
+
+    fn collect_manifests(dir: &Path, depth: u32, found: &mut Vec<ResumableDownload>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+
+            let manifest_path = path.join(MANIFEST_FILE_NAME);
+            if manifest_path.is_file() {
+                match fs::read_to_string(&manifest_path)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|json| Ok(serde_json::from_str::<DownloadManifest>(&json)?))
+                {
+                    Ok(manifest) => found.push(ResumableDownload {
+                        root: path,
+                        manifest,
+                    }),
+                    Err(err) => log::warn!(
+                        "Ignoring unreadable download manifest {}: {}",
+                        manifest_path.display(),
+                        err
+                    ),
+                }
+            } else if depth > 1 {
+                Self::collect_manifests(&path, depth - 1, found);
+            }
+        }
+    }
+
+    /// Forget an unfinished run without downloading the rest
+    pub fn discard_incomplete(resumable: &ResumableDownload) -> Result<()> {
+        fs::remove_file(resumable.root.join(MANIFEST_FILE_NAME))?;
+        Ok(())
+    }
+
+    /// Download only the files an earlier run did not complete
+    pub async fn resume<F>(
+        &self,
+        mut resumable: ResumableDownload,
+        control: &DownloadControl,
+        progress_callback: F,
+    ) -> Result<DownloadSummary>
+    where
+        F: FnMut(f64),
+    {
+        // A file recorded as done but gone from disk is fetched again
+        for entry in resumable
+            .manifest
+            .entries
+            .iter_mut()
+            .filter(|entry| entry.completed && !entry.file.exists())
+        {
+            log::info!("{} is missing; downloading it again", entry.file.display());
+            entry.completed = false;
+        }
+        log::info!(
+            "Resuming download in {} ({} of {} files left)",
+            resumable.root.display(),
+            resumable.manifest.remaining(),
+            resumable.manifest.entries.len()
+        );
+        self.run_manifest(
+            resumable.root,
+            resumable.manifest,
+            control,
+            progress_callback,
+        )
+        .await
+    }
+
+    /// Fetch every entry of `manifest` not yet marked completed
+    async fn run_manifest<F>(
+        &self,
+        download_root: PathBuf,
+        manifest: DownloadManifest,
+        control: &DownloadControl,
+        mut progress_callback: F,
+    ) -> Result<DownloadSummary>
+    where
+        F: FnMut(f64),
+    {
+        let pending_entries: Vec<(usize, ManifestEntry)> = manifest
+            .entries
+            .iter()
+            .cloned()
+            .enumerate()
+            .filter(|(_, entry)| !entry.completed && !entry.skipped && entry.duplicate_of.is_none())
+            .collect();
+        let total_files = pending_entries.len();
+        let mut completed: HashSet<usize> = manifest
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.completed)
+            .map(|(index, _)| index)
+            .collect();
+        let already_completed = completed.len();
+        let mut entries = manifest.entries.clone();
         let mut ensured_dirs: HashSet<PathBuf> = HashSet::new();
         let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(
             self.settings.download.max_concurrent as usize,
         ));
 
+        // With stop_on_error, the first failure cancels the run and is kept here
+        let stop_on_error = self.settings.download.stop_on_error;
+        let first_error: Arc<Mutex<Option<String>>> = Arc::default();
+
+        control.begin(download_root.clone(), manifest);
         progress_callback(0.0);
 
         let mut tasks = Vec::new();
 
-        for result in results {
-            if result.media_urls.is_empty() {
-                continue;
+        for (entry_index, entry) in pending_entries {
+            if control.is_cancelled() {
+                break;
             }
 
-            let platform_dir = download_root.join(result.platform.folder_name());
-            if ensured_dirs.insert(platform_dir.clone()) {
-                fs::create_dir_all(&platform_dir)?;
+            let file_dir = entry
+                .file
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| download_root.clone());
+            if ensured_dirs.insert(file_dir.clone()) {
+                if let Err(err) = fs::create_dir_all(&file_dir) {
+                    control.finish();
+                    return Err(err.into());
+                }
             }
 
-            for (media_index, media_url) in result.media_urls.iter().enumerate() {
-                let permit = semaphore.clone().acquire_owned().await?;
-                let client = self.client.clone();
-                let media_url = media_url.clone();
-                let file_dir = platform_dir.clone();
-                let result_id = result.id.clone();
+            let permit = semaphore.clone().acquire_owned().await?;
+            let client = self.client.clone();
+            let token = self.token_for(&entry.url);
+            let task_control = control.clone();
+            let options = FileOptions {
+                max_bytes: self.max_file_bytes(),
+                convert_to: self.settings.download.convert_images_to,
+                stall_timeout: Duration::from_secs(
+                    self.settings.download.timeout_secs.max(1) as u64
+                ),
+                on_collision: self.settings.download.on_collision,
+            };
+            let retries = self.settings.download.retries;
+            let first_error = first_error.clone();
 
-                let task = tokio::spawn(async move {
-                    let _permit = permit;
-                    if let Err(err) = tokio::fs::create_dir_all(&file_dir).await {
-                        return Err(anyhow::anyhow!(
-                            "Failed to prepare download directory: {}",
-                            err
-                        ));
-                    }
+            let task = tokio::spawn(async move {
+                let _permit = permit;
+                if task_control.is_cancelled() {
+                    return Err(anyhow::anyhow!("Download cancelled"));
+                }
 
-                    let filename = Self::generate_filename(&result_id, media_index, &media_url);
-                    let file_path = file_dir.join(filename);
+                if let Err(err) = tokio::fs::create_dir_all(&file_dir).await {
+                    return Err(anyhow::anyhow!(
+                        "Failed to prepare download directory: {}",
+                        err
+                    ));
+                }
 
-                    Self::download_file(&client, &media_url, &file_path).await
-                });
+                let mut attempt = 0u32;
+                let outcome = loop {
+                    match Self::download_file(
+                        &client,
+                        &entry.url,
+                        token.as_deref(),
+                        (entry_index, &entry.file),
+                        options,
+                        &task_control,
+                    )
+                    .await
+                    {
+                        Err(err)
+                            if attempt < retries
+                                && !task_control.is_cancelled()
+                                && is_retryable(&err) =>
+                        {
+                            attempt += 1;
+                            log::info!(
+                                "Retrying {} ({}/{}) after: {}",
+                                entry.url,
+                                attempt,
+                                retries,
+                                err
+                            );
+                            tokio::time::sleep(Duration::from_secs(attempt as u64)).await;
+                        }
+                        outcome => break outcome,
+                    }
+                };
 
-                tasks.push(task);
-            }
+                // Recorded here, not when the result is collected, so a file
+                // that finished just before a cancel is never taken for a
+                // partial one
+                match &outcome {
+                    Ok(FileOutcome::Downloaded {
+                        file, conversion, ..
+                    }) => {
+                        let saved = match conversion {
+                            Conversion::Converted(path) => path,
+                            _ => file,
+                        };
+                        task_control.mark_completed(entry_index, saved);
+                    }
+                    Ok(FileOutcome::Skipped) => task_control.mark_skipped(entry_index),
+                    Err(_) => {}
+                }
+                if let Err(err) = &outcome {
+                    if stop_on_error && !task_control.is_cancelled() {
+                        first_error
+                            .lock()
+                            .unwrap_or_else(|poisoned| poisoned.into_inner())
+                            .get_or_insert_with(|| format!("{}: {}", entry.url, err));
+                        task_control.cancel();
+                    }
+                }
+                outcome
+            });
+
+            tasks.push((entry_index, task));
         }
 
         let mut downloaded_files = 0usize;
+        let mut skipped_files = 0usize;
+        let mut skipped: HashSet<usize> = entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.skipped)
+            .map(|(index, _)| index)
+            .collect();
+        let mut failed_files = 0usize;
+        let mut conversion_failures = 0usize;
+        let mut total_bytes = 0u64;
 
-        for task in tasks {
-            match task.await? {
-                Ok(_) => {
+        for (entry_index, task) in tasks {
+            // Tasks that already finished still count; the rest stop here
+            if control.is_cancelled() && !task.is_finished() {
+                task.abort();
+            }
+            let outcome = match task.await {
+                Ok(outcome) => outcome,
+                Err(err) if err.is_cancelled() => continue,
+                Err(err) => return Err(err.into()),
+            };
+
+            match outcome {
+                Ok(FileOutcome::Downloaded {
+                    bytes,
+                    file,
+                    conversion,
+                }) => {
+                    completed.insert(entry_index);
                     downloaded_files += 1;
-                    let progress = downloaded_files as f64 / total_files as f64;
-                    progress_callback(progress);
+                    total_bytes += bytes;
+                    entries[entry_index].file = file;
+                    match conversion {
+                        Conversion::Converted(path) => entries[entry_index].file = path,
+                        Conversion::Failed => conversion_failures += 1,
+                        Conversion::Unchanged => {}
+                    }
+                }
+                Ok(FileOutcome::Skipped) => {
+                    skipped_files += 1;
+                    skipped.insert(entry_index);
                 }
                 Err(e) => {
+                    failed_files += 1;
                     log::warn!("Failed to download file: {}", e);
+                    continue;
+                }
+            }
+
+            let progress = (downloaded_files + skipped_files) as f64 / total_files as f64;
+            progress_callback(progress);
+        }
+
+        let first_error = first_error
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take();
+        if let Some(err) = first_error {
+            // Other workers may have finished files after the failure; the
+            // control saw every one of them
+            let saved = control.completed_count().saturating_sub(already_completed);
+            control.flush_partial();
+            return Err(anyhow::anyhow!(
+                "Download stopped at the first failed file ({}). {} of {} files were saved; partial manifest in {}",
+                err,
+                saved,
+                total_files,
+                download_root.display()
+            ));
+        }
+
+        if control.is_cancelled() {
+            control.flush_partial();
+            return Err(anyhow::anyhow!(
+                "Download cancelled after {} of {} files. Partial manifest saved in {}",
+                downloaded_files,
+                total_files,
+                download_root.display()
+            ));
+        }
+
+        let mut duplicate_files = 0usize;
+        if self.settings.download.dedupe_across_platforms {
+            // Same URL: link to the copy fetched for the first occurrence
+            for (index, entry) in entries.iter().enumerate() {
+                let Some(original) = entry.duplicate_of else {
+                    continue;
+                };
+                if entry.completed || !completed.contains(&original) {
+                    continue;
                 }
+                // Follow the original's extension in case it was converted
+                let target = entry
+                    .file
+                    .with_extension(entries[original].file.extension().unwrap_or_default());
+                match link_or_copy(
+                    &entries[original].file,
+                    &target,
+                    self.settings.download.on_collision,
+                ) {
+                    Ok(Some(linked)) => {
+                        control.mark_completed(index, &linked);
+                        completed.insert(index);
+                        duplicate_files += 1;
+                    }
+                    Ok(None) => {
+                        log::info!("Keeping existing {}", target.display());
+                        control.mark_skipped(index);
+                        skipped.insert(index);
+                    }
+                    Err(err) => {
+                        log::warn!("Failed to link duplicate {}: {}", entry.file.display(), err)
+                    }
+                }
+            }
+
+            // Different URLs, same bytes: typical for cross-posted media
+            let fetched: Vec<PathBuf> = entries
+                .iter()
+                .enumerate()
+                .filter(|(index, entry)| completed.contains(index) && entry.duplicate_of.is_none())
+                .map(|(_, entry)| entry.file.clone())
+                .collect();
+            duplicate_files += tokio::task::spawn_blocking(move || link_identical_files(&fetched))
+                .await
+                .unwrap_or(0);
+        }
+
+        // (failed, total) files per post; skipped files count as retrieved
+        let mut per_post: HashMap<(Platform, String), (usize, usize)> = HashMap::new();
+        for (index, entry) in entries.iter().enumerate() {
+            let Some(post) = &entry.post else {
+                continue;
+            };
+            let counts = per_post.entry(post.clone()).or_default();
+            counts.1 += 1;
+            if !completed.contains(&index) && !skipped.contains(&index) {
+                counts.0 += 1;
             }
         }
+        let media_status = per_post
+            .into_iter()
+            .map(|(post, (failed, total))| (post, MediaStatus::from_counts(failed, total)))
+            .collect();
+
+        control.finish();
+        Ok(DownloadSummary {
+            root: download_root,
+            downloaded: downloaded_files,
+            skipped: skipped_files,
+            duplicates: duplicate_files,
+            failed: failed_files,
+            conversion_failed: conversion_failures,
+            bytes: total_bytes,
+            media_status,
+        })
+    }
 
-        Ok(download_root)
+    /// Size cap from settings in bytes; `None` or 0 means no cap
+    fn max_file_bytes(&self) -> Option<u64> {
+        self.settings
+            .download
+            .max_file_size_mb
+            .filter(|mb| *mb > 0)
+            .map(|mb| mb as u64 * 1024 * 1024)
     }
 
-    fn create_download_root(&self, context: Option<&SearchContext>) -> Result<PathBuf> {
+    /// Verify the download folder is usable and has room for the media before
+    /// any file is written
+    async fn check_destination(&self, results: &[SearchResult]) -> Result<()> {
         let base_path = Path::new(&self.settings.download.base_path);
+
+        if !base_path.is_dir() {
+            return Err(anyhow::anyhow!(
+                "Download folder {} does not exist. Is the drive connected? Choose another folder in Settings.",
+                base_path.display()
+            ));
+        }
+
+        let probe = base_path.join(".fedi-sleuth-write-test");
+        fs::write(&probe, b"").map_err(|err| {
+            anyhow::anyhow!(
+                "Download folder {} is not writable: {}",
+                base_path.display(),
+                err
+            )
+        })?;
+        let _ = fs::remove_file(&probe);
+
+        let (estimated_bytes, unknown_files) = self.estimate_size(results).await;
+        let available_bytes = fs2::available_space(base_path)?;
+
+        log::info!(
+            "Estimated download size {} ({} file(s) of unknown size), {} free in {}",
+            format_bytes(estimated_bytes),
+            unknown_files,
+            format_bytes(available_bytes),
+            base_path.display()
+        );
+
+        // Only files with a known size count, so this never blocks on a guess
+        if estimated_bytes > available_bytes {
+            return Err(anyhow::anyhow!(
+                "Not enough free space in {}: about {} needed, {} available",
+                base_path.display(),
+                format_bytes(estimated_bytes),
+                format_bytes(available_bytes)
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Sum the Content-Length of every media file via HEAD requests. Returns
+    /// the known total and how many files did not report a size.
+    ///
+    /// Runs up to `max_concurrent` requests at once, like the downloads.
+    pub async fn estimate_size(&self, results: &[SearchResult]) -> (u64, usize) {
+        let limit = self.settings.download.max_concurrent.max(1) as usize;
+        let max_bytes = self.max_file_bytes();
+        let mut seen: HashSet<&str> = HashSet::new();
+        let dedupe = self.settings.download.dedupe_across_platforms;
+        let mut urls = results
+            .iter()
+            .flat_map(|result| result.media.iter().map(|item| &item.url))
+            .filter(|url| !dedupe || seen.insert(url.as_str()));
+        let mut in_flight = FuturesUnordered::new();
+        for url in urls.by_ref().take(limit) {
+            in_flight.push(self.head_content_length(url));
+        }
+
+        let mut total = 0u64;
+        let mut unknown = 0usize;
+
+        while let Some(length) = in_flight.next().await {
+            if let Some(url) = urls.next() {
+                in_flight.push(self.head_content_length(url));
+            }
+
+            match length {
+                // Files over the size cap will be skipped, so they need no space
+                Some(bytes) if max_bytes.is_some_and(|limit| bytes > limit) => {}
+                Some(bytes) => total += bytes,
+                None => unknown += 1,
+            }
+        }
+
+        (total, unknown)
+    }
+
+    /// Size a HEAD request advertises. Read from the header itself: hyper
+    /// reports the (empty) HEAD body's length as `content_length()`.
+    async fn head_content_length(&self, url: &str) -> Option<u64> {
+        let mut request = self.client.head(url);
+        if let Some(token) = self.token_for(url) {
+            request = request.bearer_auth(token);
+        }
+        request
+            .timeout(std::time::Duration::from_secs(10))
+            .send()
+            .await
+            .ok()
+            .filter(|response| response.status().is_success())
+            .and_then(|response| {
+                response
+                    .headers()
+                    .get(reqwest::header::CONTENT_LENGTH)?
+                    .to_str()
+                    .ok()?
+                    .trim()
+                    .parse()
+                    .ok()
+            })
+    }
+
+    /// Folder a post's files go in: the platform folder, plus the author's
+    /// folder when organizing by author
+    fn post_dir(&self, download_root: &Path, result: &SearchResult) -> PathBuf {
+        let platform_dir = if self.settings.download.platform_folders() {
+            download_root.join(result.platform.folder_name())
+        } else {
+            download_root.to_path_buf()
+        };
+        if self.settings.download.organize_by_author {
+            let author = result.filing_author(self.settings.download.boost_filing);
+            platform_dir.join(sanitize_path_component(author))
+        } else {
+            platform_dir
+        }
+    }
+
+    /// Create the folder for one search by rendering the path template
+    /// under `base_path`. `{platform}` folders are added per post.
+    pub fn create_download_root(&self, context: Option<&SearchContext>) -> Result<PathBuf> {
+        let download = &self.settings.download;
+        if let Some(problem) = download.path_template_problem() {
+            return Err(anyhow::anyhow!("Download path template: {}", problem));
+        }
+
+        let base_path = Path::new(&download.base_path);
         let now = Utc::now();
 
-        let mut root = if self.settings.download.organize_by_date {
-            base_path.join(now.format("%Y-%m-%d").to_string())
+        // {date} drops out when organizing by date is off
+        let date = if download.organize_by_date {
+            now.format("%Y-%m-%d").to_string()
         } else {
-            base_path.to_path_buf()
+            String::new()
+        };
+        let (search_type, query, days) = match context {
+            Some(ctx) => (
+                ctx.search_type.slug().to_string(),
+                if ctx.search_type.needs_query() {
+                    ctx.get_folder_name()
+                } else {
+                    String::new()
+                },
+                format!("{}d", ctx.days_back),
+            ),
+            None => ("search".to_string(), String::new(), "any".to_string()),
         };
+        let values = [
+            ("{date}", date),
+            ("{type}", search_type),
+            ("{query}", query),
+            ("{days}", days),
+            ("{time}", now.format("%H%M%S").to_string()),
+        ];
 
-        let (query_folder, days_segment) = context
-            .map(|ctx| {
-                let prefix = match ctx.search_type {
-                    SearchType::User => "user",
-                    SearchType::Hashtag => "hashtag",
-                };
-                (
-                    format!("{}-{}", prefix, ctx.get_folder_name()),
-                    format!("{}d", ctx.days_back),
-                )
-            })
-            .unwrap_or_else(|| ("search".to_string(), "any".to_string()));
+        let mut root = base_path.to_path_buf();
+        for segment in download.download_path_template.trim().split(['/', '\\']) {
+            if segment.trim() == "{platform}" {
+                continue;
+            }
+            let rendered = values
+                .iter()
+                .fold(segment.to_string(), |text, (token, value)| {
+                    text.replace(token, value)
+                });
+            // Empty tokens leave no stray separators: "bookmarks--180d" → "bookmarks-180d"
+            let mut tidy = String::with_capacity(rendered.len());
+            for c in rendered.trim().chars() {
+                if !(c == '-' && tidy.ends_with('-')) {
+                    tidy.push(c);
+                }
+            }
+            let tidy = tidy.trim_matches(|c: char| c == '-' || c == '_' || c.is_whitespace());
+            if !tidy.is_empty() {
+                root.push(sanitize_path_component(tidy));
+            }
+        }
 
-        root = root.join(format!(
-            "{}-{}-{}",
-            query_folder,
-            days_segment,
-            now.format("%H%M%S")
-        ));
+        if !root.starts_with(base_path) || root == base_path {
+            return Err(anyhow::anyhow!(
+                "Download path template must produce a folder inside {}",
+                base_path.display()
+            ));
+        }
 
         fs::create_dir_all(&root)?;
 
         Ok(root)
     }
 
-    async fn download_file(client: &reqwest::Client, url: &str, file_path: &Path) -> Result<()> {
-        let response = client.get(url).send().await?;
+    /// Fetch `url` into the planned file of manifest entry `index`. Whatever
+    /// this creates is tracked in `control` until it is complete, and removed
+    /// again on any failure.
+    async fn download_file(
+        client: &reqwest::Client,
+        url: &str,
+        token: Option<&str>,
+        (index, file_path): (usize, &Path),
+        options: FileOptions,
+        control: &DownloadControl,
+    ) -> Result<FileOutcome> {
+        let FileOptions {
+            max_bytes,
+            convert_to,
+            stall_timeout,
+            on_collision,
+        } = options;
 
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!(
-                "Failed to download: HTTP {}",
-                response.status()
-            ));
+        let renamed;
+        let file_path = match on_collision {
+            CollisionPolicy::Skip if file_path.exists() => {
+                log::info!("Skipping {} ({} exists)", url, file_path.display());
+                return Ok(FileOutcome::Skipped);
+            }
+            CollisionPolicy::Rename if file_path.exists() => {
+                renamed = free_path(file_path);
+                log::info!(
+                    "{} exists; saving as {}",
+                    file_path.display(),
+                    renamed.display()
+                );
+                renamed.as_path()
+            }
+            _ => file_path,
+        };
+
+        let mut request = client.get(url);
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = tokio::time::timeout(stall_timeout, request.send())
+            .await
+            .map_err(|_| Stalled::NoResponse(stall_timeout.as_secs()))??
+            .error_for_status()?;
+
+        if let (Some(limit), Some(length)) = (max_bytes, response.content_length()) {
+            if length > limit {
+                log::info!("Skipping {} ({} bytes is over the size cap)", url, length);
+                return Ok(FileOutcome::Skipped);
+            }
         }
 
+        control.start_writing(index, file_path);
+        let written = match Self::write_body(response, file_path, options, control).await {
+            Ok(Some(written)) => written,
+            streamed => {
+                // Nothing half written survives, so a retry starts clean and
+                // Rename does not leave `_N` orphans behind
+                let _ = tokio::fs::remove_file(file_path).await;
+                control.stop_writing(index);
+                return streamed.map(|_| {
+                    log::info!("Skipping {} (stream exceeded the size cap)", url);
+                    FileOutcome::Skipped
+                });
+            }
+        };
+
+        let conversion = match convert_to {
+            Some(target) => {
+                let source = file_path.to_path_buf();
+                match tokio::task::spawn_blocking(move || convert_image(&source, target)).await {
+                    Ok(Ok(Some(converted))) => Conversion::Converted(converted),
+                    Ok(Ok(None)) => Conversion::Unchanged,
+                    Ok(Err(err)) => {
+                        log::warn!("Failed to convert {}: {}", file_path.display(), err);
+                        Conversion::Failed
+                    }
+                    Err(err) => {
+                        log::warn!(
+                            "Conversion task for {} failed: {}",
+                            file_path.display(),
+                            err
+                        );
+                        Conversion::Failed
+                    }
+                }
+            }
+            None => Conversion::Unchanged,
+        };
+
+        Ok(FileOutcome::Downloaded {
+            bytes: written,
+            file: file_path.to_path_buf(),
+            conversion,
+        })
+    }
+
+    /// Stream the response body into a new file at `file_path`. Returns the
+    /// bytes written, or `None` when the body ran over the size cap.
+    async fn write_body(
+        response: reqwest::Response,
+        file_path: &Path,
+        options: FileOptions,
+        control: &DownloadControl,
+    ) -> Result<Option<u64>> {
         let mut file = tokio::fs::File::create(file_path).await?;
         let mut stream = response.bytes_stream();
+        let mut written = 0u64;
+
+        loop {
+            // A dead connection must not hold a concurrency slot forever
+            let chunk = match tokio::time::timeout(options.stall_timeout, stream.next()).await {
+                Ok(Some(chunk)) => chunk?,
+                Ok(None) => break,
+                Err(_) => return Err(Stalled::NoData(options.stall_timeout.as_secs()).into()),
+            };
+            if control.is_cancelled() {
+                return Err(anyhow::anyhow!("Download cancelled"));
+            }
+
+            // No Content-Length (chunked): enforce the cap while streaming
+            written += chunk.len() as u64;
+            if options.max_bytes.is_some_and(|limit| written > limit) {
+                return Ok(None);
+            }
 
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk?;
             file.write_all(&chunk).await?;
         }
 
         file.flush().await?;
+        Ok(Some(written))
+    }
+
+    fn write_sidecar(&self, result: &SearchResult, dir: &Path) -> Result<()> {
+        let format = self.settings.download.sidecar_format;
+        let path = dir.join(format!(
+            "{}.{}",
+            sanitize_path_component(&result.id),
+            format.extension()
+        ));
+        let content = match self.settings.download.max_sidecar_text_chars {
+            0 => result.content.as_str().into(),
+            limit => truncate_text(&result.content, limit as usize),
+        };
+
+        let body = match format {
+            SidecarFormat::Json => serde_json::to_string_pretty(&serde_json::json!({
+                "platform": result.platform.name(),
+                "id": result.id,
+                "author": result.author,
+                "created_at": result.created_at.to_rfc3339(),
+                "content": content,
+                "url": result.url,
+                "likes": result.likes,
+                "shares": result.shares,
+                "reblogged_by": result.reblogged_by,
+                "account_profile": result.account_profile,
+                "poll": result.poll,
+                "media_urls": result.media.iter().map(|item| &item.url).collect::<Vec<_>>(),
+                "media": result.media,
+            }))?,
+            SidecarFormat::Text => format!(
+                "Author: {}\n{}Date: {}\nURL: {}\nLikes: {}\nShares: {}\n\n{}\n{}{}",
+                result.author,
+                result
+                    .reblogged_by
+                    .as_ref()
+                    .map(|booster| format!("Boosted by: {}\n", booster))
+                    .unwrap_or_default(),
+                result.created_at.to_rfc3339(),
+                result.url,
+                result.likes,
+                result.shares,
+                content,
+                result.poll.as_ref().map(poll_text).unwrap_or_default(),
+                result
+                    .account_profile
+                    .as_ref()
+                    .map(profile_text)
+                    .unwrap_or_default()
+            ),
+        };
+
+        fs::write(path, body)?;
         Ok(())
     }
 
     fn generate_filename(post_id: &str, media_index: usize, url: &str) -> String {
-        // Extract file extension from URL
-        let extension = Path::new(url)
+        // Extract file extension from the URL path, ignoring any query string
+        let path = url.split(['?', '#']).next().unwrap_or(url);
+        let extension = Path::new(path)
             .extension()
             .and_then(|ext| ext.to_str())
+            .filter(|ext| ext.len() <= 5 && ext.chars().all(|c| c.is_ascii_alphanumeric()))
             .unwrap_or("jpg");
 
-        format!("{}_{:03}.{}", post_id, media_index + 1, extension)
+        // Bluesky ids are AT-URIs full of '/' and ':'
+        format!(
+            "{}_{:03}.{}",
+            sanitize_path_component(post_id),
+            media_index + 1,
+            extension
+        )
+    }
+}
+
+/// Text sidecar section for a post's poll
+fn poll_text(poll: &Poll) -> String {
+    let mut text = format!("\nPoll ({}):\n", poll.summary());
+    for option in &poll.options {
+        match (option.votes_count, poll.percent(option)) {
+            (Some(votes), Some(percent)) => {
+                text.push_str(&format!("- {}: {} ({}%)\n", option.title, votes, percent))
+            }
+            _ => text.push_str(&format!("- {}\n", option.title)),
+        }
+    }
+    text
+}
+
+/// Text sidecar section for the searched account's profile
+fn profile_text(profile: &AccountProfile) -> String {
+    let mut text = format!(
+        "\nProfile of @{} as of {}\nFollowers: {}\nFollowing: {}\nPosts: {}\n",
+        profile.handle,
+        profile.fetched_at.to_rfc3339(),
+        profile.followers,
+        profile.following,
+        profile.posts
+    );
+    if let Some(name) = &profile.display_name {
+        text.push_str(&format!("Name: {}\n", name));
+    }
+    if let Some(bio) = &profile.description {
+        text.push_str(&format!("Bio:\n{}\n", bio.trim()));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flatten_puts_transparent_pixels_on_white() {
+        let image = image::DynamicImage::ImageRgba8(
+            image::RgbaImage::from_vec(3, 1, vec![0, 0, 0, 0, 10, 20, 30, 255, 0, 0, 0, 128])
+                .unwrap(),
+        );
+        let flat = flatten_onto_white(&image);
+        assert_eq!(flat.get_pixel(0, 0).0, [255, 255, 255]);
+        assert_eq!(flat.get_pixel(1, 0).0, [10, 20, 30]);
+        assert_eq!(flat.get_pixel(2, 0).0, [127, 127, 127]);
+    }
+
+    #[tokio::test]
+    async fn head_size_comes_from_the_content_length_header() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/photo.jpg", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let _ = socket.read(&mut request).await;
+            let _ = socket
+                .write_all(
+                    b"HTTP/1.1 200 OK\r\nContent-Length: 123456\r\nConnection: close\r\n\r\n",
+                )
+                .await;
+        });
+
+        let service = DownloadService::new(AppSettings::default());
+        assert_eq!(service.head_content_length(&url).await, Some(123456));
+    }
+
+    #[test]
+    fn stalls_are_retried_but_local_errors_are_not() {
+        assert!(is_retryable(&Stalled::NoData(30).into()));
+        assert!(is_retryable(&Stalled::NoResponse(30).into()));
+        let denied = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+        assert!(!is_retryable(&denied.into()));
+        assert!(!is_retryable(&anyhow::anyhow!("Download cancelled")));
     }
 }
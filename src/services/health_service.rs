@@ -0,0 +1,105 @@
+// ============================================================================
+// Health Service - Background check of stored credentials
+// ============================================================================
+// Verifies each enabled platform's credentials (verify_credentials for the
+// OAuth platforms, createSession for Bluesky) concurrently so expired tokens
+// show up in the header before a search fails on them.
+// ============================================================================
+
+use anyhow::Result;
+
+use crate::models::{AppSettings, NetworkSettings, Platform, PlatformAuth};
+use crate::services::{AuthService, BlueskyService};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CredentialStatus {
+    Checking,
+    Valid,
+    Invalid(String),
+}
+
+pub struct HealthService;
+
+impl HealthService {
+    /// Check every enabled platform at once. Disabled platforms are left out.
+    pub async fn check_all(settings: &AppSettings) -> Vec<(Platform, CredentialStatus)> {
+        let (pixelfed, mastodon, bluesky) = tokio::join!(
+            Self::check_oauth(
+                &settings.api.pixelfed,
+                &settings.home_instances.pixelfed,
+                &settings.network
+            ),
+            Self::check_oauth(
+                &settings.api.mastodon,
+                &settings.home_instances.mastodon,
+                &settings.network
+            ),
+            Self::check_bluesky(settings),
+        );
+
+        [
+            (Platform::Pixelfed, pixelfed),
+            (Platform::Mastodon, mastodon),
+            (Platform::Bluesky, bluesky),
+        ]
+        .into_iter()
+        .filter_map(|(platform, outcome)| {
+            let outcome = outcome?;
+            let status = match outcome {
+                Ok(()) => CredentialStatus::Valid,
+                Err(err) => {
+                    log::warn!(
+                        "{} credentials failed verification: {}",
+                        platform.name(),
+                        err
+                    );
+                    CredentialStatus::Invalid(err.to_string())
+                }
+            };
+            Some((platform, status))
+        })
+        .collect()
+    }
+
+    async fn check_oauth(
+        auth: &PlatformAuth,
+        home_instance: &str,
+        network: &NetworkSettings,
+    ) -> Option<Result<()>> {
+        if !auth.enabled {
+            return None;
+        }
+        let Some(token) = auth
+            .access_token
+            .as_deref()
+            .filter(|token| !token.is_empty())
+        else {
+            return Some(Err(anyhow::anyhow!("Not signed in")));
+        };
+
+        let instance = match auth.instance_url.trim() {
+            "" => home_instance.trim(),
+            url => url,
+        };
+        let instance_url = if instance.starts_with("http://") || instance.starts_with("https://") {
+            instance.trim_end_matches('/').to_string()
+        } else {
+            format!("https://{}", instance.trim_end_matches('/'))
+        };
+
+        let service = match AuthService::new(auth.clone(), &instance_url)
+            .and_then(|service| service.with_network(network))
+        {
+            Ok(service) => service,
+            Err(err) => return Some(Err(err)),
+        };
+        Some(service.verify_token(token).await.map(|_| ()))
+    }
+
+    async fn check_bluesky(settings: &AppSettings) -> Option<Result<()>> {
+        if !settings.api.bluesky.enabled {
+            return None;
+        }
+        Some(BlueskyService::new(settings).verify_credentials().await)
+    }
+}
@@ -0,0 +1,110 @@
+// ============================================================================
+// History Service - Log of finished download runs
+// ============================================================================
+// Appends one record per completed download (what was searched, how many
+// files came from each platform, where they went) to a JSON file in the app
+// data directory so earlier runs can be found again from the History view.
+// ============================================================================
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{PlatformSearchResults, SearchContext};
+use crate::services::DownloadSummary;
+
+/// Oldest records are dropped beyond this many
+const MAX_RECORDS: usize = 500;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DownloadRecord {
+    pub timestamp: DateTime<Utc>,
+    pub query: String,
+    /// Posts per platform (name, count) in the downloaded result set
+    pub platform_counts: Vec<(String, usize)>,
+    pub files: usize,
+    pub destination: PathBuf,
+    pub total_bytes: u64,
+}
+
+impl DownloadRecord {
+    pub fn new(
+        context: Option<&SearchContext>,
+        groups: &[PlatformSearchResults],
+        summary: &DownloadSummary,
+    ) -> Self {
+        let query = match context {
+            Some(ctx) if ctx.search_type.needs_query() => {
+                format!("{} \"{}\"", ctx.search_type.slug(), ctx.query)
+            }
+            Some(ctx) => ctx.search_type.slug().to_string(),
+            None => summary
+                .root
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "Download".to_string()),
+        };
+
+        let platform_counts = groups
+            .iter()
+            .filter(|group| group.error.is_none() && !group.results.is_empty())
+            .map(|group| (group.platform.name().to_string(), group.results.len()))
+            .collect();
+
+        Self {
+            timestamp: Utc::now(),
+            query,
+            platform_counts,
+            files: summary.downloaded + summary.duplicates,
+            destination: summary.root.clone(),
+            total_bytes: summary.bytes,
+        }
+    }
+}
+
+pub struct HistoryService;
+
+impl HistoryService {
+    /// All recorded runs, oldest first. A missing or unreadable file is an
+    /// empty history.
+    pub fn load() -> Vec<DownloadRecord> {
+        Self::history_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn record(record: DownloadRecord) -> Result<()> {
+        let mut records = Self::load();
+        records.push(record);
+        if records.len() > MAX_RECORDS {
+            records.drain(..records.len() - MAX_RECORDS);
+        }
+        Self::store(&records)
+    }
+
+    pub fn clear() -> Result<()> {
+        Self::store(&[])
+    }
+
+    fn history_path() -> Option<PathBuf> {
+        Some(
+            dirs::data_dir()?
+                .join("pixelfed-rust")
+                .join("download_history.json"),
+        )
+    }
+
+    fn store(records: &[DownloadRecord]) -> Result<()> {
+        let path = Self::history_path()
+            .ok_or_else(|| anyhow::anyhow!("No data directory for download history"))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(records)?)?;
+        Ok(())
+    }
+}
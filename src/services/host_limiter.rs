@@ -0,0 +1,80 @@
+// ============================================================================
+// Host Limiter - Per-instance request concurrency
+// ============================================================================
+// Keeps one semaphore per instance host so that, no matter how many queries
+// target the same server, only a bounded number of requests run against it
+// at once. Shared by every platform service.
+// ============================================================================
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+static GLOBAL_LIMITER: OnceLock<HostLimiter> = OnceLock::new();
+
+struct HostSlot {
+    limit: u32,
+    semaphore: Arc<Semaphore>,
+}
+
+/// Keyed semaphore map, one entry per instance host
+pub struct HostLimiter {
+    slots: Mutex<HashMap<String, HostSlot>>,
+}
+
+impl HostLimiter {
+    /// Process-wide limiter used by all services
+    pub fn global() -> &'static HostLimiter {
+        GLOBAL_LIMITER.get_or_init(|| HostLimiter {
+            slots: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Wait for a free request slot on the host of `url`.
+    ///
+    /// The returned permit must be held for the lifetime of the request.
+    pub async fn acquire(&self, url: &str, limit: u32) -> OwnedSemaphorePermit {
+        let limit = limit.max(1);
+        let host = host_key(url);
+
+        let semaphore = {
+            let mut slots = self
+                .slots
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+            let slot = slots.entry(host.clone()).or_insert_with(|| HostSlot {
+                limit,
+                semaphore: Arc::new(Semaphore::new(limit as usize)),
+            });
+
+            // Settings changed since the slot was created; start a fresh one.
+            // Requests holding permits on the old semaphore finish normally.
+            if slot.limit != limit {
+                *slot = HostSlot {
+                    limit,
+                    semaphore: Arc::new(Semaphore::new(limit as usize)),
+                };
+            }
+
+            slot.semaphore.clone()
+        };
+
+        if semaphore.available_permits() == 0 {
+            log::debug!("Waiting for a free request slot on {}", host);
+        }
+
+        semaphore
+            .acquire_owned()
+            .await
+            .expect("host limiter semaphore is never closed")
+    }
+}
+
+fn host_key(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|parsed| parsed.host_str().map(|host| host.to_ascii_lowercase()))
+        .unwrap_or_else(|| url.to_string())
+}
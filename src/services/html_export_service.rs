@@ -0,0 +1,167 @@
+// ============================================================================
+// HTML Export Service - Static page snapshot of a result set
+// ============================================================================
+// Writes a single self-contained index.html into the download root listing
+// each post with its media thumbnails (linked to the originals), author,
+// date and text. The page has inline styles and no scripts so it opens
+// offline in any browser and can be shared as-is.
+// ============================================================================
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use chrono::Utc;
+
+use crate::models::{AppSettings, SearchContext, SearchResult};
+use crate::services::DownloadService;
+
+const PAGE_STYLE: &str = r#"
+body { font-family: system-ui, -apple-system, "Segoe UI", sans-serif; margin: 0; background: #f4f4f5; color: #18181b; }
+header { padding: 24px 32px; background: #18181b; color: #fafafa; }
+header h1 { margin: 0 0 4px; font-size: 22px; }
+header p { margin: 0; color: #a1a1aa; font-size: 13px; }
+main { max-width: 960px; margin: 0 auto; padding: 24px 16px; }
+article { background: #fff; border: 1px solid #e4e4e7; border-radius: 8px; padding: 16px; margin-bottom: 16px; }
+.meta { display: flex; gap: 12px; align-items: baseline; flex-wrap: wrap; font-size: 13px; color: #71717a; }
+.meta strong { color: #18181b; font-size: 15px; }
+.content { white-space: pre-wrap; margin: 10px 0; }
+.media { display: flex; flex-wrap: wrap; gap: 8px; }
+.media img, .media video { width: 200px; height: 200px; object-fit: cover; border-radius: 6px; background: #e4e4e7; }
+.media .link { display: inline-block; padding: 8px 10px; border: 1px solid #e4e4e7; border-radius: 6px; font-size: 13px; }
+footer { text-align: center; color: #a1a1aa; font-size: 12px; padding: 16px; }
+"#;
+
+pub struct HtmlExportService {
+    settings: AppSettings,
+}
+
+impl HtmlExportService {
+    pub fn new(settings: AppSettings) -> Self {
+        Self { settings }
+    }
+
+    /// Write `index.html` for `results` into the download root and return
+    /// its path. Results are listed newest first.
+    pub fn export(
+        &self,
+        context: Option<&SearchContext>,
+        results: &[SearchResult],
+    ) -> Result<PathBuf> {
+        if results.is_empty() {
+            return Err(anyhow::anyhow!("No results to export"));
+        }
+
+        let mut sorted: Vec<&SearchResult> = results.iter().collect();
+        sorted.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+        let title = context
+            .map(|ctx| {
+                if ctx.search_type.needs_query() {
+                    format!("{} \"{}\"", ctx.search_type.slug(), ctx.query)
+                } else {
+                    ctx.search_type.slug().to_string()
+                }
+            })
+            .unwrap_or_else(|| "Search results".to_string());
+        let subtitle = format!(
+            "{} posts{} · exported {} by Fedi-Sleuth",
+            sorted.len(),
+            context
+                .map(|ctx| format!(" from the last {} day(s)", ctx.days_back))
+                .unwrap_or_default(),
+            Utc::now().format("%Y-%m-%d %H:%M UTC")
+        );
+
+        let mut html = String::new();
+        let _ = write!(
+            html,
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+             <title>{title}</title>\n<style>{PAGE_STYLE}</style>\n</head>\n<body>\n\
+             <header><h1>{title}</h1><p>{subtitle}</p></header>\n<main>\n",
+            title = escape_html(&title),
+            subtitle = escape_html(&subtitle),
+        );
+
+        for result in sorted {
+            Self::write_post(&mut html, result);
+        }
+
+        html.push_str("</main>\n<footer>Media links point to the original servers.</footer>\n</body>\n</html>\n");
+
+        let root = DownloadService::new(self.settings.clone()).create_download_root(context)?;
+        let path = root.join("index.html");
+        fs::write(&path, html)?;
+
+        Ok(path)
+    }
+
+    fn write_post(html: &mut String, result: &SearchResult) {
+        let _ = write!(
+            html,
+            "<article>\n<div class=\"meta\"><span>{emoji} {platform}</span><strong>{author}</strong>\
+             <span>{date}</span><span>👍 {likes} · 🔄 {shares}</span>\
+             <a href=\"{url}\">View original</a></div>\n",
+            emoji = result.platform.emoji(),
+            platform = result.platform.name(),
+            author = escape_html(&result.author),
+            date = result.created_at.format("%Y-%m-%d %H:%M"),
+            likes = result.likes,
+            shares = result.shares,
+            url = escape_html(&result.url),
+        );
+
+        if !result.content.is_empty() {
+            let _ = writeln!(
+                html,
+                "<p class=\"content\">{}</p>",
+                escape_html(&result.content)
+            );
+        }
+
+        if !result.media.is_empty() {
+            html.push_str("<div class=\"media\">\n");
+            for (index, item) in result.media.iter().enumerate() {
+                let url = escape_html(&item.url);
+                let _ = if item.is_video() {
+                    writeln!(
+                        html,
+                        "<a href=\"{url}\"><video src=\"{url}\" preload=\"metadata\" muted></video></a>"
+                    )
+                } else if item.is_link() {
+                    writeln!(html, "<a class=\"link\" href=\"{url}\">🔗 {url}</a>")
+                } else {
+                    let alt = item
+                        .description
+                        .clone()
+                        .unwrap_or_else(|| format!("Media {}", index + 1));
+                    writeln!(
+                        html,
+                        "<a href=\"{url}\"><img src=\"{url}\" loading=\"lazy\" alt=\"{}\"></a>",
+                        escape_html(&alt)
+                    )
+                };
+            }
+            html.push_str("</div>\n");
+        }
+
+        html.push_str("</article>\n");
+    }
+}
+
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
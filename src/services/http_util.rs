@@ -0,0 +1,851 @@
+// ============================================================================
+// HTTP Helpers - Shared request/response handling for instance APIs
+// ============================================================================
+// Some instances reject requests without an explicit JSON Accept header, and
+// a misconfigured instance URL or a bot-protection challenge (Cloudflare and
+// the like) answers with an HTML page. These helpers keep the services from
+// feeding HTML into serde, and keep the raw body around when a payload does
+// not match the expected shape. Requests go through `send_checked`, which
+// waits out short rate limits and turns every failure into a `SearchError`.
+// It also counts and times requests for the search that sent them.
+// ============================================================================
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use reqwest::header::LOCATION;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, CONTENT_ENCODING, CONTENT_TYPE};
+use reqwest::redirect::Policy;
+use reqwest::{Certificate, ClientBuilder, RequestBuilder, Response, StatusCode, Url};
+use serde::de::DeserializeOwned;
+
+use crate::models::{NetworkSettings, SearchStats};
+
+/// Sent with every request the app makes
+pub const USER_AGENT: &str = "Fedi-Sleuth/0.1.0";
+
+/// First bytes of a gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Content codings that compress the body. The client strips the header of
+/// any it decoded, so one still present was not decoded.
+const COMPRESSED_CODINGS: [&str; 7] = [
+    "gzip",
+    "x-gzip",
+    "deflate",
+    "br",
+    "zstd",
+    "compress",
+    "x-compress",
+];
+
+/// How much of an unparseable body to include in error messages
+const BODY_PREVIEW_CHARS: usize = 500;
+
+/// Statuses bot-protection services answer a blocked client with
+const CHALLENGE_STATUSES: [StatusCode; 2] =
+    [StatusCode::FORBIDDEN, StatusCode::SERVICE_UNAVAILABLE];
+
+/// Text found on Cloudflare, DDoS-Guard and similar challenge pages
+const CHALLENGE_MARKERS: [&str; 6] = [
+    "cf-chl",
+    "challenge-platform",
+    "just a moment",
+    "attention required",
+    "ddos-guard",
+    "captcha",
+];
+
+/// Most redirects followed for one request
+const MAX_REDIRECTS: usize = 10;
+
+/// A client builder with the app's user agent and the user's connection
+/// pool, keep-alive, certificate and redirect settings. Services add their
+/// headers and timeouts. Responses compressed with gzip, deflate or brotli
+/// are decoded transparently.
+pub fn client_builder(network: &NetworkSettings) -> ClientBuilder {
+    let idle_timeout = match network.pool_idle_timeout_secs {
+        0 => None,
+        secs => Some(Duration::from_secs(secs)),
+    };
+    let keepalive = match network.tcp_keepalive_secs {
+        0 => None,
+        secs => Some(Duration::from_secs(secs)),
+    };
+    let mut builder = reqwest::Client::builder()
+        .user_agent(USER_AGENT)
+        .gzip(true)
+        .deflate(true)
+        .brotli(true)
+        .pool_max_idle_per_host(network.pool_max_idle_per_host)
+        .pool_idle_timeout(idle_timeout)
+        .tcp_keepalive(keepalive)
+        .redirect(if network.follow_redirects {
+            Policy::limited(MAX_REDIRECTS)
+        } else {
+            Policy::none()
+        });
+
+    let ca_path = network.custom_ca_path.trim();
+    if !ca_path.is_empty() {
+        match load_certificate(ca_path) {
+            Ok(certificate) => builder = builder.add_root_certificate(certificate),
+            Err(err) => log::warn!("Ignoring custom CA certificate {}: {}", ca_path, err),
+        }
+    }
+    if network.accept_invalid_certs {
+        log::warn!("TLS certificate verification is turned off in Settings");
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    builder
+}
+
+/// `client_builder` for a client that sends the sign-in token. Redirects
+/// are only followed within the same host, where the Authorization header
+/// is kept; a hop to another host fails with a `SearchError::Redirect`
+/// rather than carrying on signed out (or leaking the token).
+pub fn api_client_builder(network: &NetworkSettings) -> ClientBuilder {
+    let policy = if network.follow_redirects {
+        Policy::custom(|attempt| {
+            if attempt.previous().len() > MAX_REDIRECTS {
+                return attempt.error("too many redirects");
+            }
+            let Some(origin) = attempt.previous().first().cloned() else {
+                return attempt.follow();
+            };
+            if same_host(&origin, attempt.url()) {
+                attempt.follow()
+            } else {
+                let refused = CrossHostRedirect {
+                    from: host_of(&origin),
+                    to: attempt.url().to_string(),
+                };
+                attempt.error(refused)
+            }
+        })
+    } else {
+        Policy::none()
+    };
+    client_builder(network).redirect(policy)
+}
+
+/// Same host and port, allowing an upgrade from http to https but never a
+/// downgrade
+fn same_host(from: &Url, to: &Url) -> bool {
+    from.host_str()
+        .zip(to.host_str())
+        .is_some_and(|(a, b)| a.eq_ignore_ascii_case(b))
+        && from.port() == to.port()
+        && !(from.scheme() == "https" && to.scheme() == "http")
+}
+
+/// An API call redirected to another host, which was not followed
+#[derive(Debug, thiserror::Error)]
+#[error("{from} redirected to another host ({to}); not followed so your sign-in is not sent there")]
+struct CrossHostRedirect {
+    from: String,
+    to: String,
+}
+
+/// Read a PEM or DER certificate file
+pub fn load_certificate(path: &str) -> Result<Certificate> {
+    let bytes = std::fs::read(path)?;
+    let certificate = if bytes.starts_with(b"-----BEGIN") {
+        Certificate::from_pem(&bytes)?
+    } else {
+        Certificate::from_der(&bytes)?
+    };
+    Ok(certificate)
+}
+
+/// Default headers for every request sent to an instance API
+pub fn json_headers() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+    headers
+}
+
+/// Headers the services set themselves; a custom value would break requests
+const RESERVED_HEADERS: [&str; 4] = ["authorization", "host", "content-length", "content-type"];
+
+/// Check a user-supplied header before it is saved
+pub fn validate_header(name: &str, value: &str) -> Result<(), String> {
+    let name = name.trim();
+    let parsed = HeaderName::from_bytes(name.as_bytes())
+        .map_err(|_| format!("'{}' is not a valid header name", name))?;
+    if RESERVED_HEADERS.contains(&parsed.as_str()) {
+        return Err(format!(
+            "{} is set by the app and cannot be overridden",
+            name
+        ));
+    }
+    HeaderValue::from_str(value.trim()).map_err(|_| {
+        format!(
+            "The value for {} contains characters headers cannot carry",
+            name
+        )
+    })?;
+    Ok(())
+}
+
+/// The user's custom headers for one platform. Entries that fail
+/// validation (e.g. edited by hand in the settings file) are skipped.
+pub fn custom_headers(pairs: &[(String, String)]) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    for (name, value) in pairs {
+        if let Err(err) = validate_header(name, value) {
+            log::warn!("Ignoring custom header: {}", err);
+            continue;
+        }
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.trim().as_bytes()),
+            HeaderValue::from_str(value.trim()),
+        ) {
+            headers.append(name, value);
+        }
+    }
+    headers
+}
+
+/// `json_headers` plus the user's custom headers for the instance
+pub fn instance_headers(custom: &[(String, String)]) -> HeaderMap {
+    let mut headers = json_headers();
+    headers.extend(custom_headers(custom));
+    headers
+}
+
+/// Page size every platform accepts, used after a larger one is rejected
+pub const SAFE_PAGE_SIZE: u32 = 20;
+
+/// The `limit` a service requests per page: the user's setting clamped to
+/// the platform's maximum, dropping to `SAFE_PAGE_SIZE` for the rest of the
+/// search if the server rejects it
+pub struct PageSize(AtomicU32);
+
+impl PageSize {
+    pub fn new(requested: u32, max: u32) -> Self {
+        Self(AtomicU32::new(requested.clamp(1, max)))
+    }
+
+    pub fn get(&self) -> u32 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Switch to the safe size after `status`. True when the request is
+    /// worth retrying, i.e. it looked like a rejected limit and the size
+    /// was not already the safe one.
+    pub fn fall_back(&self, status: StatusCode) -> bool {
+        if !matches!(
+            status,
+            StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY
+        ) {
+            return false;
+        }
+        let previous = self.0.swap(SAFE_PAGE_SIZE, Ordering::Relaxed);
+        if previous == SAFE_PAGE_SIZE {
+            return false;
+        }
+        log::warn!(
+            "Server rejected a page size of {} ({}); retrying with {}",
+            previous,
+            status,
+            SAFE_PAGE_SIZE
+        );
+        true
+    }
+
+    /// `url` with its `limit` query parameter set to the current size
+    pub fn apply(&self, url: &str) -> String {
+        let Ok(mut parsed) = url::Url::parse(url) else {
+            return url.to_string();
+        };
+        let pairs: Vec<(String, String)> = parsed
+            .query_pairs()
+            .filter(|(key, _)| key != "limit")
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect();
+        parsed
+            .query_pairs_mut()
+            .clear()
+            .extend_pairs(pairs)
+            .append_pair("limit", &self.get().to_string());
+        parsed.to_string()
+    }
+}
+
+fn content_type(headers: &HeaderMap) -> String {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("")
+        .to_ascii_lowercase()
+}
+
+fn host_of(url: &reqwest::Url) -> String {
+    url.host_str().unwrap_or("The instance").to_string()
+}
+
+/// Why an API request failed, so callers can react to the kind of failure
+/// (sign in again, wait, shrink the page) instead of matching on text
+#[derive(Debug, thiserror::Error)]
+pub enum SearchError {
+    #[error("{host} is behind a bot-protection challenge (e.g. Cloudflare); API access is blocked. Ask the instance's admins to allow API clients, or use another instance.")]
+    Challenge { host: String },
+    #[error("{host} is rate limiting requests (429){}", retry_hint(.retry_after))]
+    RateLimited {
+        host: String,
+        retry_after: Option<u64>,
+    },
+    #[error("Not signed in, or the session expired (401): {detail}")]
+    Unauthorized { detail: String },
+    #[error("Access denied (403): {detail}")]
+    Forbidden { detail: String },
+    #[error("Not found (404): {detail}")]
+    NotFound { detail: String },
+    #[error("{status}: {detail}")]
+    Status { status: StatusCode, detail: String },
+    /// The body was not the JSON expected, e.g. an HTML page
+    #[error("{0}")]
+    Parse(String),
+    /// A redirect the client would not or may not follow
+    #[error("Redirect not followed: {0}")]
+    Redirect(String),
+    #[error("Network error: {0}")]
+    Network(#[from] reqwest::Error),
+}
+
+impl SearchError {
+    /// The HTTP status behind the error, when the server answered
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            SearchError::RateLimited { .. } => Some(StatusCode::TOO_MANY_REQUESTS),
+            SearchError::Unauthorized { .. } => Some(StatusCode::UNAUTHORIZED),
+            SearchError::Forbidden { .. } => Some(StatusCode::FORBIDDEN),
+            SearchError::NotFound { .. } => Some(StatusCode::NOT_FOUND),
+            SearchError::Status { status, .. } => Some(*status),
+            SearchError::Challenge { .. }
+            | SearchError::Parse(_)
+            | SearchError::Redirect(_)
+            | SearchError::Network(_) => None,
+        }
+    }
+}
+
+fn retry_hint(retry_after: &Option<u64>) -> String {
+    match retry_after {
+        Some(seconds) => format!("; try again in {}s", seconds),
+        None => String::new(),
+    }
+}
+
+/// Longest Retry-After a request waits out before giving up with an error
+const MAX_RETRY_AFTER_SECS: u64 = 60;
+
+fn is_challenge(status: StatusCode, headers: &HeaderMap) -> bool {
+    let flagged = headers
+        .get("cf-mitigated")
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.eq_ignore_ascii_case("challenge"));
+    flagged || (CHALLENGE_STATUSES.contains(&status) && content_type(headers).contains("html"))
+}
+
+/// Seconds to wait before retrying, from `Retry-After` (seconds or an
+/// HTTP date) or, on Bluesky, the `ratelimit-reset` Unix time
+fn retry_after(headers: &HeaderMap) -> Option<u64> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+    };
+    let now = chrono::Utc::now().timestamp();
+    header("retry-after")
+        .and_then(|value| {
+            value.parse::<i64>().ok().or_else(|| {
+                chrono::DateTime::parse_from_rfc2822(value)
+                    .ok()
+                    .map(|date| date.timestamp() - now)
+            })
+        })
+        .or_else(|| {
+            header("ratelimit-reset")
+                .and_then(|value| value.parse::<i64>().ok())
+                .map(|reset| reset - now)
+        })
+        .map(|seconds| seconds.max(0) as u64)
+}
+
+/// The error for a failed status other than 429
+fn status_error(status: StatusCode, detail: String) -> SearchError {
+    match status {
+        StatusCode::UNAUTHORIZED => SearchError::Unauthorized { detail },
+        StatusCode::FORBIDDEN => SearchError::Forbidden { detail },
+        StatusCode::NOT_FOUND => SearchError::NotFound { detail },
+        _ => SearchError::Status { status, detail },
+    }
+}
+
+/// The useful part of an error body: the `error`/`message` fields APIs put
+/// in JSON errors, or a preview of anything else
+fn error_detail(body: &str) -> String {
+    if let Ok(json) = serde_json::from_str::<serde_json::Value>(body) {
+        let field = |name: &str| json.get(name).and_then(|value| value.as_str());
+        match (field("error"), field("message")) {
+            (Some(error), Some(message)) => return format!("{}: {}", error, message),
+            (Some(text), None) | (None, Some(text)) => return text.to_string(),
+            (None, None) => {}
+        }
+    }
+    body_preview(body)
+}
+
+/// Fail with a clear message when a bot-protection page answered in place
+/// of the API. Call right after sending, before looking at the status.
+pub fn check_challenge(response: Response) -> Result<Response, SearchError> {
+    if is_challenge(response.status(), response.headers()) {
+        Err(SearchError::Challenge {
+            host: host_of(response.url()),
+        })
+    } else {
+        Ok(response)
+    }
+}
+
+/// Requests sent while a measured future runs
+#[derive(Debug, Default)]
+struct RequestCounter {
+    requests: AtomicU32,
+    wait_ms: AtomicU64,
+}
+
+tokio::task_local! {
+    static REQUEST_COUNTER: Arc<RequestCounter>;
+}
+
+/// Request count and timings of one measured search
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequestTiming {
+    pub requests: u32,
+    pub wait: Duration,
+    pub elapsed: Duration,
+}
+
+impl RequestTiming {
+    pub fn record(&self, stats: &mut SearchStats) {
+        stats.requests = self.requests;
+        stats.request_ms = self.wait.as_millis() as u64;
+        stats.elapsed_ms = self.elapsed.as_millis() as u64;
+    }
+}
+
+/// Run `future`, counting and timing every request it sends through
+/// `send_checked`. Concurrent measured futures keep separate counts.
+pub async fn measure_requests<F: Future>(future: F) -> (F::Output, RequestTiming) {
+    let counter = Arc::new(RequestCounter::default());
+    let started = Instant::now();
+    let output = REQUEST_COUNTER.scope(counter.clone(), future).await;
+    let timing = RequestTiming {
+        requests: counter.requests.load(Ordering::Relaxed),
+        wait: Duration::from_millis(counter.wait_ms.load(Ordering::Relaxed)),
+        elapsed: started.elapsed(),
+    };
+    (output, timing)
+}
+
+fn count_request(wait: Duration) {
+    let _ = REQUEST_COUNTER.try_with(|counter| {
+        counter.requests.fetch_add(1, Ordering::Relaxed);
+        counter
+            .wait_ms
+            .fetch_add(wait.as_millis() as u64, Ordering::Relaxed);
+    });
+}
+
+/// Send a request and turn any failure into a `SearchError`. A 429 with a
+/// short Retry-After is waited out and retried once.
+pub async fn send_checked(request: RequestBuilder) -> Result<Response, SearchError> {
+    let mut request = request;
+    let mut retried = false;
+    loop {
+        // Requests with streaming bodies cannot be cloned, so are not retried
+        let retry = if retried { None } else { request.try_clone() };
+        let sent_at = Instant::now();
+        let sent = request.send().await;
+        count_request(sent_at.elapsed());
+        let response = check_challenge(sent.map_err(redirect_error)?)?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        // Only reached with redirects turned off in Settings
+        if status.is_redirection() {
+            let location = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or("an unknown address");
+            return Err(SearchError::Redirect(format!(
+                "{} sent {} to {}, and following redirects is off in Settings",
+                host_of(response.url()),
+                status,
+                location
+            )));
+        }
+
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            let wait = retry_after(response.headers());
+            match (retry, wait) {
+                (Some(next), Some(seconds)) if seconds <= MAX_RETRY_AFTER_SECS => {
+                    log::info!(
+                        "Rate limited by {}; retrying in {}s",
+                        host_of(response.url()),
+                        seconds
+                    );
+                    tokio::time::sleep(std::time::Duration::from_secs(seconds + 1)).await;
+                    request = next;
+                    retried = true;
+                    continue;
+                }
+                _ => {
+                    return Err(SearchError::RateLimited {
+                        host: host_of(response.url()),
+                        retry_after: wait,
+                    })
+                }
+            }
+        }
+
+        let detail = error_detail(&response.text().await.unwrap_or_default());
+        return Err(status_error(status, detail));
+    }
+}
+
+/// A refused cross-host redirect as its own error; anything else as a
+/// network error
+fn redirect_error(err: reqwest::Error) -> SearchError {
+    let refused = std::error::Error::source(&err)
+        .and_then(|source| source.downcast_ref::<CrossHostRedirect>())
+        .map(CrossHostRedirect::to_string);
+    match refused {
+        Some(message) => SearchError::Redirect(message),
+        None => SearchError::Network(err),
+    }
+}
+
+/// `send_checked`, then `parse_json`
+pub async fn send_and_parse<T: DeserializeOwned>(
+    request: RequestBuilder,
+    what: &str,
+) -> Result<T, SearchError> {
+    parse_json(send_checked(request).await?, what).await
+}
+
+/// Fail early with a readable message when a response is not JSON
+pub fn ensure_json(response: &Response) -> Result<(), SearchError> {
+    let content_type = content_type(response.headers());
+
+    // Missing header: let the parser decide rather than rejecting outright
+    if content_type.is_empty() || content_type.contains("json") {
+        return Ok(());
+    }
+
+    if content_type.contains("html") {
+        Err(SearchError::Parse(format!(
+            "{} returned HTML, not JSON — wrong instance URL?",
+            response.url()
+        )))
+    } else {
+        Err(SearchError::Parse(format!(
+            "{} returned '{}' instead of JSON",
+            response.url(),
+            content_type
+        )))
+    }
+}
+
+/// Deserialize a JSON response, keeping a preview of the raw body on failure.
+///
+/// `what` names the payload for the error, e.g. "Mastodon timeline".
+pub async fn parse_json<T: DeserializeOwned>(
+    response: Response,
+    what: &str,
+) -> Result<T, SearchError> {
+    ensure_json(&response)?;
+    let url = response.url().clone();
+    // The client removes the header once it has decoded the body
+    let encoding = response
+        .headers()
+        .get(CONTENT_ENCODING)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|err| SearchError::Parse(format!("Failed to read {} response: {}", what, err)))?;
+    if let Some(coding) = undecoded_coding(&bytes, encoding.as_deref()) {
+        return Err(SearchError::Parse(format!(
+            "{} came back compressed ({}) and could not be decoded",
+            what, coding
+        )));
+    }
+    parse_body(&String::from_utf8_lossy(&bytes), &url, what)
+}
+
+/// The compression a body is still in: gzip by its magic bytes, or a
+/// compressing coding left in `Content-Encoding`. `identity` and values
+/// that name no compression are plain bytes.
+fn undecoded_coding(bytes: &[u8], encoding: Option<&str>) -> Option<String> {
+    if bytes.starts_with(&GZIP_MAGIC) {
+        return Some("gzip".to_string());
+    }
+    encoding?
+        .split(',')
+        .map(str::trim)
+        .find(|coding| {
+            COMPRESSED_CODINGS
+                .iter()
+                .any(|compressed| coding.eq_ignore_ascii_case(compressed))
+        })
+        .map(str::to_string)
+}
+
+/// Deserialize a response body already read as text
+fn parse_body<T: DeserializeOwned>(body: &str, url: &Url, what: &str) -> Result<T, SearchError> {
+    serde_json::from_str(body).map_err(|err| {
+        // No Content-Type header, but the body is a web page
+        let start: String = body.trim_start().chars().take(15).collect();
+        let start = start.to_ascii_lowercase();
+        if start.starts_with("<!doctype") || start.starts_with("<html") {
+            let lower = body.to_ascii_lowercase();
+            if CHALLENGE_MARKERS
+                .iter()
+                .any(|marker| lower.contains(marker))
+            {
+                return SearchError::Challenge { host: host_of(url) };
+            }
+            return SearchError::Parse(format!(
+                "{} returned HTML, not JSON — wrong instance URL?",
+                url
+            ));
+        }
+        SearchError::Parse(format!(
+            "Failed to parse {}: {}. Body: {}",
+            what,
+            err,
+            body_preview(body)
+        ))
+    })
+}
+
+fn body_preview(body: &str) -> String {
+    let trimmed = body.trim();
+    if trimmed.chars().count() <= BODY_PREVIEW_CHARS {
+        return trimmed.to_string();
+    }
+    let preview: String = trimmed.chars().take(BODY_PREVIEW_CHARS).collect();
+    format!("{}… ({} bytes total)", preview, trimmed.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `{"id":"1"}` gzipped, as a server that ignored Accept-Encoding sends it
+    const GZIPPED_JSON: [u8; 30] = [
+        0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xab, 0x56, 0xca, 0x4c, 0x51,
+        0xb2, 0x52, 0x32, 0x54, 0xaa, 0x05, 0x00, 0xf3, 0xc8, 0x18, 0xf6, 0x0a, 0x00, 0x00, 0x00,
+    ];
+
+    fn api_url() -> Url {
+        Url::parse("https://example.social/api/v1/timelines/home").unwrap()
+    }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        pairs
+            .iter()
+            .map(|(name, value)| {
+                (
+                    HeaderName::from_static(name),
+                    HeaderValue::from_str(value).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn failed_statuses_map_to_their_errors() {
+        let detail = || "detail".to_string();
+        assert!(matches!(
+            status_error(StatusCode::UNAUTHORIZED, detail()),
+            SearchError::Unauthorized { .. }
+        ));
+        assert!(matches!(
+            status_error(StatusCode::FORBIDDEN, detail()),
+            SearchError::Forbidden { .. }
+        ));
+        assert!(matches!(
+            status_error(StatusCode::NOT_FOUND, detail()),
+            SearchError::NotFound { .. }
+        ));
+        let other = status_error(StatusCode::BAD_GATEWAY, detail());
+        assert!(matches!(other, SearchError::Status { .. }));
+        assert_eq!(other.status(), Some(StatusCode::BAD_GATEWAY));
+        let limited = SearchError::RateLimited {
+            host: "example.social".to_string(),
+            retry_after: Some(30),
+        };
+        assert_eq!(limited.status(), Some(StatusCode::TOO_MANY_REQUESTS));
+        assert!(limited.to_string().ends_with("try again in 30s"));
+    }
+
+    #[test]
+    fn error_detail_prefers_json_error_fields() {
+        assert_eq!(
+            error_detail(r#"{"error":"The access token is invalid"}"#),
+            "The access token is invalid"
+        );
+        assert_eq!(
+            error_detail(r#"{"error":"RateLimitExceeded","message":"Slow down"}"#),
+            "RateLimitExceeded: Slow down"
+        );
+        assert_eq!(error_detail("  plain text  "), "plain text");
+    }
+
+    #[test]
+    fn retry_after_reads_seconds_dates_and_bluesky_reset() {
+        assert_eq!(retry_after(&headers(&[("retry-after", "30")])), Some(30));
+        assert_eq!(retry_after(&headers(&[("retry-after", "-5")])), Some(0));
+        assert_eq!(
+            retry_after(&headers(&[(
+                "retry-after",
+                "Wed, 21 Oct 2015 07:28:00 GMT"
+            )])),
+            Some(0)
+        );
+        let in_a_minute = chrono::Utc::now() + chrono::Duration::seconds(60);
+        let date = in_a_minute.to_rfc2822();
+        let wait = retry_after(&headers(&[("retry-after", date.as_str())])).unwrap();
+        assert!((58..=60).contains(&wait), "{wait}");
+        let reset = in_a_minute.timestamp().to_string();
+        let wait = retry_after(&headers(&[("ratelimit-reset", reset.as_str())])).unwrap();
+        assert!((58..=60).contains(&wait), "{wait}");
+        assert_eq!(retry_after(&headers(&[("retry-after", "soon")])), None);
+        assert_eq!(retry_after(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn challenge_pages_are_detected_from_headers() {
+        assert!(is_challenge(
+            StatusCode::OK,
+            &headers(&[("cf-mitigated", "challenge")])
+        ));
+        assert!(is_challenge(
+            StatusCode::FORBIDDEN,
+            &headers(&[("content-type", "text/html; charset=UTF-8")])
+        ));
+        assert!(!is_challenge(
+            StatusCode::FORBIDDEN,
+            &headers(&[("content-type", "application/json")])
+        ));
+        assert!(!is_challenge(
+            StatusCode::NOT_FOUND,
+            &headers(&[("content-type", "text/html")])
+        ));
+    }
+
+    #[test]
+    fn html_interstitials_are_not_parsed_as_json() {
+        let challenge = "<!DOCTYPE html><html><head><title>Just a moment...</title></head>\
+            <body><div id=\"challenge-platform\"></div></body></html>";
+        assert!(matches!(
+            parse_body::<serde_json::Value>(challenge, &api_url(), "timeline"),
+            Err(SearchError::Challenge { host }) if host == "example.social"
+        ));
+
+        let landing = "<html><body>Welcome to nginx!</body></html>";
+        match parse_body::<serde_json::Value>(landing, &api_url(), "timeline") {
+            Err(SearchError::Parse(message)) => assert!(message.contains("returned HTML")),
+            other => panic!("unexpected {other:?}"),
+        }
+
+        match parse_body::<Vec<u32>>(r#"{"error":"nope"}"#, &api_url(), "timeline") {
+            Err(SearchError::Parse(message)) => {
+                assert!(message.starts_with("Failed to parse timeline"));
+                assert!(message.contains(r#"{"error":"nope"}"#));
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn gzip_body_is_reported_as_compressed() {
+        assert_eq!(
+            undecoded_coding(&GZIPPED_JSON, None).as_deref(),
+            Some("gzip")
+        );
+        assert_eq!(
+            undecoded_coding(&GZIPPED_JSON, Some("identity")).as_deref(),
+            Some("gzip")
+        );
+    }
+
+    #[tokio::test]
+    async fn gzipped_responses_are_decoded_by_the_client() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!(
+            "http://{}/api/v1/statuses/1",
+            listener.local_addr().unwrap()
+        );
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let _ = socket.read(&mut request).await;
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Encoding: gzip\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                GZIPPED_JSON.len()
+            );
+            let _ = socket.write_all(head.as_bytes()).await;
+            let _ = socket.write_all(&GZIPPED_JSON).await;
+        });
+
+        let client = client_builder(&NetworkSettings::default()).build().unwrap();
+        let body = send_and_parse::<serde_json::Value>(client.get(&url), "status")
+            .await
+            .unwrap();
+        assert_eq!(body, serde_json::json!({"id": "1"}));
+    }
+
+    #[test]
+    fn undecoded_content_encoding_is_reported() {
+        let body = br#"{"id":"1"}"#;
+        assert_eq!(undecoded_coding(body, Some("br")).as_deref(), Some("br"));
+        assert_eq!(
+            undecoded_coding(body, Some("identity, zstd")).as_deref(),
+            Some("zstd")
+        );
+    }
+
+    #[test]
+    fn plain_body_with_harmless_content_encoding_parses() {
+        let body = br#"{"id":"1"}"#;
+        for encoding in [
+            None,
+            Some("identity"),
+            Some("IDENTITY"),
+            Some("utf-8"),
+            Some(""),
+        ] {
+            assert_eq!(undecoded_coding(body, encoding), None, "{encoding:?}");
+        }
+        let parsed: serde_json::Value =
+            parse_body(r#"{"id":"1"}"#, &api_url(), "timeline").unwrap();
+        assert_eq!(parsed["id"], "1");
+    }
+}
@@ -0,0 +1,82 @@
+// ============================================================================
+// Link Export Service - Media URL lists for external downloaders
+// ============================================================================
+// Instead of fetching any bytes, writes every media URL of a result set into
+// the download root: links.txt with one URL per line (for `wget -i`,
+// `yt-dlp -a` and the like) and links.csv with the post each URL came from.
+// ============================================================================
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::models::{AppSettings, SearchContext, SearchResult};
+use crate::services::DownloadService;
+
+const CSV_HEADER: &str = "url,media_type,platform,post_id,author,created_at,post_url";
+
+pub struct LinkExportService {
+    settings: AppSettings,
+}
+
+impl LinkExportService {
+    pub fn new(settings: AppSettings) -> Self {
+        Self { settings }
+    }
+
+    /// Write `links.txt` and `links.csv` for `results` into the download
+    /// root. Returns the root and how many links were written.
+    pub fn export(
+        &self,
+        context: Option<&SearchContext>,
+        results: &[SearchResult],
+    ) -> Result<(PathBuf, usize)> {
+        let mut sorted: Vec<&SearchResult> = results
+            .iter()
+            .filter(|result| !result.media.is_empty())
+            .collect();
+        if sorted.is_empty() {
+            return Err(anyhow::anyhow!("No media links to export"));
+        }
+        sorted.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+        let mut txt = String::new();
+        let mut csv = format!("{}\n", CSV_HEADER);
+        let mut count = 0;
+        for result in sorted {
+            for item in &result.media {
+                txt.push_str(&item.url);
+                txt.push('\n');
+                let _ = writeln!(
+                    csv,
+                    "{},{},{},{},{},{},{}",
+                    csv_field(&item.url),
+                    csv_field(&item.media_type),
+                    result.platform.name(),
+                    csv_field(&result.id),
+                    csv_field(&result.author),
+                    result.created_at.to_rfc3339(),
+                    csv_field(&result.url),
+                );
+                count += 1;
+            }
+        }
+
+        let root = DownloadService::new(self.settings.clone()).create_download_root(context)?;
+        fs::write(root.join("links.txt"), txt)?;
+        fs::write(root.join("links.csv"), csv)?;
+
+        Ok((root, count))
+    }
+}
+
+/// Quote a CSV field when it holds a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
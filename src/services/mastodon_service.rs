@@ -7,22 +7,75 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use chrono::{DateTime, Duration, Utc};
+use futures_util::StreamExt;
+use reqwest::header::{HeaderMap, ACCEPT, LINK};
 use reqwest::Client;
 
-use crate::models::{AppSettings, PixelfedPost, Platform, SearchResult};
-use crate::services::SocialPlatform;
+use crate::models::{
+    AppSettings, MediaItem, NetworkSettings, PixelfedPost, Platform, SearchOutcome, SearchResult,
+    StatusContext, TagCombination, TagQuery,
+};
+use crate::services::http_util::{
+    api_client_builder, custom_headers, instance_headers, parse_json, send_and_parse, send_checked,
+    PageSize, SearchError,
+};
+use crate::services::paginator::{paginate, with_max_id, Page, PageLimits};
+use crate::services::{
+    restricted_account_notice, user_lookup_status, with_account_notice, HostLimiter,
+    SocialPlatform, StatusSender,
+};
+use crate::utils::{cap_content, parse_post_date};
+
+/// Mastodon sends a heartbeat comment every 15 seconds or so; a stream
+/// that stays silent much longer than that is dead
+const STREAM_IDLE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(90);
+
+/// Actionable message for a failed request. A missing token is caught by
+/// `require_access_token`; 401 and 403 cover a token the instance turned
+/// down. `what` names the request, e.g. "Mastodon timeline".
+fn api_error(err: SearchError, what: &str) -> anyhow::Error {
+    match err {
+        SearchError::Unauthorized { .. } => anyhow::anyhow!(
+            "Mastodon session expired or was revoked (401). Sign in again in Settings."
+        ),
+        SearchError::Forbidden { .. } => anyhow::anyhow!(
+            "Mastodon token lacks the required scope (read) (403). Sign in again in Settings to re-authorize with the correct scopes."
+        ),
+        SearchError::Challenge { .. } | SearchError::Parse(_) => err.into(),
+        _ => anyhow::anyhow!("Failed to fetch {}: {}", what, err),
+    }
+}
+
+/// Mastodon silently caps larger limits on its timeline endpoints
+const MAX_PAGE_SIZE: u32 = 40;
 
 pub struct MastodonService {
     client: Client,
     instance_url: String,
     access_token: Option<String>,
     enabled: bool,
+    requests_per_instance: u32,
+    include_thread_context: bool,
+    only_media: bool,
+    tag_combination: TagCombination,
+    count_only: bool,
+    page_limits: PageLimits,
+    page_size: PageSize,
+    custom_headers: Vec<(String, String)>,
+    /// For the streaming client, which is built per stream
+    network: NetworkSettings,
+    status: Option<StatusSender>,
+    /// Searching another instance anonymously rather than the signed-in one
+    public: bool,
 }
 
 impl MastodonService {
     pub fn new(settings: &AppSettings) -> Self {
         let platform_auth = &settings.api.mastodon;
-        let trimmed = platform_auth.instance_url.trim();
+        let trimmed = match platform_auth.instance_url.trim() {
+            "" => settings.home_instances.mastodon.trim(),
+            url => url,
+        };
 
         let normalized_url = if trimmed.starts_with("http://") || trimmed.starts_with("https://") {
             trimmed.trim_end_matches('/').to_string()
@@ -30,8 +83,8 @@ impl MastodonService {
             format!("https://{}", trimmed.trim_end_matches('/'))
         };
 
-        let client = Client::builder()
-            .user_agent("Fedi-Sleuth/0.1.0")
+        let client = api_client_builder(&settings.network)
+            .default_headers(instance_headers(&platform_auth.custom_headers))
             .timeout(std::time::Duration::from_secs(60))
             .build()
             .expect("Failed to create HTTP client");
@@ -41,7 +94,54 @@ impl MastodonService {
             instance_url: normalized_url,
             access_token: platform_auth.access_token.clone(),
             enabled: platform_auth.enabled,
+            requests_per_instance: settings.search.max_requests_per_instance,
+            include_thread_context: settings.search.include_thread_context,
+            only_media: settings.search.mastodon_only_media,
+            tag_combination: settings.search.tag_combination,
+            count_only: settings.search.count_only,
+            page_limits: PageLimits::from_settings(&settings.search),
+            page_size: PageSize::new(settings.search.page_size, MAX_PAGE_SIZE),
+            custom_headers: platform_auth.custom_headers.clone(),
+            network: settings.network.clone(),
+            status: None,
+            public: false,
+        }
+    }
+
+    /// A service for a one-off anonymous search of `instance_url` instead
+    /// of the configured instance. Only public posts are visible.
+    pub fn for_instance(settings: &AppSettings, instance_url: &str) -> Self {
+        let mut settings = settings.clone();
+        let auth = &mut settings.api.mastodon;
+        auth.instance_url = instance_url.trim().to_string();
+        auth.access_token = None;
+        auth.enabled = true;
+        auth.custom_headers.clear();
+        Self {
+            public: true,
+            ..Self::new(&settings)
+        }
+    }
+
+    /// Report search progress to the UI as it happens
+    pub fn with_status(mut self, status: StatusSender) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    fn report(&self, message: String) {
+        if let Some(status) = &self.status {
+            let _ = status.send(message);
+        }
+    }
+
+    /// The token for a user or hashtag search; None when searching another
+    /// instance anonymously
+    fn search_token(&self) -> Result<Option<&str>> {
+        if self.public {
+            return Ok(None);
         }
+        self.require_access_token().map(Some)
     }
 
     fn require_access_token(&self) -> Result<&str> {
@@ -83,105 +183,367 @@ impl MastodonService {
             .to_string()
     }
 
-    fn extract_media(post: &PixelfedPost) -> (Vec<String>, Vec<String>, u32) {
-        let mut urls = Vec::new();
-        let mut types = Vec::new();
+    fn extract_media(post: &PixelfedPost) -> Vec<MediaItem> {
+        post.media_attachments
+            .iter()
+            .filter_map(|attachment| {
+                let url = attachment.url.as_deref()?.trim();
+                (!url.is_empty()).then(|| attachment.to_media_item(url))
+            })
+            .collect()
+    }
 
-        for attachment in &post.media_attachments {
-            if let Some(url) = attachment.url.as_deref() {
-                let trimmed = url.trim();
-                if trimmed.is_empty() {
-                    continue;
+    fn build_result(&self, post: &PixelfedPost, created_at: DateTime<Utc>) -> SearchResult {
+        // A boost carries no content of its own; report the boosted post
+        let (post, reblogged_by) = match post.reblog.as_deref() {
+            Some(original) => (original, Some(Self::account_name(post))),
+            None => (post, None),
+        };
+        let media = Self::extract_media(post);
+
+        if self.count_only {
+            return SearchResult::count_stub(
+                Platform::Mastodon,
+                post.id.clone(),
+                created_at,
+                &media,
+            );
+        }
+
+        SearchResult {
+            platform: Platform::Mastodon,
+            id: post.id.clone(),
+            author: Self::account_name(post),
+            content: cap_content(strip_html_tags(post.content.as_deref().unwrap_or(""))),
+            created_at,
+            media,
+            likes: post.favourites_count.unwrap_or(0),
+            shares: post.reblogs_count.unwrap_or(0),
+            url: post
+                .url
+                .clone()
+                .unwrap_or_else(|| self.fallback_post_url(post)),
+            content_warning: post.content_warning(),
+            reblogged_by,
+            account_profile: None,
+            poll: post.poll.clone(),
+        }
+    }
+
+    /// Extract the `rel="next"` target from a Link header
+    fn next_page_link(headers: &HeaderMap) -> Option<String> {
+        let link = headers.get(LINK)?.to_str().ok()?;
+
+        link.split(',').find_map(|part| {
+            let mut sections = part.split(';');
+            let target = sections.next()?.trim();
+            let is_next = sections.any(|param| param.trim() == "rel=\"next\"");
+
+            is_next.then(|| {
+                target
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            })
+        })
+    }
+
+    /// Fetch an account collection such as bookmarks or favourites.
+    ///
+    /// These endpoints are ordered by when the post was saved rather than when
+    /// it was written and paginate with opaque ids from the Link header, so
+    /// the cutoff filters posts instead of ending the scan.
+    async fn fetch_account_collection(
+        &self,
+        path: &str,
+        cutoff_date: DateTime<Utc>,
+    ) -> Result<SearchOutcome> {
+        let access_token = self.require_access_token()?;
+        let first_url = format!(
+            "{}{}?limit={}",
+            self.instance_url,
+            path,
+            self.page_size.get()
+        );
+        let what = format!("Mastodon {}", path);
+
+        paginate(&what, self.page_limits, |next_url: Option<String>| {
+            let url = next_url.unwrap_or_else(|| first_url.clone());
+            let what = &what;
+            async move {
+                log::debug!("Fetching {}", url);
+                let (posts, next) = self.fetch_page(&url, Some(access_token), what).await?;
+                if posts.is_empty() {
+                    return Ok(Page::default());
                 }
-                urls.push(trimmed.to_string());
-                types.push(attachment.r#type.as_deref().unwrap_or("").to_string());
+
+                let mut page = Page {
+                    next,
+                    seen: posts.len(),
+                    ..Page::default()
+                };
+                for post in &posts {
+                    let created_at = match post.created_at.as_deref().and_then(parse_post_date) {
+                        Some(dt) => dt,
+                        None => continue,
+                    };
+
+                    if created_at >= cutoff_date {
+                        page.results.push(self.build_result(post, created_at));
+                    }
+                }
+                Ok(page)
             }
+        })
+        .await
+    }
+
+    /// Fetch one page of statuses and the Link header's next-page URL
+    async fn fetch_page(
+        &self,
+        url: &str,
+        access_token: Option<&str>,
+        what: &str,
+    ) -> Result<(Vec<PixelfedPost>, Option<String>)> {
+        let _permit = HostLimiter::global()
+            .acquire(url, self.requests_per_instance)
+            .await;
+
+        loop {
+            let mut request = self.client.get(self.page_size.apply(url));
+            if let Some(token) = access_token {
+                request = request.header("Authorization", format!("Bearer {}", token));
+            }
+
+            let response = match send_checked(request).await {
+                Ok(response) => response,
+                Err(err)
+                    if err
+                        .status()
+                        .is_some_and(|status| self.page_size.fall_back(status)) =>
+                {
+                    continue
+                }
+                Err(err) => return Err(api_error(err, what)),
+            };
+
+            let next_link = Self::next_page_link(response.headers());
+            let values = parse_json(response, what).await?;
+            return Ok((PixelfedPost::parse_page(values), next_link));
         }
+    }
 
-        let count = urls.len() as u32;
-        (urls, types, count)
+    /// Authenticated GET against this instance, parsed as JSON
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, url: &str, what: &str) -> Result<T> {
+        let access_token = self.require_access_token()?;
+        let _permit = HostLimiter::global()
+            .acquire(url, self.requests_per_instance)
+            .await;
+
+        let request = self
+            .client
+            .get(url)
+            .header("Authorization", format!("Bearer {}", access_token));
+        send_and_parse(request, what)
+            .await
+            .map_err(|err| api_error(err, what))
+    }
+
+    /// Resolve a post URL (from any instance) to a status on this instance
+    async fn resolve_status(&self, post_url: &str) -> Result<PixelfedPost> {
+        let search_url = format!(
+            "{}/api/v2/search?q={}&type=statuses&resolve=true&limit=1",
+            self.instance_url,
+            urlencoding::encode(post_url)
+        );
+
+        let data: serde_json::Value = self.get_json(&search_url, "Mastodon status search").await?;
+        if let Some(status) = data["statuses"].as_array().and_then(|list| list.first()) {
+            return Ok(serde_json::from_value(status.clone())?);
+        }
+
+        // Local posts can be fetched directly when resolving is disabled
+        let instance_host = url::Url::parse(&self.instance_url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string));
+        let parsed = url::Url::parse(post_url)
+            .map_err(|_| anyhow::anyhow!("'{}' is not a valid post URL", post_url))?;
+        let local_id = parsed
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .filter(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()));
+
+        match (parsed.host_str(), local_id) {
+            (Some(host), Some(id)) if Some(host.to_string()) == instance_host => {
+                let status_url = format!("{}/api/v1/statuses/{}", self.instance_url, id);
+                self.get_json(&status_url, "Mastodon status").await
+            }
+            _ => Err(anyhow::anyhow!(
+                "{} could not resolve {}. The post may be private or deleted.",
+                self.instance_url,
+                post_url
+            )),
+        }
+    }
+
+    async fn fetch_post_thread(&self, post_url: &str) -> Result<Vec<SearchResult>> {
+        let status = self.resolve_status(post_url.trim()).await?;
+
+        let mut posts = Vec::new();
+        if self.include_thread_context {
+            let context_url = format!(
+                "{}/api/v1/statuses/{}/context",
+                self.instance_url, status.id
+            );
+            let context: StatusContext = self
+                .get_json(&context_url, "Mastodon thread context")
+                .await?;
+            posts.extend(context.ancestors);
+            posts.push(status);
+            posts.extend(context.descendants);
+        } else {
+            posts.push(status);
+        }
+
+        Ok(posts
+            .iter()
+            .filter_map(|post| {
+                let created_at = post.created_at.as_deref().and_then(parse_post_date)?;
+                Some(self.build_result(post, created_at))
+            })
+            .collect())
     }
 
     async fn search_user_posts(
         &self,
         username: &str,
         cutoff_date: DateTime<Utc>,
-    ) -> Result<Vec<SearchResult>> {
-        let access_token = self.require_access_token()?;
+    ) -> Result<SearchOutcome> {
+        let access_token = self.search_token()?;
 
         let clean_username = username.trim_start_matches('@');
         let search_query = clean_username.to_string();
 
-        let search_url = format!(
-            "{}/api/v2/search?q={}&type=accounts&resolve=true&limit=1",
-            self.instance_url,
-            urlencoding::encode(&search_query)
-        );
+        // Anonymous search cannot resolve remote accounts; lookup still
+        // finds any account the instance already knows
+        let search_url = match access_token {
+            Some(_) => format!(
+                "{}/api/v2/search?q={}&type=accounts&resolve=true&limit=1",
+                self.instance_url,
+                urlencoding::encode(&search_query)
+            ),
+            None => format!(
+                "{}/api/v1/accounts/lookup?acct={}",
+                self.instance_url,
+                urlencoding::encode(&search_query)
+            ),
+        };
 
         log::info!(
             "Searching for Mastodon user '{}' via {}",
             search_query,
             search_url
         );
+        self.report(user_lookup_status(Platform::Mastodon, &search_query));
 
-        let response = self
+        let permit = HostLimiter::global()
+            .acquire(&search_url, self.requests_per_instance)
+            .await;
+
+        let mut request = self
             .client
             .get(&search_url)
-            .header("Authorization", format!("Bearer {}", access_token))
-            .timeout(std::time::Duration::from_secs(45))
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(anyhow::anyhow!(
-                "User search failed: {}. Response: {}. User '{}' may not exist or is unreachable.",
-                status,
-                body,
-                search_query
-            ));
-        }
-
-        let data: serde_json::Value = response.json().await?;
-        let accounts = data["accounts"]
-            .as_array()
-            .ok_or_else(|| anyhow::anyhow!("Invalid search response"))?;
-
-        if accounts.is_empty() {
-            return Err(anyhow::anyhow!(
-                "User '{}' not found on {}. Try searching directly on their home instance.",
-                search_query,
-                self.instance_url
-            ));
+            .timeout(std::time::Duration::from_secs(45));
+        if let Some(token) = access_token {
+            request = request.header("Authorization", format!("Bearer {}", token));
         }
+        let data: serde_json::Value = match send_and_parse(request, "Mastodon account search").await
+        {
+            Ok(data) => data,
+            Err(err @ (SearchError::Status { .. } | SearchError::NotFound { .. })) => {
+                return Err(anyhow::anyhow!(
+                    "User search failed: {}. User '{}' may not exist or is unreachable.",
+                    err,
+                    search_query
+                ))
+            }
+            Err(err) => return Err(api_error(err, "Mastodon account search")),
+        };
+        drop(permit);
+        let account = if access_token.is_some() {
+            let accounts = data["accounts"]
+                .as_array()
+                .ok_or_else(|| anyhow::anyhow!("Invalid search response"))?;
+
+            match accounts.first() {
+                Some(account) => account.clone(),
+                None => {
+                    return Err(anyhow::anyhow!(
+                        "User '{}' not found on {}. Try searching directly on their home instance.",
+                        search_query,
+                        self.instance_url
+                    ))
+                }
+            }
+        } else {
+            data
+        };
 
-        let user_id = accounts[0]["id"]
+        let user_id = account["id"]
             .as_str()
             .ok_or_else(|| anyhow::anyhow!("Invalid user data"))?;
+        let notice = restricted_account_notice(&account);
+        if let Some(notice) = &notice {
+            log::info!("Mastodon user '{}': {}", search_query, notice);
+        }
 
-        let timeline_url = format!(
-            "{}/api/v1/accounts/{}/statuses?limit=40",
-            self.instance_url, user_id
+        self.report("Mastodon: Fetching posts…".to_string());
+        let mut timeline_url = format!(
+            "{}/api/v1/accounts/{}/statuses?limit={}",
+            self.instance_url,
+            user_id,
+            self.page_size.get()
         );
-        self.fetch_timeline(&timeline_url, cutoff_date, Some(access_token))
-            .await
+        // Text-only posts are dropped server-side, so far fewer pages
+        if self.only_media {
+            timeline_url.push_str("&only_media=true");
+        }
+        let outcome = self
+            .fetch_timeline(&timeline_url, cutoff_date, access_token)
+            .await;
+        with_account_notice(outcome, notice)
     }
 
     async fn search_hashtag_posts(
         &self,
         hashtag: &str,
         cutoff_date: DateTime<Utc>,
-    ) -> Result<Vec<SearchResult>> {
-        let access_token = self.require_access_token()?;
-        let clean_hashtag = hashtag.trim_start_matches('#');
+    ) -> Result<SearchOutcome> {
+        let access_token = self.search_token()?;
+        let query = TagQuery::parse(hashtag);
+        let Some(primary) = query.primary() else {
+            return Err(anyhow::anyhow!("Enter at least one hashtag to search"));
+        };
 
-        let timeline_url = format!(
-            "{}/api/v1/timelines/tag/{}?limit=40",
-            self.instance_url, clean_hashtag
+        // The first tag goes in the path; the rest combine server-side
+        let mut timeline_url = format!(
+            "{}/api/v1/timelines/tag/{}?limit={}",
+            self.instance_url,
+            urlencoding::encode(primary),
+            self.page_size.get()
         );
+        for tag in &query.tags[1..] {
+            timeline_url.push_str(&format!(
+                "&{}={}",
+                self.tag_combination.api_param(),
+                urlencoding::encode(tag)
+            ));
+        }
+        for tag in &query.excluded {
+            timeline_url.push_str(&format!("&none[]={}", urlencoding::encode(tag)));
+        }
 
-        self.fetch_timeline(&timeline_url, cutoff_date, Some(access_token))
+        self.fetch_timeline(&timeline_url, cutoff_date, access_token)
             .await
     }
 
@@ -190,120 +552,171 @@ impl MastodonService {
         base_url: &str,
         cutoff_date: DateTime<Utc>,
         access_token: Option<&str>,
-    ) -> Result<Vec<SearchResult>> {
-        let mut results = Vec::new();
-        let mut max_id: Option<String> = None;
-        let mut page = 0u32;
-
-        loop {
-            page += 1;
-            if page > 120 {
-                log::warn!("Mastodon timeline fetch aborted after {} pages", page);
-                break;
-            }
-
-            let mut url = base_url.to_string();
-            if let Some(ref id) = max_id {
-                let join_char = if url.contains('?') { '&' } else { '?' };
-                url.push_str(&format!("{}max_id={}", join_char, id));
-            }
+    ) -> Result<SearchOutcome> {
+        paginate(
+            "Mastodon timeline",
+            self.page_limits,
+            |max_id: Option<String>| {
+                let url = match &max_id {
+                    Some(id) => with_max_id(base_url, id),
+                    None => base_url.to_string(),
+                };
+                async move {
+                    log::debug!("Fetching {}", url);
+                    let (posts, _) = self
+                        .fetch_page(&url, access_token, "Mastodon timeline")
+                        .await?;
+
+                    let mut page = Page {
+                        seen: posts.len(),
+                        ..Page::default()
+                    };
+                    for post in posts {
+                        if post.id.is_empty() {
+                            continue;
+                        }
+                        page.next = Some(post.id.clone());
+
+                        let created_at = match post.created_at.as_deref().and_then(parse_post_date)
+                        {
+                            Some(dt) => dt,
+                            None => continue,
+                        };
+
+                        if created_at < cutoff_date {
+                            page.reached_cutoff = true;
+                            break;
+                        }
+
+                        page.results.push(self.build_result(&post, created_at));
+                    }
+                    Ok(page)
+                }
+            },
+        )
+        .await
+    }
 
-            log::info!("Fetching Mastodon timeline page {}: {}", page, url);
+    /// Base URL of the instance's streaming API. Large instances serve it
+    /// from a separate host, advertised in the instance info.
+    async fn streaming_base_url(&self) -> String {
+        let url = format!("{}/api/v1/instance", self.instance_url);
+        let advertised = self
+            .get_json::<serde_json::Value>(&url, "Mastodon instance")
+            .await
+            .ok()
+            .and_then(|instance| {
+                instance["urls"]["streaming_api"]
+                    .as_str()
+                    .map(str::to_string)
+            })
+            .filter(|streaming| !streaming.is_empty());
+
+        match advertised {
+            Some(streaming) => streaming
+                .replacen("wss://", "https://", 1)
+                .replacen("ws://", "http://", 1)
+                .trim_end_matches('/')
+                .to_string(),
+            None => self.instance_url.clone(),
+        }
+    }
 
-            let mut request = self.client.get(&url);
-            if let Some(token) = access_token {
-                request = request.header("Authorization", format!("Bearer {}", token));
-            }
+    /// Follow a hashtag live, calling `on_post` for every new post until the
+    /// connection drops or the caller stops polling.
+    ///
+    /// Uses the server-sent events transport of `/api/v1/streaming/hashtag`
+    /// rather than the WebSocket one: it carries the same `update` events and
+    /// runs over the existing HTTP client without a WebSocket dependency.
+    /// Only the first tag of the query is followed.
+    pub async fn stream_hashtag<F>(&self, hashtag: &str, mut on_post: F) -> Result<()>
+    where
+        F: FnMut(SearchResult),
+    {
+        let access_token = self.require_access_token()?;
+        let query = TagQuery::parse(hashtag);
+        let Some(tag) = query.primary() else {
+            return Err(anyhow::anyhow!("Enter a hashtag to monitor"));
+        };
 
-            let response = request.send().await?;
-            if !response.status().is_success() {
-                let status = response.status();
-                let body = response.text().await.unwrap_or_default();
-                return Err(anyhow::anyhow!(
-                    "Failed to fetch timeline: {}. Response: {}",
-                    status,
-                    body
-                ));
-            }
+        let url = format!(
+            "{}/api/v1/streaming/hashtag?tag={}",
+            self.streaming_base_url().await,
+            urlencoding::encode(tag)
+        );
 
-            let posts: Vec<PixelfedPost> = response.json().await?;
-            if posts.is_empty() {
-                break;
-            }
+        // The shared client's overall timeout would cut the stream off
+        let client = api_client_builder(&self.network)
+            .default_headers(custom_headers(&self.custom_headers))
+            .connect_timeout(std::time::Duration::from_secs(30))
+            .build()?;
+        let request = client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", access_token))
+            .header(ACCEPT, "text/event-stream");
+        let response = send_checked(request)
+            .await
+            .map_err(|err| api_error(err, "the Mastodon stream"))?;
 
-            let mut found_old_post = false;
-            let mut processed_any = false;
-            let mut fallback_next_max_id: Option<String> = None;
+        log::info!("Streaming Mastodon hashtag #{} from {}", tag, url);
 
-            for post in posts {
-                if post.id.is_empty() {
-                    continue;
+        let mut stream = response.bytes_stream();
+        let mut buffer: Vec<u8> = Vec::new();
+        loop {
+            let chunk = match tokio::time::timeout(STREAM_IDLE_TIMEOUT, stream.next()).await {
+                Ok(Some(chunk)) => chunk?,
+                Ok(None) => return Err(anyhow::anyhow!("The server closed the stream")),
+                Err(_) => {
+                    return Err(anyhow::anyhow!(
+                        "No data from the stream for {} seconds",
+                        STREAM_IDLE_TIMEOUT.as_secs()
+                    ))
                 }
+            };
+            buffer.extend(chunk.iter().filter(|byte| **byte != b'\r'));
 
-                let post_id = post.id.clone();
-                fallback_next_max_id = Some(post_id.clone());
-
-                let created_at_str = match post.created_at.as_deref() {
-                    Some(value) if !value.is_empty() => value,
-                    _ => continue,
-                };
-
-                let created_at = match DateTime::parse_from_rfc3339(created_at_str) {
-                    Ok(dt) => dt.with_timezone(&Utc),
-                    Err(_) => continue,
+            // Events end with a blank line
+            while let Some(end) = buffer.windows(2).position(|pair| pair == b"\n\n") {
+                let event: Vec<u8> = buffer.drain(..end + 2).collect();
+                let Some(post) = Self::parse_update_event(&String::from_utf8_lossy(&event)) else {
+                    continue;
                 };
-
-                if created_at < cutoff_date {
-                    found_old_post = true;
-                    break;
-                }
-
-                let author = Self::account_name(&post);
-                let (media_urls, media_types, media_count) = Self::extract_media(&post);
-                let likes = post.favourites_count.unwrap_or(0);
-                let shares = post.reblogs_count.unwrap_or(0);
-                let url = post
-                    .url
-                    .clone()
-                    .unwrap_or_else(|| self.fallback_post_url(&post));
-
-                results.push(SearchResult {
-                    platform: Platform::Mastodon,
-                    id: post_id.clone(),
-                    author,
-                    content: strip_html_tags(post.content.as_deref().unwrap_or("")),
-                    created_at,
-                    media_urls,
-                    media_types,
-                    media_count,
-                    likes,
-                    shares,
-                    url,
-                });
-
-                processed_any = true;
-                max_id = Some(post_id);
+                let created_at = post
+                    .created_at
+                    .as_deref()
+                    .and_then(parse_post_date)
+                    .unwrap_or_else(Utc::now);
+                on_post(self.build_result(&post, created_at));
             }
+        }
+    }
 
-            if found_old_post {
-                break;
+    /// The status carried by an `update` event; other events (deletions,
+    /// edits) and heartbeat comments are ignored
+    fn parse_update_event(event: &str) -> Option<PixelfedPost> {
+        let mut name = "";
+        let mut data = Vec::new();
+        for line in event.lines() {
+            if let Some(value) = line.strip_prefix("event:") {
+                name = value.trim();
+            } else if let Some(value) = line.strip_prefix("data:") {
+                data.push(value.strip_prefix(' ').unwrap_or(value));
             }
+        }
+        // Multi-line payloads are joined with newlines per the SSE spec
+        let data = data.join("\n");
 
-            if !processed_any {
-                if let Some(next_id) = fallback_next_max_id {
-                    if max_id.as_ref() == Some(&next_id) {
-                        break;
-                    }
-                    max_id = Some(next_id);
-                } else {
-                    break;
-                }
+        if name != "update" {
+            return None;
+        }
+        match serde_json::from_str::<PixelfedPost>(&data) {
+            Ok(post) if !post.id.is_empty() => Some(post),
+            Ok(_) => None,
+            Err(err) => {
+                log::warn!("Skipping unreadable streamed post: {}", err);
+                None
             }
-
-            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
         }
-
-        Ok(results)
     }
 }
 
@@ -328,18 +741,56 @@ impl SocialPlatform for MastodonService {
         &self.instance_url
     }
 
-    async fn search_user(&self, username: &str, days_back: u32) -> Result<Vec<SearchResult>> {
+    async fn search_user(&self, username: &str, days_back: u32) -> Result<SearchOutcome> {
         let cutoff_date = Utc::now() - Duration::days(days_back as i64);
         self.search_user_posts(username, cutoff_date).await
     }
 
-    async fn search_hashtag(&self, hashtag: &str, days_back: u32) -> Result<Vec<SearchResult>> {
+    async fn search_hashtag(&self, hashtag: &str, days_back: u32) -> Result<SearchOutcome> {
         let cutoff_date = Utc::now() - Duration::days(days_back as i64);
         self.search_hashtag_posts(hashtag, cutoff_date).await
     }
+
+    async fn search_bookmarks(&self, days_back: u32) -> Result<SearchOutcome> {
+        let cutoff_date = Utc::now() - Duration::days(days_back as i64);
+        self.fetch_account_collection("/api/v1/bookmarks", cutoff_date)
+            .await
+    }
+
+    async fn search_favourites(&self, days_back: u32) -> Result<SearchOutcome> {
+        let cutoff_date = Utc::now() - Duration::days(days_back as i64);
+        self.fetch_account_collection("/api/v1/favourites", cutoff_date)
+            .await
+    }
+
+    async fn search_post_url(&self, url: &str) -> Result<SearchOutcome> {
+        self.fetch_post_thread(url).await.map(SearchOutcome::from)
+    }
 }
 
 fn strip_html_tags(html: &str) -> String {
     let re = regex::Regex::new(r"<[^>]*>").unwrap();
     re.replace_all(html, "").trim().to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_event_joins_data_lines_with_newlines() {
+        let event = "event: update\n\
+                     data: {\"id\": \"42\",\n\
+                     data: \"account\": {\"id\": \"7\"},\n\
+                     data: \"content\": \"<p>hi</p>\"}";
+        let post = MastodonService::parse_update_event(event).unwrap();
+        assert_eq!(post.id, "42");
+        assert_eq!(post.account.id, "7");
+    }
+
+    #[test]
+    fn non_update_events_are_ignored() {
+        assert!(MastodonService::parse_update_event("event: delete\ndata: 42").is_none());
+        assert!(MastodonService::parse_update_event(":thump").is_none());
+    }
+}
@@ -5,16 +5,40 @@
 
 pub mod auth_service;
 pub mod bluesky_service;
+pub mod cache_service;
+pub mod contact_sheet_service;
 pub mod download_service;
+pub mod health_service;
+pub mod history_service;
+pub mod host_limiter;
+pub mod html_export_service;
+pub mod http_util;
+pub mod link_export_service;
 pub mod mastodon_service;
+pub mod paginator;
 pub mod pixelfed_service;
 pub mod platform_trait;
 pub mod settings_service;
+pub mod snapshot_service;
 
-pub use auth_service::AuthService;
+pub use auth_service::{oauth_scopes, AuthService};
 pub use bluesky_service::BlueskyService;
-pub use download_service::DownloadService;
+pub use cache_service::CacheService;
+pub use contact_sheet_service::ContactSheetService;
+pub use download_service::{
+    DownloadControl, DownloadService, DownloadSummary, MediaStatus, ResumableDownload,
+};
+pub use health_service::{CredentialStatus, HealthService};
+pub use history_service::{DownloadRecord, HistoryService};
+pub use host_limiter::HostLimiter;
+pub use html_export_service::HtmlExportService;
+pub use link_export_service::LinkExportService;
 pub use mastodon_service::MastodonService;
 pub use pixelfed_service::PixelfedService;
-pub use platform_trait::{platform_display_name, SocialPlatform};
+pub use platform_trait::{
+    platform_display_name, platform_service, platform_services, public_platform_service,
+    restricted_account_notice, search_tags_separately, user_lookup_status, with_account_notice,
+    SocialPlatform, StatusSender,
+};
 pub use settings_service::SettingsService;
+pub use snapshot_service::SnapshotService;
@@ -0,0 +1,155 @@
+// ============================================================================
+// Paginator - Shared page loop for all platform searches
+// ============================================================================
+// Every platform pages backwards through time until it reaches the date
+// cutoff. The loop around each page (page cap, empty-page guard, repeated
+// cursor check, partial results on a later failure, courtesy pause) lives
+// here; the services only fetch and convert one page at a time.
+// ============================================================================
+
+use std::future::Future;
+
+use anyhow::Result;
+
+use crate::models::{SearchOutcome, SearchResult, SearchSettings, SearchStats};
+
+/// Pause between pages so instances are not hammered
+const PAGE_DELAY: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// What one page of a search yielded
+pub struct Page<C> {
+    /// Results kept from this page
+    pub results: Vec<SearchResult>,
+    /// The page reached posts older than the cutoff, so later pages would
+    /// only be older still
+    pub reached_cutoff: bool,
+    /// Where the next page starts; None when there are no more pages
+    pub next: Option<C>,
+    /// Posts the API returned on this page, before any filtering
+    pub seen: usize,
+}
+
+impl<C> Default for Page<C> {
+    fn default() -> Self {
+        Self {
+            results: Vec::new(),
+            reached_cutoff: false,
+            next: None,
+            seen: 0,
+        }
+    }
+}
+
+/// How far a search may page, from the search settings
+#[derive(Debug, Clone, Copy)]
+pub struct PageLimits {
+    pub max_pages: u32,
+    /// Pages in a row that add no results before giving up; 0 never
+    pub max_empty_pages: u32,
+}
+
+impl PageLimits {
+    pub fn from_settings(search: &SearchSettings) -> Self {
+        Self {
+            max_pages: search.max_pages.max(1),
+            max_empty_pages: search.max_empty_pages,
+        }
+    }
+}
+
+/// Fetch pages until one reaches the cutoff, runs out, or a limit trips.
+///
+/// `fetch` gets the cursor from the previous page, or None for the first.
+/// A failure on the first page is an error; a later one returns the pages
+/// already fetched as a partial outcome. `what` names the search in logs,
+/// e.g. "Mastodon timeline".
+pub async fn paginate<C, F, Fut>(
+    what: &str,
+    limits: PageLimits,
+    mut fetch: F,
+) -> Result<SearchOutcome>
+where
+    C: Clone + PartialEq,
+    F: FnMut(Option<C>) -> Fut,
+    Fut: Future<Output = Result<Page<C>>>,
+{
+    let mut results = Vec::new();
+    let mut cursor: Option<C> = None;
+    let mut page = 0u32;
+    let mut empty_streak = 0u32;
+    let mut stats = SearchStats::default();
+    let with_stats = |mut outcome: SearchOutcome, stats: SearchStats| {
+        outcome.stats = stats;
+        outcome
+    };
+
+    loop {
+        page += 1;
+        if page > limits.max_pages {
+            log::warn!(
+                "{} reached the {}-page limit; results are truncated",
+                what,
+                limits.max_pages
+            );
+            return Ok(with_stats(
+                SearchOutcome::page_limit(results, limits.max_pages),
+                stats,
+            ));
+        }
+
+        log::info!("Fetching {} page {}", what, page);
+
+        let fetched = match fetch(cursor.clone()).await {
+            Ok(fetched) => fetched,
+            Err(err) if page == 1 => return Err(err),
+            Err(err) => {
+                log::warn!("{} stopped at page {}: {}", what, page, err);
+                return Ok(with_stats(
+                    SearchOutcome::partial(results, err.to_string()),
+                    stats,
+                ));
+            }
+        };
+        stats.pages = page;
+        stats.seen += fetched.seen;
+
+        let kept = fetched.results.len();
+        results.extend(fetched.results);
+        if fetched.reached_cutoff {
+            break;
+        }
+
+        if kept > 0 {
+            empty_streak = 0;
+        } else {
+            empty_streak += 1;
+            if limits.max_empty_pages > 0 && empty_streak >= limits.max_empty_pages {
+                log::warn!(
+                    "{}: {} pages in a row added no posts; stopping early",
+                    what,
+                    empty_streak
+                );
+                return Ok(with_stats(
+                    SearchOutcome::unproductive(results, empty_streak),
+                    stats,
+                ));
+            }
+        }
+
+        // A server handing back the same cursor would loop forever
+        match fetched.next {
+            Some(next) if cursor.as_ref() != Some(&next) => cursor = Some(next),
+            _ => break,
+        }
+
+        tokio::time::sleep(PAGE_DELAY).await;
+    }
+
+    Ok(with_stats(SearchOutcome::complete(results), stats))
+}
+
+/// A Mastodon-style timeline URL continued below `max_id`
+pub fn with_max_id(base_url: &str, max_id: &str) -> String {
+    let join_char = if base_url.contains('?') { '&' } else { '?' };
+    format!("{}{}max_id={}", base_url, join_char, max_id)
+}
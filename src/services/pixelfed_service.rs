@@ -10,20 +10,48 @@ use async_trait::async_trait;
 use chrono::{DateTime, Duration, Utc};
 use reqwest::Client;
 
-use crate::models::{AppSettings, PixelfedPost, Platform, SearchResult};
-use crate::services::SocialPlatform;
+use crate::models::{
+    AppSettings, MediaItem, PixelfedPost, Platform, SearchOutcome, SearchResult, TagCombination,
+    TagQuery,
+};
+use crate::services::http_util::{
+    api_client_builder, instance_headers, send_and_parse, PageSize, SearchError,
+};
+use crate::services::paginator::{paginate, with_max_id, Page, PageLimits};
+use crate::services::{
+    restricted_account_notice, search_tags_separately, user_lookup_status, with_account_notice,
+    HostLimiter, SocialPlatform, StatusSender,
+};
+use crate::utils::{cap_content, parse_post_date};
+
+/// Pixelfed accepts up to 100 posts per timeline page
+const MAX_PAGE_SIZE: u32 = 100;
+/// Trending tags offered as shortcuts in the search form
+const MAX_TRENDING_TAGS: usize = 20;
 
 pub struct PixelfedService {
     client: Client,
     instance_url: String,
     access_token: Option<String>,
     enabled: bool,
+    requests_per_instance: u32,
+    prefer_original: bool,
+    count_only: bool,
+    page_limits: PageLimits,
+    page_size: PageSize,
+    tag_combination: TagCombination,
+    status: Option<StatusSender>,
+    /// Searching another instance anonymously rather than the signed-in one
+    public: bool,
 }
 
 impl PixelfedService {
     pub fn new(settings: &AppSettings) -> Self {
         let platform_auth = &settings.api.pixelfed;
-        let trimmed_instance = platform_auth.instance_url.trim();
+        let trimmed_instance = match platform_auth.instance_url.trim() {
+            "" => settings.home_instances.pixelfed.trim(),
+            url => url,
+        };
 
         let normalized_url = if trimmed_instance.starts_with("http://")
             || trimmed_instance.starts_with("https://")
@@ -35,8 +63,8 @@ impl PixelfedService {
             format!("https://{}", trimmed_instance.trim_end_matches('/'))
         };
 
-        let client = Client::builder()
-            .user_agent("Fedi-Sleuth/0.1.0")
+        let client = api_client_builder(&settings.network)
+            .default_headers(instance_headers(&platform_auth.custom_headers))
             .timeout(std::time::Duration::from_secs(60))
             .build()
             .expect("Failed to create HTTP client");
@@ -46,7 +74,51 @@ impl PixelfedService {
             instance_url: normalized_url,
             access_token: platform_auth.access_token.clone(),
             enabled: platform_auth.enabled,
+            requests_per_instance: settings.search.max_requests_per_instance,
+            prefer_original: settings.download.prefer_original_over_preview,
+            count_only: settings.search.count_only,
+            page_limits: PageLimits::from_settings(&settings.search),
+            page_size: PageSize::new(settings.search.page_size, MAX_PAGE_SIZE),
+            tag_combination: settings.search.tag_combination,
+            status: None,
+            public: false,
+        }
+    }
+
+    /// A service for a one-off anonymous search of `instance_url` instead
+    /// of the configured instance. Only public posts are visible.
+    pub fn for_instance(settings: &AppSettings, instance_url: &str) -> Self {
+        let mut settings = settings.clone();
+        let auth = &mut settings.api.pixelfed;
+        auth.instance_url = instance_url.trim().to_string();
+        auth.access_token = None;
+        auth.enabled = true;
+        auth.custom_headers.clear();
+        Self {
+            public: true,
+            ..Self::new(&settings)
+        }
+    }
+
+    /// Report search progress to the UI as it happens
+    pub fn with_status(mut self, status: StatusSender) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    fn report(&self, message: String) {
+        if let Some(status) = &self.status {
+            let _ = status.send(message);
+        }
+    }
+
+    /// The token for a user or hashtag search; None when searching another
+    /// instance anonymously
+    fn search_token(&self) -> Result<Option<&str>> {
+        if self.public {
+            return Ok(None);
         }
+        self.require_access_token().map(Some)
     }
 
     fn require_access_token(&self) -> Result<&str> {
@@ -84,120 +156,154 @@ impl PixelfedService {
             .to_string()
     }
 
-    fn extract_media(post: &PixelfedPost) -> (Vec<String>, Vec<String>, u32) {
-        let mut urls = Vec::new();
-        let mut types = Vec::new();
+    /// Collect every attachment of a post (albums included); the count only
+    /// covers attachments that have a usable URL
+    fn extract_media(&self, post: &PixelfedPost) -> Vec<MediaItem> {
+        let mut media = Vec::new();
 
         for attachment in &post.media_attachments {
-            if let Some(url) = attachment.url.as_ref() {
-                let trimmed = url.trim();
-                if trimmed.is_empty() {
-                    continue;
-                }
-                urls.push(trimmed.to_string());
-                types.push(attachment.r#type.as_deref().unwrap_or("").to_string());
+            match attachment.download_url(self.prefer_original) {
+                Some(url) => media.push(attachment.to_media_item(url)),
+                None => log::debug!(
+                    "Skipping attachment {} of post {}: no url or preview_url",
+                    attachment.id,
+                    post.id
+                ),
             }
         }
 
-        let count = urls.len() as u32;
-        (urls, types, count)
+        media
     }
 
     async fn search_user_posts(
         &self,
         username: &str,
         cutoff_date: DateTime<Utc>,
-    ) -> Result<Vec<SearchResult>> {
-        let access_token = self.require_access_token()?;
+    ) -> Result<SearchOutcome> {
+        let access_token = self.search_token()?;
 
         let clean_username = username.trim_start_matches('@');
         let search_query = clean_username.to_string();
 
-        let search_url = format!(
-            "{}/api/v2/search?q={}&type=accounts&resolve=true&limit=1",
-            self.instance_url,
-            urlencoding::encode(&search_query)
-        );
+        // Anonymous search cannot resolve remote accounts; lookup still
+        // finds any account the instance already knows
+        let search_url = match access_token {
+            Some(_) => format!(
+                "{}/api/v2/search?q={}&type=accounts&resolve=true&limit=1",
+                self.instance_url,
+                urlencoding::encode(&search_query)
+            ),
+            None => format!(
+                "{}/api/v1/accounts/lookup?acct={}",
+                self.instance_url,
+                urlencoding::encode(&search_query)
+            ),
+        };
 
         log::info!(
             "Searching for user '{}' at: {} (this may take a moment for federated lookups...)",
             search_query,
             search_url
         );
+        self.report(user_lookup_status(Platform::Pixelfed, &search_query));
+
+        let permit = HostLimiter::global()
+            .acquire(&search_url, self.requests_per_instance)
+            .await;
 
-        let search_response = self
+        let mut request = self
             .client
             .get(&search_url)
-            .header("Authorization", format!("Bearer {}", access_token))
-            .timeout(std::time::Duration::from_secs(45))
-            .send()
-            .await?;
-
-        if !search_response.status().is_success() {
-            let status = search_response.status();
-            let body = search_response.text().await.unwrap_or_default();
-
-            let helpful_msg = if search_query.contains("@mastodon.")
-                || search_query.contains("@fosstodon.")
-            {
-                "\n\nNote: Pixelfed instances may have limited federation with Mastodon instances. Try: 1) Searching on a Mastodon instance instead, or 2) Searching for Pixelfed users only (e.g., from pixelfed.social, pix.art, etc.)"
-            } else {
-                ""
-            };
-
-            return Err(anyhow::anyhow!(
-				"User search failed: {}. Response: {}. User '{}' may not exist or instance may not be federated.{}",
-				status,
-				body,
-				search_query,
-				helpful_msg
-			));
+            .timeout(std::time::Duration::from_secs(45));
+        if let Some(token) = access_token {
+            request = request.header("Authorization", format!("Bearer {}", token));
         }
+        let search_data: serde_json::Value = match send_and_parse(
+            request,
+            "Pixelfed account search",
+        )
+        .await
+        {
+            Ok(data) => data,
+            Err(err @ (SearchError::Challenge { .. } | SearchError::Parse(_))) => {
+                return Err(err.into())
+            }
+            Err(err) => {
+                let helpful_msg = if search_query.contains("@mastodon.")
+                    || search_query.contains("@fosstodon.")
+                {
+                    "\n\nNote: Pixelfed instances may have limited federation with Mastodon instances. Try: 1) Searching on a Mastodon instance instead, or 2) Searching for Pixelfed users only (e.g., from pixelfed.social, pix.art, etc.)"
+                } else {
+                    ""
+                };
 
-        let search_data: serde_json::Value = search_response.json().await?;
-        let accounts = search_data["accounts"]
-            .as_array()
-            .ok_or_else(|| anyhow::anyhow!("Invalid search response"))?;
-
-        if accounts.is_empty() {
-            return Err(anyhow::anyhow!(
-				"User '{}' not found. If this is a remote user, their instance may not be federated with {}. Try searching for them directly on their home instance.",
+                return Err(anyhow::anyhow!(
+				"User search failed: {}. User '{}' may not exist or instance may not be federated.{}",
+				err,
 				search_query,
-				self.instance_url
+				helpful_msg
 			));
-        }
+            }
+        };
+        drop(permit);
+        let account = if access_token.is_some() {
+            let accounts = search_data["accounts"]
+                .as_array()
+                .ok_or_else(|| anyhow::anyhow!("Invalid search response"))?;
+
+            match accounts.first() {
+                Some(account) => account.clone(),
+                None => return Err(anyhow::anyhow!(
+                    "User '{}' not found. If this is a remote user, their instance may not be federated with {}. Try searching for them directly on their home instance.",
+                    search_query,
+                    self.instance_url
+                )),
+            }
+        } else {
+            search_data
+        };
 
-        let user_id = accounts[0]["id"]
+        let user_id = account["id"]
             .as_str()
             .ok_or_else(|| anyhow::anyhow!("Invalid user data"))?;
+        let notice = restricted_account_notice(&account);
+        if let Some(notice) = &notice {
+            log::info!("Pixelfed user '{}': {}", search_query, notice);
+        }
 
-        self.fetch_timeline(
-            &format!(
-                "{}/api/v1/accounts/{}/statuses?limit=40",
-                self.instance_url, user_id
-            ),
-            cutoff_date,
-            Some(access_token),
-        )
-        .await
+        self.report("Pixelfed: Fetching posts…".to_string());
+        let outcome = self
+            .fetch_timeline(
+                &format!(
+                    "{}/api/v1/accounts/{}/statuses?limit={}",
+                    self.instance_url,
+                    user_id,
+                    self.page_size.get()
+                ),
+                cutoff_date,
+                access_token,
+            )
+            .await;
+        with_account_notice(outcome, notice)
     }
 
     async fn search_hashtag_posts(
         &self,
         hashtag: &str,
         cutoff_date: DateTime<Utc>,
-    ) -> Result<Vec<SearchResult>> {
-        let access_token = self.require_access_token()?;
-        let clean_hashtag = hashtag.trim_start_matches('#');
-
-        self.fetch_timeline(
-            &format!(
-                "{}/api/v1/timelines/tag/{}?limit=40",
-                self.instance_url, clean_hashtag
-            ),
-            cutoff_date,
-            Some(access_token),
-        )
+    ) -> Result<SearchOutcome> {
+        let access_token = self.search_token()?;
+        // Pixelfed's tag timeline takes one tag and no exclusions
+        let query = TagQuery::parse(hashtag);
+        search_tags_separately(&query, self.tag_combination, self.count_only, |tag| {
+            let url = format!(
+                "{}/api/v1/timelines/tag/{}?limit={}",
+                self.instance_url,
+                urlencoding::encode(&tag),
+                self.page_size.get()
+            );
+            async move { self.fetch_timeline(&url, cutoff_date, access_token).await }
+        })
         .await
     }
 
@@ -206,124 +312,152 @@ impl PixelfedService {
         base_url: &str,
         cutoff_date: DateTime<Utc>,
         access_token: Option<&str>,
-    ) -> Result<Vec<SearchResult>> {
-        let mut results = Vec::new();
-        let mut max_id: Option<String> = None;
-        let mut pages_fetched = 0u32;
-
-        loop {
-            pages_fetched += 1;
-            if pages_fetched > 100 {
-                log::warn!(
-                    "Pixelfed timeline fetch aborted after {} pages with no cutoff",
-                    pages_fetched
-                );
-                break;
-            }
-
-            let mut url = base_url.to_string();
-            if let Some(ref id) = max_id {
-                let join_char = if url.contains('?') { '&' } else { '?' };
-                url.push_str(&format!("{}max_id={}", join_char, id));
-            }
-
-            log::info!("Fetching Pixelfed timeline page {}: {}", pages_fetched, url);
-
-            let mut request = self.client.get(&url);
-            if let Some(token) = access_token {
-                request = request.header("Authorization", format!("Bearer {}", token));
-            }
-
-            let response = request.send().await?;
-
-            if !response.status().is_success() {
-                let status = response.status();
-                let body = response.text().await.unwrap_or_default();
-                return Err(anyhow::anyhow!(
-                    "Failed to fetch timeline: {}. Response: {}",
-                    status,
-                    body
-                ));
-            }
-
-            let posts: Vec<PixelfedPost> = response.json().await?;
-            if posts.is_empty() {
-                break;
-            }
-
-            let mut found_old_post = false;
-            let mut processed_any = false;
-            let mut fallback_next_max_id: Option<String> = None;
-
-            for post in posts {
-                if post.id.is_empty() {
-                    continue;
+    ) -> Result<SearchOutcome> {
+        paginate(
+            "Pixelfed timeline",
+            self.page_limits,
+            |max_id: Option<String>| {
+                let url = match &max_id {
+                    Some(id) => with_max_id(base_url, id),
+                    None => base_url.to_string(),
+                };
+                async move {
+                    log::debug!("Fetching {}", url);
+                    let posts = self.fetch_page(&url, access_token).await?;
+
+                    let mut page = Page {
+                        seen: posts.len(),
+                        ..Page::default()
+                    };
+                    for post in posts {
+                        if post.id.is_empty() {
+                            continue;
+                        }
+                        page.next = Some(post.id.clone());
+
+                        let Some(created_at) = post_created_at(&post) else {
+                            continue;
+                        };
+
+                        if created_at < cutoff_date {
+                            page.reached_cutoff = true;
+                            break;
+                        }
+
+                        page.results.push(self.to_result(&post, created_at));
+                    }
+                    Ok(page)
                 }
+            },
+        )
+        .await
+    }
 
-                fallback_next_max_id = Some(post.id.clone());
+    /// Turn a post into a result, or a bare count in count-only mode
+    fn to_result(&self, post: &PixelfedPost, created_at: DateTime<Utc>) -> SearchResult {
+        // A boost (share) carries no content of its own; report the original
+        let (post, reblogged_by) = match post.reblog.as_deref() {
+            Some(original) => (original, Some(Self::account_display_name(post))),
+            None => (post, None),
+        };
+        let media = self.extract_media(post);
+        if self.count_only {
+            return SearchResult::count_stub(
+                Platform::Pixelfed,
+                post.id.clone(),
+                created_at,
+                &media,
+            );
+        }
 
-                let created_at_str = match post.created_at.as_deref() {
-                    Some(value) if !value.is_empty() => value,
-                    _ => continue,
-                };
+        SearchResult {
+            platform: Platform::Pixelfed,
+            id: post.id.clone(),
+            author: Self::account_display_name(post),
+            content: cap_content(strip_html_tags(post.content.as_deref().unwrap_or(""))),
+            created_at,
+            media,
+            likes: post.favourites_count.unwrap_or(0),
+            shares: post.reblogs_count.unwrap_or(0),
+            url: post
+                .url
+                .clone()
+                .unwrap_or_else(|| self.fallback_post_url(&post.id)),
+            content_warning: post.content_warning(),
+            reblogged_by,
+            account_profile: None,
+            poll: post.poll.clone(),
+        }
+    }
 
-                let created_at = match DateTime::parse_from_rfc3339(created_at_str) {
-                    Ok(dt) => dt.with_timezone(&Utc),
-                    Err(_) => continue,
-                };
+    /// Posts on the instance's discover page. The list is ranked, not
+    /// chronological, so it is one request filtered by the cutoff.
+    async fn search_trending_posts(&self, days_back: u32) -> Result<SearchOutcome> {
+        let access_token = self.require_access_token()?;
+        let range = trending_range(days_back);
+        self.report(format!("Pixelfed: Fetching {} trending posts…", range));
 
-                if created_at < cutoff_date {
-                    found_old_post = true;
-                    break;
-                }
+        let url = format!(
+            "{}/api/v1.1/discover/posts/trending?range={}",
+            self.instance_url, range
+        );
+        let posts = self.fetch_page(&url, Some(access_token)).await?;
 
-                let author = Self::account_display_name(&post);
-                let (media_urls, media_types, media_count) = Self::extract_media(&post);
-                let likes = post.favourites_count.unwrap_or(0);
-                let shares = post.reblogs_count.unwrap_or(0);
-                let url = post
-                    .url
-                    .clone()
-                    .unwrap_or_else(|| self.fallback_post_url(&post.id));
-
-                let search_result = SearchResult {
-                    platform: Platform::Pixelfed,
-                    id: post.id.clone(),
-                    author,
-                    content: strip_html_tags(post.content.as_deref().unwrap_or("")),
-                    created_at,
-                    media_urls,
-                    media_types,
-                    media_count,
-                    likes,
-                    shares,
-                    url,
-                };
+        let cutoff_date = Utc::now() - Duration::days(days_back as i64);
+        let results = posts
+            .iter()
+            .filter(|post| !post.id.is_empty())
+            .filter_map(|post| Some((post, post_created_at(post)?)))
+            .filter(|(_, created_at)| *created_at >= cutoff_date)
+            .map(|(post, created_at)| self.to_result(post, created_at))
+            .collect();
+        Ok(SearchOutcome::complete(results).single_page(posts.len()))
+    }
 
-                processed_any = true;
-                max_id = Some(post.id);
-                results.push(search_result);
-            }
+    /// Hashtags trending on the instance, most popular first, without the #
+    pub async fn trending_tags(&self) -> Result<Vec<String>> {
+        let access_token = self.require_access_token()?;
+        let url = format!("{}/api/v1.1/discover/posts/hashtags", self.instance_url);
+        let request = self
+            .client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", access_token));
+        let tags: Vec<serde_json::Value> = send_and_parse(request, "Pixelfed trending tags")
+            .await
+            .map_err(|err| anyhow::anyhow!("Failed to fetch trending tags: {}", err))?;
+        Ok(tags
+            .iter()
+            .filter_map(|tag| tag.get("name").or_else(|| tag.get("hashtag"))?.as_str())
+            .map(|name| name.trim_start_matches('#').to_string())
+            .filter(|name| !name.is_empty())
+            .take(MAX_TRENDING_TAGS)
+            .collect())
+    }
 
-            if found_old_post {
-                break;
-            }
+    /// Fetch one page of a timeline
+    async fn fetch_page(&self, url: &str, access_token: Option<&str>) -> Result<Vec<PixelfedPost>> {
+        let _permit = HostLimiter::global()
+            .acquire(url, self.requests_per_instance)
+            .await;
 
-            if !processed_any {
-                if let Some(next_id) = fallback_next_max_id {
-                    if max_id.as_ref() == Some(&next_id) {
-                        break;
-                    }
-                    max_id = Some(next_id);
-                } else {
-                    break;
-                }
+        loop {
+            let mut request = self.client.get(self.page_size.apply(url));
+            if let Some(token) = access_token {
+                request = request.header("Authorization", format!("Bearer {}", token));
             }
 
-            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+            return match send_and_parse(request, "Pixelfed timeline").await {
+                Err(err)
+                    if err
+                        .status()
+                        .is_some_and(|status| self.page_size.fall_back(status)) =>
+                {
+                    continue
+                }
+                Err(err) => Err(anyhow::anyhow!("Failed to fetch timeline: {}", err)),
+                Ok(values) => Ok(PixelfedPost::parse_page(values)),
+            };
         }
-
-        Ok(results)
     }
 }
 
@@ -352,21 +486,39 @@ impl SocialPlatform for PixelfedService {
         &self.instance_url
     }
 
-    async fn search_user(&self, username: &str, days_back: u32) -> Result<Vec<SearchResult>> {
+    async fn search_user(&self, username: &str, days_back: u32) -> Result<SearchOutcome> {
         let cutoff_date = Utc::now() - Duration::days(days_back as i64);
         self.search_user_posts(username, cutoff_date).await
     }
 
-    async fn search_hashtag(&self, hashtag: &str, days_back: u32) -> Result<Vec<SearchResult>> {
+    async fn search_hashtag(&self, hashtag: &str, days_back: u32) -> Result<SearchOutcome> {
         let cutoff_date = Utc::now() - Duration::days(days_back as i64);
         self.search_hashtag_posts(hashtag, cutoff_date).await
     }
+
+    async fn search_trending(&self, days_back: u32) -> Result<SearchOutcome> {
+        self.search_trending_posts(days_back).await
+    }
 }
 
 // ============================================================================
 // Helper Functions
 // ============================================================================
 
+/// When a post was published, if it says so in a date we can read
+fn post_created_at(post: &PixelfedPost) -> Option<DateTime<Utc>> {
+    post.created_at.as_deref().and_then(parse_post_date)
+}
+
+/// The discover page's ranking window that covers `days_back`
+fn trending_range(days_back: u32) -> &'static str {
+    match days_back {
+        0..=1 => "daily",
+        2..=31 => "monthly",
+        _ => "yearly",
+    }
+}
+
 fn strip_html_tags(html: &str) -> String {
     let re = regex::Regex::new(r"<[^>]*>").unwrap();
     re.replace_all(html, "").trim().to_string()
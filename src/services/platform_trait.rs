@@ -6,10 +6,14 @@
 // implementation file with platform-specific API calls.
 // ============================================================================
 
+use std::collections::HashSet;
+use std::future::Future;
+
 use anyhow::Result;
 use async_trait::async_trait;
 
-use crate::models::{Platform, SearchResult, SearchType};
+use crate::models::{AppSettings, Platform, SearchOutcome, SearchType, TagCombination, TagQuery};
+use crate::services::{BlueskyService, MastodonService, PixelfedService};
 
 /// Common interface for all social media platforms
 #[async_trait]
@@ -33,8 +37,10 @@ pub trait SocialPlatform: Send + Sync {
     /// * `days_back` - Number of days to search back
     ///
     /// # Returns
-    /// Vector of SearchResult with platform field populated
-    async fn search_user(&self, username: &str, days_back: u32) -> Result<Vec<SearchResult>>;
+    /// SearchOutcome with the platform field of each result populated. When
+    /// a later page fails, the pages already fetched are returned as a
+    /// partial outcome instead of an error.
+    async fn search_user(&self, username: &str, days_back: u32) -> Result<SearchOutcome>;
 
     /// Search for posts by hashtag
     ///
@@ -43,23 +49,154 @@ pub trait SocialPlatform: Send + Sync {
     /// * `days_back` - Number of days to search back
     ///
     /// # Returns
-    /// Vector of SearchResult with platform field populated
-    async fn search_hashtag(&self, hashtag: &str, days_back: u32) -> Result<Vec<SearchResult>>;
+    /// SearchOutcome with the platform field of each result populated
+    async fn search_hashtag(&self, hashtag: &str, days_back: u32) -> Result<SearchOutcome>;
+
+    /// Posts bookmarked by the authenticated account
+    ///
+    /// Platforms without a bookmarks API keep the default, which reports
+    /// the mode as unsupported.
+    async fn search_bookmarks(&self, _days_back: u32) -> Result<SearchOutcome> {
+        Err(anyhow::anyhow!(
+            "Bookmarks are not available on {}",
+            self.platform().name()
+        ))
+    }
+
+    /// Posts favourited by the authenticated account
+    async fn search_favourites(&self, _days_back: u32) -> Result<SearchOutcome> {
+        Err(anyhow::anyhow!(
+            "Favourites are not available on {}",
+            self.platform().name()
+        ))
+    }
+
+    /// A single post addressed by its web URL, plus its thread when enabled
+    async fn search_post_url(&self, _url: &str) -> Result<SearchOutcome> {
+        Err(anyhow::anyhow!(
+            "Post URL lookup is not available on {}",
+            self.platform().name()
+        ))
+    }
+
+    /// Posts from a custom feed, given its AT-URI or web link
+    async fn search_feed(&self, _feed: &str, _days_back: u32) -> Result<SearchOutcome> {
+        Err(anyhow::anyhow!(
+            "Custom feeds are not available on {}",
+            self.platform().name()
+        ))
+    }
+
+    /// Posts trending on the instance within the last `days_back` days
+    async fn search_trending(&self, _days_back: u32) -> Result<SearchOutcome> {
+        Err(anyhow::anyhow!(
+            "Trending posts are not available on {}",
+            self.platform().name()
+        ))
+    }
 
-    /// Generic search method that dispatches to user or hashtag search
+    /// Generic search method that dispatches on the search type
     async fn search(
         &self,
         query: String,
         search_type: SearchType,
         days_back: u32,
-    ) -> Result<Vec<SearchResult>> {
+    ) -> Result<SearchOutcome> {
         match search_type {
             SearchType::User => self.search_user(&query, days_back).await,
             SearchType::Hashtag => self.search_hashtag(&query, days_back).await,
+            SearchType::Bookmarks => self.search_bookmarks(days_back).await,
+            SearchType::Favourites => self.search_favourites(days_back).await,
+            SearchType::PostUrl => self.search_post_url(&query).await,
+            SearchType::Feed => self.search_feed(&query, days_back).await,
+            SearchType::Trending => self.search_trending(days_back).await,
         }
     }
 }
 
+/// Progress lines a service sends while a search runs, shown as the status
+pub type StatusSender = tokio::sync::mpsc::UnboundedSender<String>;
+
+/// The service for one platform, built from the settings. Services that
+/// report slow phases send them on `status` when given one.
+pub fn platform_service(
+    platform: Platform,
+    settings: &AppSettings,
+    status: Option<&StatusSender>,
+) -> Box<dyn SocialPlatform> {
+    match platform {
+        Platform::Pixelfed => {
+            let service = PixelfedService::new(settings);
+            match status {
+                Some(status) => Box::new(service.with_status(status.clone())),
+                None => Box::new(service),
+            }
+        }
+        Platform::Mastodon => {
+            let service = MastodonService::new(settings);
+            match status {
+                Some(status) => Box::new(service.with_status(status.clone())),
+                None => Box::new(service),
+            }
+        }
+        Platform::Bluesky => Box::new(BlueskyService::new(settings)),
+    }
+}
+
+/// A service that searches `instance_url` anonymously instead of the
+/// platform's configured instance. Bluesky has one public API host, so it
+/// gets its usual service.
+pub fn public_platform_service(
+    platform: Platform,
+    settings: &AppSettings,
+    instance_url: &str,
+    status: Option<&StatusSender>,
+) -> Box<dyn SocialPlatform> {
+    match platform {
+        Platform::Pixelfed => {
+            let service = PixelfedService::for_instance(settings, instance_url);
+            match status {
+                Some(status) => Box::new(service.with_status(status.clone())),
+                None => Box::new(service),
+            }
+        }
+        Platform::Mastodon => {
+            let service = MastodonService::for_instance(settings, instance_url);
+            match status {
+                Some(status) => Box::new(service.with_status(status.clone())),
+                None => Box::new(service),
+            }
+        }
+        Platform::Bluesky => platform_service(platform, settings, status),
+    }
+}
+
+/// Every platform's service, in `Platform::ALL` order
+pub fn platform_services(
+    settings: &AppSettings,
+    status: Option<&StatusSender>,
+) -> Vec<Box<dyn SocialPlatform>> {
+    Platform::ALL
+        .into_iter()
+        .map(|platform| platform_service(platform, settings, status))
+        .collect()
+}
+
+/// Status for the account lookup a user search starts with. Remote handles
+/// go through federation, which can take half a minute.
+pub fn user_lookup_status(platform: Platform, username: &str) -> String {
+    let handle = username.trim().trim_start_matches('@');
+    if handle.contains('@') {
+        format!(
+            "{}: Resolving @{} via federation — this can take a while…",
+            platform.name(),
+            handle
+        )
+    } else {
+        format!("{}: Looking up @{}…", platform.name(), handle)
+    }
+}
+
 /// Helper to create a descriptive name for logging
 pub fn platform_display_name(platform: Platform, instance_url: &str) -> String {
     match platform {
@@ -68,3 +205,96 @@ pub fn platform_display_name(platform: Platform, instance_url: &str) -> String {
         Platform::Bluesky => format!("{} Bluesky", platform.emoji()),
     }
 }
+
+/// Note for a user search when the looked-up account is locked or limited,
+/// from the account object of the lookup
+pub fn restricted_account_notice(account: &serde_json::Value) -> Option<String> {
+    if account["locked"].as_bool() == Some(true) {
+        Some("This account is private; results limited to what your account can see".to_string())
+    } else if account["limited"].as_bool() == Some(true) {
+        Some(
+            "This account is limited by the instance's moderators; some posts may be hidden"
+                .to_string(),
+        )
+    } else {
+        None
+    }
+}
+
+/// Attach the restricted-account note to a user search. A private
+/// account's timeline may refuse the request outright, so a failure
+/// carries the note too.
+pub fn with_account_notice(
+    outcome: Result<SearchOutcome>,
+    notice: Option<String>,
+) -> Result<SearchOutcome> {
+    let Some(notice) = notice else {
+        return outcome;
+    };
+    match outcome {
+        Ok(mut outcome) => {
+            outcome.notice = Some(notice);
+            Ok(outcome)
+        }
+        Err(err) => Err(anyhow::anyhow!("{} ({})", notice, err)),
+    }
+}
+
+/// Hashtag search for platforms whose API takes one tag per request. With
+/// `Any`, every tag is searched in turn and the posts merged; with `All`,
+/// only the first. Posts are then checked against the whole query on this
+/// side, so excluded tags and `All` hold everywhere. Count-only stubs carry
+/// no text to check and are all kept.
+pub async fn search_tags_separately<F, Fut>(
+    query: &TagQuery,
+    combination: TagCombination,
+    count_only: bool,
+    mut search_tag: F,
+) -> Result<SearchOutcome>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<SearchOutcome>>,
+{
+    let searched = match combination {
+        TagCombination::Any => query.tags.len(),
+        TagCombination::All => query.tags.len().min(1),
+    };
+    if searched == 0 {
+        return Err(anyhow::anyhow!("Enter at least one hashtag to search"));
+    }
+
+    let mut merged = SearchOutcome::default();
+    let mut ids = HashSet::new();
+    for (index, tag) in query.tags.iter().take(searched).enumerate() {
+        let outcome = match search_tag(tag.clone()).await {
+            Ok(outcome) => outcome,
+            Err(err) if index == 0 => return Err(err),
+            Err(err) => {
+                log::warn!("Search for #{} failed: {}", tag, err);
+                merged
+                    .stopped_early
+                    .get_or_insert_with(|| format!("#{}: {}", tag, err));
+                continue;
+            }
+        };
+        merged.stats.pages += outcome.stats.pages;
+        merged.stats.seen += outcome.stats.seen;
+        if merged.stopped_early.is_none() {
+            merged.stopped_early = outcome.stopped_early;
+        }
+        merged.notice = merged.notice.or(outcome.notice);
+        merged.results.extend(
+            outcome
+                .results
+                .into_iter()
+                .filter(|result| ids.insert(result.id.clone())),
+        );
+    }
+
+    if !count_only {
+        merged
+            .results
+            .retain(|result| query.keeps(result, combination));
+    }
+    Ok(merged)
+}
@@ -1,4 +1,4 @@
-use crate::models::AppSettings;
+use crate::models::{AppSettings, LogLevel};
 use anyhow::Result;
 
 pub struct SettingsService;
@@ -16,6 +16,16 @@ impl SettingsService {
         }
     }
 
+    /// Read settings without an async runtime, for use before the app launches
+    pub fn load_settings_blocking() -> Option<AppSettings> {
+        confy::load("pixelfed-rust", "settings").ok()
+    }
+
+    /// Change the log verbosity of the running app
+    pub fn apply_log_level(level: LogLevel) {
+        log::set_max_level(level.to_filter());
+    }
+
     pub async fn save_settings(settings: &AppSettings) -> Result<()> {
         confy::store("pixelfed-rust", "settings", settings)
             .map_err(|e| anyhow::anyhow!("Failed to save settings: {}", e))
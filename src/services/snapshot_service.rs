@@ -0,0 +1,82 @@
+// ============================================================================
+// Snapshot Service - Result ids from the previous run of a search
+// ============================================================================
+// Stores the post ids returned for each (query, platform) so a later run of
+// the same search can highlight what is new since last time.
+// ============================================================================
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Platform, PlatformSearchResults, SearchContext};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SearchSnapshot {
+    saved_at: Option<DateTime<Utc>>,
+    platforms: HashMap<Platform, HashSet<String>>,
+}
+
+pub struct SnapshotService;
+
+impl SnapshotService {
+    /// Mark results that were not returned by the previous run of this search
+    /// and record the current ids for next time.
+    ///
+    /// On the first run of a search nothing is marked new.
+    pub fn apply_and_store(context: &SearchContext, groups: &mut [PlatformSearchResults]) {
+        let path = match Self::snapshot_path(context) {
+            Some(path) => path,
+            None => return,
+        };
+
+        let mut snapshot = Self::load(&path);
+
+        for group in groups.iter_mut().filter(|group| group.is_success()) {
+            let current: HashSet<String> = group
+                .results
+                .iter()
+                .map(|result| result.id.clone())
+                .collect();
+
+            if let Some(previous) = snapshot.platforms.get(&group.platform) {
+                group.new_ids = current.difference(previous).cloned().collect();
+            }
+
+            snapshot.platforms.insert(group.platform, current);
+        }
+
+        snapshot.saved_at = Some(Utc::now());
+        if let Err(err) = Self::store(&path, &snapshot) {
+            log::warn!("Failed to save search snapshot {}: {}", path.display(), err);
+        }
+    }
+
+    fn snapshot_path(context: &SearchContext) -> Option<PathBuf> {
+        let dir = dirs::data_dir()?.join("pixelfed-rust").join("snapshots");
+        Some(dir.join(format!(
+            "{}-{}.json",
+            context.search_type.slug(),
+            context.get_folder_name()
+        )))
+    }
+
+    fn load(path: &Path) -> SearchSnapshot {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    fn store(path: &Path, snapshot: &SearchSnapshot) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(snapshot)?)?;
+        Ok(())
+    }
+}
@@ -1 +1,485 @@
 // Utility functions
+
+use std::borrow::Cow;
+use std::ffi::OsStr;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local, NaiveDateTime, Utc};
+
+use crate::models::{DateDisplay, SearchResult};
+
+/// Put `text` on the system clipboard
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    let mut clipboard = arboard::Clipboard::new()
+        .map_err(|err| anyhow::anyhow!("Clipboard unavailable: {}", err))?;
+    clipboard
+        .set_text(text.to_string())
+        .map_err(|err| anyhow::anyhow!("Failed to copy to clipboard: {}", err))?;
+    Ok(())
+}
+
+/// Open `url` in the system's default browser
+pub fn open_browser(url: &str) -> Result<(), String> {
+    open_with_system(OsStr::new(url), false).map_err(|e| format!("Failed to open browser: {}", e))
+}
+
+/// Show a local folder in the system file manager
+pub fn open_folder(path: &Path) -> Result<()> {
+    open_with_system(path.as_os_str(), true)
+        .map_err(|err| anyhow::anyhow!("Failed to open {}: {}", path.display(), err))
+}
+
+fn open_with_system(target: &OsStr, is_path: bool) -> std::io::Result<()> {
+    #[cfg(target_os = "windows")]
+    {
+        if is_path {
+            Command::new("explorer").arg(target).spawn()?;
+        } else {
+            Command::new("rundll32")
+                .arg("url.dll,FileProtocolHandler")
+                .arg(target)
+                .spawn()?;
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        Command::new("open").arg(target).spawn()?;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        Command::new("xdg-open").arg(target).spawn()?;
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    let _ = is_path;
+
+    Ok(())
+}
+
+/// Turn an id, handle or query into a single safe file or folder name.
+///
+/// Keeps letters, digits, `.`, `-`, `_` and `+`; everything else (the `/`
+/// and `:` of AT-URIs and DIDs, `@`, spaces) becomes `_`. Runs of `_` are
+/// collapsed, leading/trailing dots are dropped and the result is capped so
+/// it stays well inside path length limits.
+pub fn sanitize_path_component(value: &str) -> String {
+    const MAX_LEN: usize = 100;
+    const RESERVED: [&str; 22] = [
+        "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+        "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+    ];
+
+    let mut safe = String::with_capacity(value.len());
+    for c in value.chars() {
+        let c = if c.is_alphanumeric() || matches!(c, '.' | '-' | '_' | '+') {
+            c
+        } else {
+            '_'
+        };
+        if c == '_' && safe.ends_with('_') {
+            continue;
+        }
+        safe.push(c);
+    }
+
+    let mut safe: String = safe
+        .trim_matches(|c| c == '_' || c == '.')
+        .chars()
+        .take(MAX_LEN)
+        .collect();
+    if safe.is_empty() {
+        safe.push('_');
+    }
+    // Windows refuses device names even with an extension, e.g. "nul.jpg"
+    let stem_len = safe.find('.').unwrap_or(safe.len());
+    if RESERVED
+        .iter()
+        .any(|name| safe[..stem_len].eq_ignore_ascii_case(name))
+    {
+        safe.insert(stem_len, '_');
+    }
+    safe
+}
+
+/// Human-readable byte count, e.g. "12.3 MB"
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// A post date in the user's timezone, or its age for relative display
+pub fn format_post_date(date: &DateTime<Utc>, display: DateDisplay) -> String {
+    match display {
+        DateDisplay::Absolute => date
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string(),
+        DateDisplay::Relative => format_age(Utc::now().signed_duration_since(*date)),
+    }
+}
+
+/// A short duration for diagnostics, e.g. "850 ms" or "12.4 s"
+pub fn format_duration_ms(ms: u64) -> String {
+    if ms < 1000 {
+        format!("{} ms", ms)
+    } else {
+        format!("{:.1} s", ms as f64 / 1000.0)
+    }
+}
+
+/// Longest post text kept in memory. Real posts are far shorter; this only
+/// bounds what a hostile or broken server can make the app hold.
+pub const MAX_CONTENT_CHARS: usize = 1_000_000;
+
+/// Cap captured post text at [`MAX_CONTENT_CHARS`]
+pub fn cap_content(content: String) -> String {
+    match truncate_text(&content, MAX_CONTENT_CHARS) {
+        Cow::Borrowed(_) => content,
+        Cow::Owned(capped) => capped,
+    }
+}
+
+/// `text` cut to `max_chars` characters with a note saying how much was
+/// dropped; unchanged when it fits
+pub fn truncate_text(text: &str, max_chars: usize) -> Cow<'_, str> {
+    let Some((cut, _)) = text.char_indices().nth(max_chars) else {
+        return Cow::Borrowed(text);
+    };
+    let dropped = text[cut..].chars().count();
+    Cow::Owned(format!(
+        "{}… [truncated, {} more characters]",
+        &text[..cut],
+        dropped
+    ))
+}
+
+/// Exact UTC timestamp for tooltips
+pub fn format_utc_date(date: &DateTime<Utc>) -> String {
+    date.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+}
+
+/// Parse a post timestamp from any platform. RFC 3339 first, then forms
+/// some servers emit instead: an offset without a colon, a space instead
+/// of the `T`, no offset at all (taken as UTC) and RFC 2822. A date up to
+/// a few minutes ahead of the local clock is server clock skew and becomes
+/// now, so the post is not filed in the future.
+pub fn parse_post_date(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    let parsed = DateTime::parse_from_rfc3339(value)
+        .or_else(|_| DateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f%z"))
+        .or_else(|_| DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f%:z"))
+        .or_else(|_| DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f%z"))
+        .or_else(|_| DateTime::parse_from_rfc2822(value))
+        .map(|date| date.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+                .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f"))
+                .map(|date| date.and_utc())
+        });
+
+    let date = match parsed {
+        Ok(date) => date,
+        Err(_) => {
+            log::debug!("Unrecognised post date '{}'", value);
+            return None;
+        }
+    };
+
+    let now = Utc::now();
+    if date > now && date - now <= Duration::minutes(5) {
+        Some(now)
+    } else {
+        Some(date)
+    }
+}
+
+/// A post as a Markdown snippet for write-ups: linked author and date, the
+/// text as a quote, then the media as images (videos and link cards as links)
+pub fn result_markdown(result: &SearchResult) -> String {
+    let mut markdown = format!(
+        "[{}]({}) — {}",
+        result.author,
+        result.url,
+        format_utc_date(&result.created_at)
+    );
+
+    let mut quote = String::new();
+    if let Some(warning) = &result.content_warning {
+        quote.push_str(&format!("> **CW: {}**\n>\n", warning));
+    }
+    for line in result.content.lines().map(str::trim_end) {
+        if line.is_empty() {
+            quote.push_str(">\n");
+        } else {
+            quote.push_str(&format!("> {}\n", line));
+        }
+    }
+    if !quote.is_empty() {
+        markdown.push_str("\n\n");
+        markdown.push_str(quote.trim_end());
+    }
+
+    if !result.media.is_empty() {
+        markdown.push('\n');
+        for (index, item) in result.media.iter().enumerate() {
+            let url = &item.url;
+            if item.is_video() {
+                markdown.push_str(&format!("\n[video {}]({})", index + 1, url));
+            } else if item.is_link() {
+                markdown.push_str(&format!("\n[link]({})", url));
+            } else {
+                let alt = item
+                    .description
+                    .clone()
+                    .unwrap_or_else(|| format!("media {}", index + 1));
+                markdown.push_str(&format!("\n![{}]({})", alt.replace(['[', ']'], ""), url));
+            }
+        }
+    }
+    markdown
+}
+
+fn format_age(age: chrono::Duration) -> String {
+    let plural = |count: i64, unit: &str| {
+        if count == 1 {
+            format!("1 {} ago", unit)
+        } else {
+            format!("{} {}s ago", count, unit)
+        }
+    };
+    // Small clock skew can put a fresh post slightly in the future
+    let seconds = age.num_seconds().max(0);
+    match seconds {
+        0..=59 => "just now".to_string(),
+        60..=3_599 => plural(seconds / 60, "minute"),
+        3_600..=86_399 => plural(seconds / 3_600, "hour"),
+        86_400..=2_591_999 => plural(seconds / 86_400, "day"),
+        2_592_000..=31_535_999 => plural(seconds / 2_592_000, "month"),
+        _ => plural(seconds / 31_536_000, "year"),
+    }
+}
+
+/// True when dotted version `running` is later than `seen`, e.g. "0.2.0"
+/// over "0.1.3". Pre-release and build suffixes are ignored; an empty or
+/// unreadable `seen` counts as older than anything.
+pub fn is_newer_version(running: &str, seen: &str) -> bool {
+    fn parts(version: &str) -> Option<Vec<u64>> {
+        version
+            .trim()
+            .trim_start_matches('v')
+            .split(['-', '+'])
+            .next()?
+            .split('.')
+            .map(|part| part.parse().ok())
+            .collect()
+    }
+
+    match (parts(running), parts(seen)) {
+        (Some(running), Some(seen)) => running > seen,
+        (Some(_), None) => true,
+        (None, _) => false,
+    }
+}
+
+/// Parse `#rrggbb` or `#rgb` into RGB components
+pub fn parse_hex_color(value: &str) -> Option<(u8, u8, u8)> {
+    let hex = value.trim().strip_prefix('#')?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    match hex.len() {
+        6 => Some((
+            u8::from_str_radix(&hex[0..2], 16).ok()?,
+            u8::from_str_radix(&hex[2..4], 16).ok()?,
+            u8::from_str_radix(&hex[4..6], 16).ok()?,
+        )),
+        3 => {
+            let channel = |index: usize| u8::from_str_radix(&hex[index..index + 1], 16).ok();
+            Some((channel(0)? * 17, channel(1)? * 17, channel(2)? * 17))
+        }
+        _ => None,
+    }
+}
+
+pub fn to_hex_color((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// WCAG relative luminance of an sRGB color
+fn relative_luminance((r, g, b): (u8, u8, u8)) -> f64 {
+    let linear = |channel: u8| {
+        let value = channel as f64 / 255.0;
+        if value <= 0.03928 {
+            value / 12.92
+        } else {
+            ((value + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+/// WCAG contrast ratio between two colors, from 1.0 to 21.0
+pub fn contrast_ratio(a: (u8, u8, u8), b: (u8, u8, u8)) -> f64 {
+    let (lighter, darker) = {
+        let (la, lb) = (relative_luminance(a), relative_luminance(b));
+        if la > lb {
+            (la, lb)
+        } else {
+            (lb, la)
+        }
+    };
+    (lighter + 0.05) / (darker + 0.05)
+}
+
+/// Darken (on light backgrounds) or lighten (on dark ones) `color` in small
+/// steps until it reaches `target` contrast against `background`
+pub fn adjust_for_contrast(
+    color: (u8, u8, u8),
+    background: (u8, u8, u8),
+    target: f64,
+) -> (u8, u8, u8) {
+    let toward = if relative_luminance(background) > 0.5 {
+        0.0
+    } else {
+        255.0
+    };
+    let mix = |channel: u8, amount: f64| {
+        (channel as f64 + (toward - channel as f64) * amount).round() as u8
+    };
+
+    for step in 1..=20 {
+        let amount = step as f64 * 0.05;
+        let candidate = (
+            mix(color.0, amount),
+            mix(color.1, amount),
+            mix(color.2, amount),
+        );
+        if contrast_ratio(candidate, background) >= target {
+            return candidate;
+        }
+    }
+
+    (toward as u8, toward as u8, toward as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn noon_may_first() -> DateTime<Utc> {
+        use chrono::TimeZone;
+        Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn parse_post_date_reads_rfc3339_and_fallback_formats() {
+        for value in [
+            "2024-05-01T12:00:00Z",
+            "2024-05-01T12:00:00.000Z",
+            "2024-05-01T14:00:00+02:00",
+            // Offset without a colon
+            "2024-05-01T14:00:00+0200",
+            "2024-05-01T12:00:00.000+0000",
+            // Space instead of the T
+            "2024-05-01 12:00:00+00:00",
+            "2024-05-01 07:00:00-0500",
+            // No offset: taken as UTC
+            "2024-05-01T12:00:00",
+            "2024-05-01 12:00:00",
+            "2024-05-01 12:00:00.000",
+            // RFC 2822
+            "Wed, 01 May 2024 12:00:00 +0000",
+            "Wed, 01 May 2024 12:00:00 GMT",
+            "  2024-05-01T12:00:00Z  ",
+        ] {
+            assert_eq!(parse_post_date(value), Some(noon_may_first()), "{value:?}");
+        }
+    }
+
+    #[test]
+    fn parse_post_date_rejects_garbage() {
+        for value in ["", "   ", "yesterday", "2024-13-01T00:00:00Z", "1714564800"] {
+            assert_eq!(parse_post_date(value), None, "{value:?}");
+        }
+    }
+
+    #[test]
+    fn parse_post_date_clamps_small_future_skew_to_now() {
+        let before = Utc::now();
+        let skewed = (before + Duration::minutes(2)).to_rfc3339();
+        let parsed = parse_post_date(&skewed).unwrap();
+        assert!(parsed >= before && parsed <= Utc::now(), "{parsed}");
+
+        // Further ahead is not skew; the date is kept as sent
+        let ahead = before + Duration::days(1);
+        assert_eq!(
+            parse_post_date(&ahead.to_rfc3339()).map(|date| date.timestamp()),
+            Some(ahead.timestamp())
+        );
+    }
+
+    #[test]
+    fn sanitize_path_component_flattens_at_uris_and_handles() {
+        assert_eq!(
+            sanitize_path_component("at://did:plc:abc123/app.bsky.feed.post/3kxyz"),
+            "at_did_plc_abc123_app.bsky.feed.post_3kxyz"
+        );
+        assert_eq!(
+            sanitize_path_component("@alice.bsky.social"),
+            "alice.bsky.social"
+        );
+        assert_eq!(
+            sanitize_path_component("alice@mastodon.social"),
+            "alice_mastodon.social"
+        );
+        assert_eq!(
+            sanitize_path_component("did:web:example.com"),
+            "did_web_example.com"
+        );
+    }
+
+    #[test]
+    fn sanitize_path_component_never_climbs_out() {
+        assert_eq!(sanitize_path_component(".."), "_");
+        assert_eq!(sanitize_path_component("."), "_");
+        assert_eq!(sanitize_path_component("../etc/passwd"), "etc_passwd");
+        assert_eq!(sanitize_path_component("..\\..\\boot.ini"), "boot.ini");
+        assert_eq!(sanitize_path_component(""), "_");
+    }
+
+    #[test]
+    fn sanitize_path_component_avoids_windows_device_names() {
+        assert_eq!(sanitize_path_component("CON"), "CON_");
+        assert_eq!(sanitize_path_component("nul.jpg"), "nul_.jpg");
+        assert_eq!(sanitize_path_component("Lpt1"), "Lpt1_");
+        assert_eq!(sanitize_path_component("COM10"), "COM10");
+        assert_eq!(sanitize_path_component("console"), "console");
+    }
+
+    #[test]
+    fn sanitize_path_component_caps_length() {
+        assert_eq!(sanitize_path_component(&"a".repeat(300)).len(), 100);
+        // Counted in characters, so multi-byte names are not split mid-char
+        let long = sanitize_path_component(&"é".repeat(300));
+        assert_eq!(long.chars().count(), 100);
+    }
+}